@@ -326,6 +326,7 @@ impl FollowableItem for Editor {
                 }
                 _ => false,
             },
+            _ => false,
         }
     }
 
@@ -336,7 +337,9 @@ impl FollowableItem for Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        let update_view::Variant::Editor(message) = message;
+        let update_view::Variant::Editor(message) = message else {
+            return Task::ready(Ok(()));
+        };
         let project = project.clone();
         cx.spawn_in(window, async move |this, cx| {
             update_editor_from_message(this, project, message, cx).await
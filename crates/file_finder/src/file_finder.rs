@@ -16,8 +16,8 @@ use file_icons::FileIcons;
 use fuzzy::{CharBag, PathMatch, PathMatchCandidate};
 use gpui::{
     Action, AnyElement, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
-    KeyContext, Modifiers, ModifiersChangedEvent, ParentElement, Render, Styled, Task, WeakEntity,
-    Window, actions, rems,
+    KeyContext, Modifiers, ModifiersChangedEvent, ObjectFit, ParentElement, Render, Styled, Task,
+    WeakEntity, Window, actions, img, rems,
 };
 use open_path_prompt::OpenPathPrompt;
 use picker::{Picker, PickerDelegate};
@@ -31,6 +31,7 @@ use std::{
     cmp,
     ops::Range,
     path::{Component, Path, PathBuf},
+    rc::Rc,
     sync::{
         Arc,
         atomic::{self, AtomicBool},
@@ -38,8 +39,9 @@ use std::{
 };
 use text::Point;
 use ui::{
-    ButtonLike, ContextMenu, HighlightedLabel, Indicator, KeyBinding, ListItem, ListItemSpacing,
-    PopoverMenu, PopoverMenuHandle, TintColor, Tooltip, prelude::*,
+    ButtonLike, ContextMenu, DocumentationAside, DocumentationEdge, DocumentationSide,
+    HighlightedLabel, Indicator, KeyBinding, ListItem, ListItemSpacing, PopoverMenu,
+    PopoverMenuHandle, TintColor, Tooltip, prelude::*,
 };
 use util::{
     ResultExt, maybe,
@@ -802,6 +804,9 @@ impl FoundPath {
 
 const MAX_RECENT_SELECTIONS: usize = 20;
 
+/// The size of the thumbnail shown next to the finder when an SVG is selected.
+const SVG_THUMBNAIL_SIZE: f32 = 128.;
+
 pub enum Event {
     Selected(ProjectPath),
     Dismissed,
@@ -1329,6 +1334,39 @@ impl PickerDelegate for FileFinderDelegate {
         cx.notify();
     }
 
+    fn documentation_aside(
+        &self,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<DocumentationAside> {
+        // Shows a thumbnail of the selected SVG, so that icons can be told apart without opening
+        // them.
+        if !self.project.read(cx).is_local() {
+            return None;
+        }
+        let abs_path = self
+            .matches
+            .get(self.selected_index)?
+            .abs_path(&self.project, cx)?;
+        if !abs_path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("svg"))
+        {
+            return None;
+        }
+
+        Some(DocumentationAside::new(
+            DocumentationSide::Right,
+            DocumentationEdge::Top,
+            Rc::new(move |_| {
+                img(abs_path.clone())
+                    .size(px(SVG_THUMBNAIL_SIZE))
+                    .object_fit(ObjectFit::Contain)
+                    .into_any_element()
+            }),
+        ))
+    }
+
     fn separators_after_indices(&self) -> Vec<usize> {
         if self.separate_history {
             let first_non_history_index = self
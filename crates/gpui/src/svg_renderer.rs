@@ -1,5 +1,5 @@
 use crate::{
-    AssetSource, DevicePixels, IsZero, RenderImage, Result, SharedString, Size,
+    AssetSource, DevicePixels, IsZero, Point, RenderImage, Result, Rgba, SharedString, Size,
     swap_rgba_pa_to_bgra,
 };
 use image::Frame;
@@ -13,6 +13,19 @@ use std::{
 /// When rendering SVGs, we render them at twice the size to get a higher-quality result.
 pub const SMOOTH_SVG_SCALE_FACTOR: f32 = 2.;
 
+/// How [`SvgRenderer::render_raster`] encodes the rendered image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RasterEncoding {
+    Png,
+    /// A JPEG of the given quality, from 1 to 100. Transparent areas are composited over white,
+    /// since JPEGs have no alpha channel.
+    Jpeg {
+        quality: u8,
+    },
+    /// A lossless WebP.
+    WebP,
+}
+
 #[derive(Clone, PartialEq, Hash, Eq)]
 pub(crate) struct RenderSvgParams {
     pub(crate) path: SharedString,
@@ -37,30 +50,9 @@ pub enum SvgSize {
 impl SvgRenderer {
     /// Creates a new SVG renderer with the provided asset source.
     pub fn new(asset_source: Arc<dyn AssetSource>) -> Self {
-        static FONT_DB: LazyLock<Arc<usvg::fontdb::Database>> = LazyLock::new(|| {
-            let mut db = usvg::fontdb::Database::new();
-            db.load_system_fonts();
-            Arc::new(db)
-        });
-        let default_font_resolver = usvg::FontResolver::default_font_selector();
-        let font_resolver = Box::new(
-            move |font: &usvg::Font, db: &mut Arc<usvg::fontdb::Database>| {
-                if db.is_empty() {
-                    *db = FONT_DB.clone();
-                }
-                default_font_resolver(font, db)
-            },
-        );
-        let options = usvg::Options {
-            font_resolver: usvg::FontResolver {
-                select_font: font_resolver,
-                select_fallback: usvg::FontResolver::default_fallback_selector(),
-            },
-            ..Default::default()
-        };
         Self {
             asset_source,
-            usvg_options: Arc::new(options),
+            usvg_options: Arc::new(default_usvg_options()),
         }
     }
 
@@ -76,22 +68,138 @@ impl SvgRenderer {
             SvgSize::ScaleFactor(scale_factor * SMOOTH_SVG_SCALE_FACTOR),
         )
         .map(|pixmap| {
-            let mut buffer =
-                image::ImageBuffer::from_raw(pixmap.width(), pixmap.height(), pixmap.take())
-                    .unwrap();
-
-            if to_brga {
-                for pixel in buffer.chunks_exact_mut(4) {
-                    swap_rgba_pa_to_bgra(pixel);
-                }
-            }
-
-            let mut image = RenderImage::new(SmallVec::from_const([Frame::new(buffer)]));
+            let mut image = pixmap_to_image(pixmap, to_brga);
             image.scale_factor = SMOOTH_SVG_SCALE_FACTOR;
             Arc::new(image)
         })
     }
 
+    /// The options used to parse SVGs, which resolve fonts against the system font database.
+    pub fn usvg_options(&self) -> &usvg::Options<'static> {
+        &self.usvg_options
+    }
+
+    /// Returns options like [`Self::usvg_options`] which load the images referenced by `<image>`
+    /// elements, other than data URLs, with `resolve_href`.
+    pub fn usvg_options_with_href_resolver(
+        &self,
+        resolve_href: usvg::ImageHrefStringResolverFn<'static>,
+    ) -> usvg::Options<'static> {
+        usvg::Options {
+            image_href_resolver: usvg::ImageHrefResolver {
+                resolve_data: usvg::ImageHrefResolver::default_data_resolver(),
+                resolve_string: resolve_href,
+            },
+            ..default_usvg_options()
+        }
+    }
+
+    /// The database of system fonts that text in SVGs is rendered with by default.
+    pub fn system_fonts(&self) -> Arc<usvg::fontdb::Database> {
+        FONT_DB.clone()
+    }
+
+    /// Renders the region of an already parsed SVG that starts at `origin`, in SVG pixels, into
+    /// an image of `size` device pixels, scaling the SVG by `scale_factor`.
+    pub fn render_region(
+        &self,
+        tree: &usvg::Tree,
+        origin: Point<f32>,
+        size: Size<DevicePixels>,
+        scale_factor: f32,
+        to_brga: bool,
+    ) -> Result<Arc<RenderImage>, usvg::Error> {
+        let mut pixmap = Pixmap::new(size.width.0 as u32, size.height.0 as u32)
+            .ok_or(usvg::Error::InvalidSize)?;
+        let transform = resvg::tiny_skia::Transform::from_scale(scale_factor, scale_factor)
+            .pre_translate(-origin.x, -origin.y);
+        resvg::render(tree, transform, &mut pixmap.as_mut());
+        Ok(Arc::new(pixmap_to_image(pixmap, to_brga)))
+    }
+
+    /// Renders an already parsed SVG stretched to `size` device pixels over `background`, or a
+    /// transparent background if it's `None`, encoded as a PNG.
+    pub fn render_png(
+        &self,
+        tree: &usvg::Tree,
+        size: Size<DevicePixels>,
+        background: Option<Rgba>,
+    ) -> Result<Vec<u8>> {
+        self.render_raster(tree, size, background, RasterEncoding::Png)
+    }
+
+    /// Renders an already parsed SVG stretched to `size` device pixels over `background`, or a
+    /// transparent background if it's `None`, encoded with `encoding`.
+    pub fn render_raster(
+        &self,
+        tree: &usvg::Tree,
+        size: Size<DevicePixels>,
+        background: Option<Rgba>,
+        encoding: RasterEncoding,
+    ) -> Result<Vec<u8>> {
+        let transform = resvg::tiny_skia::Transform::from_scale(
+            size.width.0 as f32 / tree.size().width(),
+            size.height.0 as f32 / tree.size().height(),
+        );
+        render_raster_with_transform(tree, size, transform, background, encoding)
+    }
+
+    /// Renders the region of an already parsed SVG starting at `origin`, in SVG pixels, into an
+    /// image of `size` device pixels at `scale_factor` device pixels per SVG pixel, over
+    /// `background`, or a transparent background if it's `None`, encoded with `encoding`.
+    pub fn render_raster_region(
+        &self,
+        tree: &usvg::Tree,
+        origin: Point<f32>,
+        size: Size<DevicePixels>,
+        scale_factor: f32,
+        background: Option<Rgba>,
+        encoding: RasterEncoding,
+    ) -> Result<Vec<u8>> {
+        let transform = resvg::tiny_skia::Transform::from_scale(scale_factor, scale_factor)
+            .pre_translate(-origin.x, -origin.y);
+        render_raster_with_transform(tree, size, transform, background, encoding)
+    }
+
+    /// Returns the color of the pixel of an already parsed SVG at `position`, in SVG pixels, when
+    /// it's rendered at `scale_factor` device pixels per SVG pixel over a transparent background.
+    pub fn render_pixel(
+        &self,
+        tree: &usvg::Tree,
+        position: Point<f32>,
+        scale_factor: f32,
+    ) -> Result<Rgba, usvg::Error> {
+        let mut pixmap = Pixmap::new(1, 1).ok_or(usvg::Error::InvalidSize)?;
+        let transform = resvg::tiny_skia::Transform::from_scale(scale_factor, scale_factor)
+            .pre_translate(-position.x, -position.y);
+        resvg::render(tree, transform, &mut pixmap.as_mut());
+        let color = pixmap.pixels()[0].demultiply();
+        let channel = |value: u8| value as f32 / 255.;
+        Ok(Rgba {
+            r: channel(color.red()),
+            g: channel(color.green()),
+            b: channel(color.blue()),
+            a: channel(color.alpha()),
+        })
+    }
+
+    /// Renders an already parsed SVG scaled to fit within `size` device pixels while preserving
+    /// its aspect ratio, centered and encoded as a PNG.
+    pub fn render_png_contained(
+        &self,
+        tree: &usvg::Tree,
+        size: Size<DevicePixels>,
+    ) -> Result<Vec<u8>> {
+        let (width, height) = (size.width.0 as f32, size.height.0 as f32);
+        let scale = (width / tree.size().width()).min(height / tree.size().height());
+        let transform = resvg::tiny_skia::Transform::from_translate(
+            (width - tree.size().width() * scale) / 2.,
+            (height - tree.size().height() * scale) / 2.,
+        )
+        .pre_scale(scale, scale);
+        render_raster_with_transform(tree, size, transform, None, RasterEncoding::Png)
+    }
+
     pub(crate) fn render_alpha_mask(
         &self,
         params: &RenderSvgParams,
@@ -147,3 +255,110 @@ impl SvgRenderer {
         Ok(pixmap)
     }
 }
+
+static FONT_DB: LazyLock<Arc<usvg::fontdb::Database>> = LazyLock::new(|| {
+    let mut db = usvg::fontdb::Database::new();
+    db.load_system_fonts();
+    Arc::new(db)
+});
+
+fn default_usvg_options() -> usvg::Options<'static> {
+    let default_font_resolver = usvg::FontResolver::default_font_selector();
+    let font_resolver = Box::new(
+        move |font: &usvg::Font, db: &mut Arc<usvg::fontdb::Database>| {
+            if db.is_empty() {
+                *db = FONT_DB.clone();
+            }
+            default_font_resolver(font, db)
+        },
+    );
+    usvg::Options {
+        font_resolver: usvg::FontResolver {
+            select_font: font_resolver,
+            select_fallback: usvg::FontResolver::default_fallback_selector(),
+        },
+        ..Default::default()
+    }
+}
+
+fn render_raster_with_transform(
+    tree: &usvg::Tree,
+    size: Size<DevicePixels>,
+    transform: resvg::tiny_skia::Transform,
+    background: Option<Rgba>,
+    encoding: RasterEncoding,
+) -> Result<Vec<u8>> {
+    let mut pixmap =
+        Pixmap::new(size.width.0 as u32, size.height.0 as u32).ok_or(usvg::Error::InvalidSize)?;
+    if let Some(background) = background {
+        pixmap.fill(
+            resvg::tiny_skia::Color::from_rgba(
+                background.r,
+                background.g,
+                background.b,
+                background.a,
+            )
+            .unwrap_or(resvg::tiny_skia::Color::TRANSPARENT),
+        );
+    }
+    resvg::render(tree, transform, &mut pixmap.as_mut());
+
+    let mut encoded = Vec::new();
+    let mut writer = std::io::Cursor::new(&mut encoded);
+    match encoding {
+        RasterEncoding::Png | RasterEncoding::WebP => {
+            // PNGs and WebPs store colors with straight alpha, whereas the pixmap's colors are
+            // premultiplied.
+            let pixels = pixmap
+                .pixels()
+                .iter()
+                .flat_map(|pixel| {
+                    let color = pixel.demultiply();
+                    [color.red(), color.green(), color.blue(), color.alpha()]
+                })
+                .collect();
+            let image = image::RgbaImage::from_raw(pixmap.width(), pixmap.height(), pixels)
+                .ok_or(usvg::Error::InvalidSize)?;
+            let format = if encoding == RasterEncoding::Png {
+                image::ImageFormat::Png
+            } else {
+                image::ImageFormat::WebP
+            };
+            image.write_to(&mut writer, format)?;
+        }
+        RasterEncoding::Jpeg { quality } => {
+            // JPEGs have no alpha channel, so the image is composited over white, which for
+            // premultiplied colors amounts to adding the transparency to each component.
+            let pixels = pixmap
+                .pixels()
+                .iter()
+                .flat_map(|pixel| {
+                    let transparency = u8::MAX - pixel.alpha();
+                    [
+                        pixel.red() + transparency,
+                        pixel.green() + transparency,
+                        pixel.blue() + transparency,
+                    ]
+                })
+                .collect();
+            let image = image::RgbImage::from_raw(pixmap.width(), pixmap.height(), pixels)
+                .ok_or(usvg::Error::InvalidSize)?;
+            image::codecs::jpeg::JpegEncoder::new_with_quality(&mut writer, quality.clamp(1, 100))
+                .encode_image(&image)?;
+        }
+    }
+    Ok(encoded)
+}
+
+fn pixmap_to_image(pixmap: Pixmap, to_brga: bool) -> RenderImage {
+    let mut buffer =
+        image::ImageBuffer::from_raw(pixmap.width(), pixmap.height(), pixmap.take()).unwrap();
+
+    if to_brga {
+        for pixel in buffer.chunks_exact_mut(4) {
+            swap_rgba_pa_to_bgra(pixel);
+        }
+    }
+
+    RenderImage::new(SmallVec::from_const([Frame::new(buffer)]))
+}
@@ -11587,6 +11587,39 @@ impl LspStore {
         }
     }
 
+    /// Registers a source of diagnostics that isn't a language server, such as a checker built
+    /// into Zed, so that the diagnostics reported under the returned id are attributed to `name`.
+    pub fn register_diagnostic_source(
+        &mut self,
+        name: LanguageServerName,
+        cx: &mut Context<Self>,
+    ) -> LanguageServerId {
+        let id = self.languages.next_language_server_id();
+        self.language_server_statuses.insert(
+            id,
+            LanguageServerStatus {
+                name: name.clone(),
+                pending_work: Default::default(),
+                has_pending_diagnostic_updates: false,
+                progress_tokens: Default::default(),
+                worktree: None,
+                binary: None,
+                configuration: None,
+                workspace_folders: BTreeSet::new(),
+            },
+        );
+        cx.emit(LspStoreEvent::LanguageServerAdded(id, name, None));
+        id
+    }
+
+    /// Unregisters a source of diagnostics that was registered with
+    /// [`Self::register_diagnostic_source`], once it has cleared its diagnostics.
+    pub fn unregister_diagnostic_source(&mut self, id: LanguageServerId, cx: &mut Context<Self>) {
+        if self.language_server_statuses.remove(&id).is_some() {
+            cx.emit(LspStoreEvent::LanguageServerRemoved(id));
+        }
+    }
+
     pub(crate) fn supplementary_language_servers(
         &self,
     ) -> impl '_ + Iterator<Item = (LanguageServerId, LanguageServerName)> {
@@ -22,9 +22,9 @@ use gpui::{
     Action, AnyElement, App, AsyncWindowContext, Bounds, ClipboardItem, Context, CursorStyle,
     DismissEvent, Div, DragMoveEvent, Entity, EventEmitter, ExternalPaths, FocusHandle, Focusable,
     Hsla, InteractiveElement, KeyContext, ListHorizontalSizingBehavior, ListSizingBehavior,
-    Modifiers, ModifiersChangedEvent, MouseButton, MouseDownEvent, ParentElement, Pixels, Point,
-    PromptLevel, Render, ScrollStrategy, Stateful, Styled, Subscription, Task,
-    UniformListScrollHandle, WeakEntity, Window, actions, anchored, deferred, div, hsla,
+    Modifiers, ModifiersChangedEvent, MouseButton, MouseDownEvent, ObjectFit, ParentElement,
+    Pixels, Point, PromptLevel, Render, ScrollStrategy, Stateful, Styled, Subscription, Task,
+    UniformListScrollHandle, WeakEntity, Window, actions, anchored, deferred, div, hsla, img,
     linear_color_stop, linear_gradient, point, px, size, transparent_white, uniform_list,
 };
 use language::DiagnosticSeverity;
@@ -59,7 +59,7 @@ use ui::{
     Color, ContextMenu, DecoratedIcon, Divider, Icon, IconDecoration, IconDecorationKind,
     IndentGuideColors, IndentGuideLayout, KeyBinding, Label, LabelSize, ListItem, ListItemSpacing,
     ScrollAxes, ScrollableHandle, Scrollbars, StickyCandidate, Tooltip, WithScrollbar, prelude::*,
-    v_flex,
+    tooltip_container, v_flex,
 };
 use util::{ResultExt, TakeUntilExt, TryFutureExt, maybe, paths::compare_paths, rel_path::RelPath};
 use workspace::{
@@ -72,6 +72,8 @@ use worktree::CreatedEntry;
 use zed_actions::{project_panel::ToggleFocus, workspace::OpenWithSystem};
 
 const PROJECT_PANEL_KEY: &str = "ProjectPanel";
+/// The size of the thumbnail shown when hovering an SVG file.
+const SVG_THUMBNAIL_SIZE: f32 = 128.;
 const NEW_ENTRY_ID: ProjectEntryId = ProjectEntryId::MAX;
 
 struct VisibleEntriesForWorktree {
@@ -534,6 +536,11 @@ struct SerializedProjectPanel {
     width: Option<Pixels>,
 }
 
+/// A rendered thumbnail of an SVG file, shown when hovering its entry.
+struct SvgThumbnailTooltip {
+    abs_path: PathBuf,
+}
+
 struct DraggedProjectEntryView {
     selection: SelectedEntry,
     icon: Option<SharedString>,
@@ -1077,6 +1084,14 @@ impl ProjectPanel {
                 && (cfg!(target_os = "windows")
                     || (settings.hide_root && visible_worktrees_count == 1));
             let should_show_compare = !is_dir && self.file_abs_paths_to_diff(cx).is_some();
+            let export_directory =
+                (is_dir && is_local).then(|| zed_actions::svg_preview::ExportDirectory {
+                    directory: Some(worktree.absolutize(&entry.path)),
+                });
+            let open_svg_gallery =
+                (is_dir && is_local).then(|| zed_actions::svg_preview::OpenGallery {
+                    directory: Some(worktree.absolutize(&entry.path)),
+                });
 
             let has_git_repo = !is_dir && {
                 let project_path = project::ProjectPath {
@@ -1114,6 +1129,12 @@ impl ProjectPanel {
                                 menu.separator()
                                     .action("Find in Folder…", Box::new(NewSearchInDirectory))
                             })
+                            .when_some(open_svg_gallery, |menu, action| {
+                                menu.action("Open SVG Gallery", Box::new(action))
+                            })
+                            .when_some(export_directory, |menu, action| {
+                                menu.action("Export SVGs to PNG", Box::new(action))
+                            })
                             .when(is_unfoldable, |menu| {
                                 menu.action("Unfold Directory", Box::new(UnfoldDirectory))
                             })
@@ -2954,6 +2975,25 @@ impl ProjectPanel {
         }
     }
 
+    /// The absolute path of the entry if it's an SVG that can be rendered as a thumbnail.
+    fn svg_thumbnail_path(
+        &self,
+        worktree_id: WorktreeId,
+        path: &RelPath,
+        cx: &App,
+    ) -> Option<PathBuf> {
+        let project = self.project.read(cx);
+        if !project.is_local()
+            || !path
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("svg"))
+        {
+            return None;
+        }
+        let worktree = project.worktree_for_id(worktree_id, cx)?;
+        Some(worktree.read(cx).absolutize(path))
+    }
+
     fn file_abs_paths_to_diff(&self, cx: &Context<Self>) -> Option<(PathBuf, PathBuf)> {
         let mut selections_abs_path = self
             .marked_entries
@@ -4462,6 +4502,11 @@ impl ProjectPanel {
         let path = details.path.clone();
         let path_for_external_paths = path.clone();
         let path_for_dragged_selection = path.clone();
+        let svg_thumbnail_path = if kind.is_file() && !show_editor && !is_sticky {
+            self.svg_thumbnail_path(details.worktree_id, &path, cx)
+        } else {
+            None
+        };
 
         let depth = details.depth;
         let worktree_id = details.worktree_id;
@@ -4565,6 +4610,14 @@ impl ProjectPanel {
             .border_r_2()
             .border_color(border_color)
             .hover(|style| style.bg(bg_hover_color).border_color(border_hover_color))
+            .when_some(svg_thumbnail_path, |this, abs_path| {
+                this.tooltip(move |_, cx| {
+                    cx.new(|_| SvgThumbnailTooltip {
+                        abs_path: abs_path.clone(),
+                    })
+                    .into()
+                })
+            })
             .when(is_sticky, |this| {
                 this.block_mouse_except_scroll()
             })
@@ -6089,6 +6142,18 @@ impl Render for ProjectPanel {
     }
 }
 
+impl Render for SvgThumbnailTooltip {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        tooltip_container(cx, |container, _| {
+            container.child(
+                img(self.abs_path.clone())
+                    .size(px(SVG_THUMBNAIL_SIZE))
+                    .object_fit(ObjectFit::Contain),
+            )
+        })
+    }
+}
+
 impl Render for DraggedProjectEntryView {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let ui_font = ThemeSettings::get_global(cx).ui_font.clone();
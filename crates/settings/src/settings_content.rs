@@ -130,6 +130,9 @@ pub struct SettingsContent {
     /// Configuration for the Notification Panel
     pub notification_panel: Option<NotificationPanelSettingsContent>,
 
+    /// The settings for the SVG preview.
+    pub svg_preview: Option<SvgPreviewSettingsContent>,
+
     pub proxy: Option<String>,
 
     /// The URL of the Zed server to connect to.
@@ -884,6 +887,359 @@ pub enum ImageFileSizeUnit {
     Decimal,
 }
 
+/// The settings for the SVG preview.
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, Default, PartialEq)]
+pub struct SvgPreviewSettingsContent {
+    /// What scrolling the mouse wheel over the preview does. Holding the platform's secondary
+    /// modifier (cmd on macOS, ctrl elsewhere) while scrolling does the other.
+    ///
+    /// Default: "zoom"
+    pub scroll_behavior: Option<SvgPreviewScrollBehavior>,
+    /// The color drawn behind the SVG: "theme", "white", "black" or a custom hex color such as
+    /// "#ff8800".
+    ///
+    /// Default: "theme"
+    pub background: Option<SvgPreviewBackground>,
+    /// How long to wait after the last edit before re-rendering the preview, in milliseconds.
+    ///
+    /// Set to 0 to disable debouncing.
+    ///
+    /// Default: 250
+    pub render_debounce_ms: Option<u64>,
+    /// The largest SVG file that is rendered, in megabytes.
+    ///
+    /// Default: 10
+    pub max_file_size_mb: Option<u64>,
+    /// SVG files larger than this, in megabytes, are only rendered after confirming it. Set to 0
+    /// to render them automatically.
+    ///
+    /// Default: 2
+    pub large_file_size_mb: Option<u64>,
+    /// SVGs with more elements than this are only rendered after confirming it. Set to 0 to
+    /// render them automatically.
+    ///
+    /// Default: 50000
+    pub large_element_count: Option<u64>,
+    /// How many megabytes the images rendered by all SVG previews may take up together.
+    ///
+    /// Default: 512
+    pub memory_budget_mb: Option<usize>,
+    /// How new previews are zoomed, unless the SVG was previewed before: "fit" to fit the whole
+    /// SVG into the pane, or "actual_size" for the SVG's intrinsic size.
+    ///
+    /// Default: "fit"
+    pub default_zoom: Option<SvgPreviewDefaultZoom>,
+    /// What the preview actions open: "pinned" for a preview of the active SVG, or "follow" for a
+    /// preview that follows the last active SVG editor.
+    ///
+    /// Default: "pinned"
+    pub default_mode: Option<SvgPreviewDefaultMode>,
+    /// Directories to load fonts from for rendering text, in addition to the system fonts.
+    ///
+    /// Default: []
+    pub font_dirs: Option<Vec<String>>,
+    /// The font family used for text whose fonts aren't available, and for text that doesn't
+    /// specify a font. Uses the renderer's default serif font when unset.
+    ///
+    /// Default: null
+    pub fallback_font_family: Option<FontFamilyName>,
+    /// The color that `currentColor` resolves to, unless the SVG sets its own color: "foreground"
+    /// for the theme's text color, "white", "black" or a custom hex color such as "#ff8800".
+    ///
+    /// Default: "foreground"
+    pub current_color: Option<SvgPreviewCurrentColor>,
+    /// The scales that exporting a PNG set renders the SVG at, producing a file such as
+    /// `icon@2x.png` for each.
+    ///
+    /// Default: [1, 2, 3]
+    pub export_scales: Option<Vec<f32>>,
+    /// Named sets of export options, each exposed as an export command of the preview.
+    ///
+    /// Default: []
+    pub export_presets: Option<Vec<SvgPreviewExportPreset>>,
+    /// How many levels of elements the bounding box overlay outlines: 1 for the top-level
+    /// elements, 2 to include their children, and so on.
+    ///
+    /// Default: 1
+    pub bounding_box_depth: Option<u32>,
+    /// Whether to remove scripts, event handlers and references to resources on the network from
+    /// SVGs before rendering them, for previewing SVGs from untrusted sources.
+    ///
+    /// Default: false
+    pub sandbox: Option<bool>,
+    /// Which editors a following preview follows: "workspace" for the last active SVG editor in
+    /// any pane, or "pane" for the last active one in the pane it was opened from.
+    ///
+    /// Default: "workspace"
+    pub follow_scope: Option<SvgPreviewFollowScope>,
+    /// Whether a following preview clears when an item other than an SVG editor becomes active,
+    /// instead of keeping the last SVG it followed on screen.
+    ///
+    /// Default: false
+    pub clear_when_inactive: Option<bool>,
+    /// Whether to close a preview that isn't following when the last editor of its SVG is closed.
+    ///
+    /// Default: false
+    pub close_with_editor: Option<bool>,
+    /// Whether to open a preview automatically when an SVG file is opened: "off", "same_pane"
+    /// for a tab next to the SVG's editor, or "split_right" for the pane to the right.
+    ///
+    /// Default: "off"
+    pub auto_open: Option<SvgPreviewAutoOpen>,
+    /// Whether new previews show an overlay with how long the last render took to parse and
+    /// rasterize the SVG, the size it was rasterized at and its number of elements.
+    ///
+    /// Default: false
+    pub show_render_stats: Option<bool>,
+    /// Whether files without an `.svg` extension can be previewed when their content starts
+    /// with an `<svg>` element, such as `icon.svg.tmpl` or extensionless exports.
+    ///
+    /// Default: false
+    pub detect_by_content: Option<bool>,
+    /// How the SVG is rasterized: "cpu" to rasterize it in tiles on the CPU, or "gpu" to draw
+    /// its paths on the GPU. The GPU only draws simple fills: flat colors, and linear gradients
+    /// with up to two stops along the x or y axis. SVGs using anything else, such as filters,
+    /// masks, radial gradients or multi-stop gradients, are rasterized on the CPU.
+    ///
+    /// Default: "cpu"
+    pub renderer: Option<SvgPreviewRenderer>,
+}
+
+/// A named set of options for exporting an SVG.
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct SvgPreviewExportPreset {
+    /// The name that identifies the preset, such as "android-icons".
+    pub name: String,
+    /// The format of the exported file.
+    ///
+    /// Default: "png"
+    #[serde(default)]
+    pub format: SvgPreviewExportFormat,
+    /// The size of exported raster images: "48x48", a width such as "48" for which the height
+    /// keeps the aspect ratio, or a scale such as "2x". Ignored for icons and PDFs.
+    ///
+    /// Default: the size of the SVG
+    pub size: Option<String>,
+    /// The color drawn behind exported raster images, as a hex string such as "#ffffff".
+    ///
+    /// Default: transparent, or white for JPEGs
+    pub background: Option<String>,
+    /// The quality of exported JPEGs, from 1 to 100.
+    ///
+    /// Default: 90
+    pub quality: Option<u8>,
+    /// The path of the exported file, relative to the SVG's directory unless absolute. `{name}`
+    /// is replaced by the SVG's file name without extension, `{ext}` by the format's extension,
+    /// and `{width}` and `{height}` by the exported size in pixels.
+    ///
+    /// Default: "{name}.{ext}"
+    pub output: Option<String>,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    Default,
+    PartialEq,
+    Eq,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SvgPreviewExportFormat {
+    /// A PNG image.
+    #[default]
+    Png,
+    /// A JPEG image, which has no transparency.
+    Jpeg,
+    /// A losslessly compressed WebP image.
+    Webp,
+    /// A Windows icon containing the standard icon sizes.
+    Ico,
+    /// A macOS icon containing the standard icon sizes.
+    Icns,
+    /// A single-page vector PDF.
+    Pdf,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    Default,
+    PartialEq,
+    Eq,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SvgPreviewScrollBehavior {
+    /// Scrolling zooms the preview in and out.
+    #[default]
+    Zoom,
+    /// Scrolling pans the preview.
+    Pan,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    Default,
+    PartialEq,
+    Eq,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SvgPreviewFollowScope {
+    /// Follows the last active SVG editor in any pane.
+    #[default]
+    Workspace,
+    /// Follows the last active SVG editor in the pane the preview was opened from.
+    Pane,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    Default,
+    PartialEq,
+    Eq,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SvgPreviewDefaultZoom {
+    /// Zooms so that the whole SVG fits into the pane.
+    #[default]
+    Fit,
+    /// Shows the SVG at its intrinsic size.
+    ActualSize,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    Default,
+    PartialEq,
+    Eq,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SvgPreviewDefaultMode {
+    /// Previews the SVG that was active when the preview was opened.
+    #[default]
+    Pinned,
+    /// Follows the last active SVG editor.
+    Follow,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    Default,
+    PartialEq,
+    Eq,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SvgPreviewAutoOpen {
+    /// Never opens a preview automatically.
+    #[default]
+    Off,
+    /// Opens the preview in a tab next to the SVG's editor.
+    SamePane,
+    /// Opens the preview in the pane to the right, splitting it off if needed.
+    SplitRight,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    Default,
+    PartialEq,
+    Eq,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SvgPreviewRenderer {
+    /// Rasterizes the SVG in tiles on the CPU.
+    #[default]
+    Cpu,
+    /// Draws the SVG's paths on the GPU, so that zooming and panning don't rasterize it again.
+    /// Only SVGs with flat colors and two-stop linear gradients along an axis are drawn this way.
+    Gpu,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SvgPreviewBackground {
+    /// Uses the editor background of the current theme.
+    #[default]
+    Theme,
+    /// Uses a white background.
+    White,
+    /// Uses a black background.
+    Black,
+    /// Uses a custom color, given as a hex string such as "#ff8800".
+    #[serde(untagged)]
+    Custom(String),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SvgPreviewCurrentColor {
+    /// Uses the text color of the current theme.
+    #[default]
+    Foreground,
+    /// Uses white.
+    White,
+    /// Uses black.
+    Black,
+    /// Uses a custom color, given as a hex string such as "#ff8800".
+    #[serde(untagged)]
+    Custom(String),
+}
+
 #[with_fallible_options]
 #[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
 pub struct RemoteSettingsContent {
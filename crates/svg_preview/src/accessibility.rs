@@ -0,0 +1,216 @@
+use std::ops::Range;
+
+use collections::HashSet;
+use gpui::Rgba;
+
+use crate::animation;
+use crate::source_map;
+
+/// The contrast ratio that WCAG requires between normal text and its background.
+const MIN_TEXT_CONTRAST: f32 = 4.5;
+
+/// A problem that makes an SVG harder to perceive with assistive technology or low vision.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Finding {
+    /// The range of the start tag's name of the element the finding is about.
+    pub range: Range<usize>,
+    pub message: String,
+}
+
+/// Checks `text` for missing names, descriptions and roles, for images and links without text
+/// alternatives, and for `text_fills` whose contrast against `background` is too low.
+///
+/// `text_fills` are the colors of the text elements starting at the given offsets, as returned by
+/// [`crate::source_map::SourceMap::text_fills`]. SVGs hidden with `aria-hidden="true"` are
+/// decorative and aren't checked.
+pub fn audit(text: &str, text_fills: &[(usize, Rgba)], background: Rgba) -> Vec<Finding> {
+    let Ok(document) = animation::parse_document(text) else {
+        return Vec::new();
+    };
+    let root = document.root_element();
+    if is_hidden(root) {
+        return Vec::new();
+    }
+    let tag_range =
+        |offset: usize| offset..source_map::tag_name_end(text, offset).unwrap_or(offset);
+    let mut findings = Vec::new();
+    let mut report = |node: roxmltree::Node<'_, '_>, message: String| {
+        findings.push(Finding {
+            range: tag_range(node.range().start),
+            message,
+        });
+    };
+
+    if !has_name(root) {
+        report(
+            root,
+            "The SVG has no <title>, aria-label or aria-labelledby to name it".to_string(),
+        );
+    }
+    if !has_child(root, "desc") {
+        report(root, "The SVG has no <desc> describing it".to_string());
+    }
+    if !matches!(root.attribute("role"), Some("img" | "graphics-document")) {
+        report(
+            root,
+            "The SVG has no role=\"img\", so some screen readers don't announce it as an image"
+                .to_string(),
+        );
+    }
+
+    let ids = document
+        .descendants()
+        .filter_map(|node| node.attribute("id"))
+        .collect::<HashSet<_>>();
+    for node in document.descendants().filter(|node| node.is_element()) {
+        for attribute in ["aria-labelledby", "aria-describedby"] {
+            for id in node
+                .attribute(attribute)
+                .unwrap_or_default()
+                .split_whitespace()
+            {
+                if !ids.contains(id) {
+                    report(
+                        node,
+                        format!("{attribute} refers to the missing id \"{id}\""),
+                    );
+                }
+            }
+        }
+        if node.ancestors().any(is_hidden) {
+            continue;
+        }
+        match node.tag_name().name() {
+            "image" if !has_name(node) => {
+                report(node, "The <image> has no <title> or aria-label".to_string());
+            }
+            "a" if !has_name(node) && !has_text(node) => {
+                report(
+                    node,
+                    "The link has no text, <title> or aria-label".to_string(),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    for (offset, fill) in text_fills {
+        let contrast = contrast_ratio(*fill, background);
+        if contrast < MIN_TEXT_CONTRAST
+            && let Some(node) = document
+                .descendants()
+                .find(|node| node.is_element() && node.range().start == *offset)
+        {
+            report(
+                node,
+                format!(
+                    "The text's contrast against the background is {contrast:.1}:1, below \
+                     {MIN_TEXT_CONTRAST}:1"
+                ),
+            );
+        }
+    }
+    findings
+}
+
+/// Returns the ratio between the relative luminances of two colors, from 1 to 21, as defined by
+/// WCAG.
+pub fn contrast_ratio(a: Rgba, b: Rgba) -> f32 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+fn relative_luminance(color: Rgba) -> f32 {
+    let linear = |channel: f32| {
+        if channel <= 0.04045 {
+            channel / 12.92
+        } else {
+            ((channel + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(color.r) + 0.7152 * linear(color.g) + 0.0722 * linear(color.b)
+}
+
+fn is_hidden(node: roxmltree::Node<'_, '_>) -> bool {
+    node.attribute("aria-hidden") == Some("true")
+}
+
+fn has_name(node: roxmltree::Node<'_, '_>) -> bool {
+    has_child(node, "title")
+        || node
+            .attribute("aria-label")
+            .is_some_and(|label| !label.trim().is_empty())
+        || node.attribute("aria-labelledby").is_some()
+}
+
+/// Returns whether `node` has a child element named `name` with text in it.
+fn has_child(node: roxmltree::Node<'_, '_>, name: &str) -> bool {
+    node.children()
+        .any(|child| child.tag_name().name() == name && has_text(child))
+}
+
+fn has_text(node: roxmltree::Node<'_, '_>) -> bool {
+    node.descendants()
+        .any(|node| node.is_text() && node.text().is_some_and(|text| !text.trim().is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(text: &str, text_fills: &[(usize, Rgba)]) -> Vec<String> {
+        audit(text, text_fills, gpui::white().into())
+            .into_iter()
+            .map(|finding| finding.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_audit() {
+        assert_eq!(
+            messages(
+                r#"<svg xmlns="http://www.w3.org/2000/svg"><image/><a><title>Home</title></a></svg>"#,
+                &[]
+            ),
+            [
+                "The SVG has no <title>, aria-label or aria-labelledby to name it",
+                "The SVG has no <desc> describing it",
+                "The SVG has no role=\"img\", so some screen readers don't announce it as an image",
+                "The <image> has no <title> or aria-label",
+            ]
+        );
+
+        let text = r#"<svg xmlns="http://www.w3.org/2000/svg" role="img" aria-describedby="missing"><title>Logo</title><desc>A logo</desc><text>Zed</text></svg>"#;
+        let light_gray = Rgba {
+            r: 0.8,
+            g: 0.8,
+            b: 0.8,
+            a: 1.,
+        };
+        assert_eq!(
+            messages(text, &[(text.find("<text").unwrap(), light_gray)]),
+            [
+                "aria-describedby refers to the missing id \"missing\"",
+                "The text's contrast against the background is 1.6:1, below 4.5:1",
+            ]
+        );
+
+        assert!(
+            messages(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" aria-hidden="true"/>"#,
+                &[]
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_contrast_ratio() {
+        let ratio = contrast_ratio(gpui::black().into(), gpui::white().into());
+        assert!((ratio - 21.).abs() < 0.01);
+        assert_eq!(
+            contrast_ratio(gpui::white().into(), gpui::white().into()),
+            1.
+        );
+    }
+}
@@ -0,0 +1,549 @@
+//! Playback support for SMIL animations (`<animate>`, `<set>` and `<animateTransform>`).
+//!
+//! The SVG renderer only draws static documents, so animations are played back by computing the
+//! value of every animated attribute at a point in time and serializing a snapshot of the
+//! document with those values applied, which can then be rendered like any other SVG.
+
+use std::fmt::Write as _;
+
+use collections::{HashMap, HashSet};
+use roxmltree::{Node, NodeId};
+
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// The SMIL animations of an SVG document.
+pub struct Animations {
+    text: String,
+    animations: Vec<Animation>,
+    /// The length of the document's timeline in seconds, after which playback loops.
+    duration: f32,
+}
+
+struct Animation {
+    target: NodeId,
+    target_attribute: TargetAttribute,
+    values: Vec<String>,
+    /// Whether the animation runs from the target's current value to the first of `values`.
+    from_base_value: bool,
+    discrete: bool,
+    begin: f32,
+    /// The length of a single iteration, or `None` if it lasts indefinitely.
+    duration: Option<f32>,
+    repeat_count: f32,
+    freeze: bool,
+}
+
+enum TargetAttribute {
+    Attribute(String),
+    Transform { kind: String, additive: bool },
+}
+
+impl Animations {
+    /// Collects the animations of the given document, which must have been parsed from `text`
+    /// with [`parse_document`]. Returns `None` if the document isn't animated.
+    pub fn new(text: &str, document: &roxmltree::Document) -> Option<Self> {
+        let animations = document
+            .descendants()
+            .filter_map(|node| Animation::new(node, document))
+            .collect::<Vec<_>>();
+        let duration = animations
+            .iter()
+            .filter_map(|animation| {
+                let duration = animation.duration?;
+                let repeat_count = if animation.repeat_count.is_finite() {
+                    animation.repeat_count
+                } else {
+                    1.
+                };
+                Some(animation.begin + duration * repeat_count)
+            })
+            .fold(0., f32::max);
+        (!animations.is_empty() && duration > 0.).then(|| Self {
+            text: text.to_string(),
+            animations,
+            duration,
+        })
+    }
+
+    /// The length of the document's timeline, in seconds.
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    /// Serializes the document with all animated attributes set to their values at `time`,
+    /// given in seconds.
+    pub fn snapshot(&self, time: f32) -> Result<String, roxmltree::Error> {
+        let document = parse_document(&self.text)?;
+
+        let mut overrides = HashMap::<NodeId, Vec<(String, String)>>::default();
+        for animation in &self.animations {
+            let Some(target) = document.get_node(animation.target) else {
+                continue;
+            };
+            let attributes = overrides.entry(animation.target).or_default();
+            let name = match &animation.target_attribute {
+                TargetAttribute::Attribute(name) => name.as_str(),
+                TargetAttribute::Transform { .. } => "transform",
+            };
+            let current_value = attributes
+                .iter()
+                .rev()
+                .find(|(attribute, _)| attribute == name)
+                .map(|(_, value)| value.clone())
+                .or_else(|| target.attribute(name).map(str::to_string));
+            let Some(value) = animation.value_at(time, current_value.as_deref()) else {
+                continue;
+            };
+            let value = match &animation.target_attribute {
+                TargetAttribute::Attribute(_) => value,
+                TargetAttribute::Transform { kind, additive } => {
+                    let transform = format!("{kind}({value})");
+                    match current_value.filter(|_| *additive) {
+                        Some(current) => format!("{current} {transform}"),
+                        None => transform,
+                    }
+                }
+            };
+            attributes.push((name.to_string(), value));
+        }
+
+        let mut output = String::with_capacity(self.text.len());
+        write_element(&mut output, document.root_element(), &overrides, None);
+        Ok(output)
+    }
+}
+
+impl Animation {
+    fn new(node: Node, document: &roxmltree::Document) -> Option<Self> {
+        if node.tag_name().namespace() != Some(SVG_NAMESPACE) {
+            return None;
+        }
+        let target_attribute = match node.tag_name().name() {
+            "animate" | "set" | "animateColor" => {
+                TargetAttribute::Attribute(node.attribute("attributeName")?.to_string())
+            }
+            "animateTransform" => TargetAttribute::Transform {
+                kind: node.attribute("type").unwrap_or("translate").to_string(),
+                additive: node.attribute("additive") == Some("sum"),
+            },
+            _ => return None,
+        };
+        let target = match node
+            .attribute("href")
+            .or_else(|| node.attribute((XLINK_NAMESPACE, "href")))
+        {
+            Some(href) => {
+                let id = href.strip_prefix('#')?;
+                document
+                    .descendants()
+                    .find(|node| node.attribute("id") == Some(id))?
+                    .id()
+            }
+            None => node.parent_element()?.id(),
+        };
+
+        let mut from_base_value = false;
+        let values = if let Some(values) = node.attribute("values") {
+            values
+                .split(';')
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+                .collect()
+        } else {
+            let from = node.attribute("from");
+            let to = node
+                .attribute("to")
+                .map(str::to_string)
+                .or_else(|| Some(add_values(from?, node.attribute("by")?)))?;
+            match from {
+                Some(from) => vec![from.to_string(), to],
+                None if node.tag_name().name() == "set" => vec![to],
+                None => {
+                    from_base_value = true;
+                    vec![to]
+                }
+            }
+        };
+        if values.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            target,
+            target_attribute,
+            values,
+            from_base_value,
+            discrete: node.attribute("calcMode") == Some("discrete"),
+            // Only offsets are supported, so animations that begin on events never play.
+            begin: node.attribute("begin").map_or(Some(0.), |begin| {
+                parse_clock_value(begin.split(';').next().unwrap_or_default())
+            })?,
+            duration: node.attribute("dur").and_then(parse_clock_value),
+            repeat_count: match node.attribute("repeatCount") {
+                Some("indefinite") => f32::INFINITY,
+                Some(count) => count.trim().parse().unwrap_or(1.),
+                None => 1.,
+            },
+            freeze: node.attribute("fill") == Some("freeze"),
+        })
+    }
+
+    /// Returns how far into the current iteration the animation is at `time`, from 0 to 1, or
+    /// `None` if it isn't active.
+    fn progress(&self, time: f32) -> Option<f32> {
+        let elapsed = time - self.begin;
+        if elapsed < 0. {
+            return None;
+        }
+        let Some(duration) = self.duration.filter(|duration| *duration > 0.) else {
+            return Some(0.);
+        };
+        let active_duration = duration * self.repeat_count;
+        if elapsed < active_duration {
+            Some(elapsed % duration / duration)
+        } else if self.freeze {
+            let last_iteration = active_duration % duration / duration;
+            Some(if last_iteration == 0. {
+                1.
+            } else {
+                last_iteration
+            })
+        } else {
+            None
+        }
+    }
+
+    fn value_at(&self, time: f32, base_value: Option<&str>) -> Option<String> {
+        let progress = self.progress(time)?;
+        let base_value = base_value.filter(|_| self.from_base_value);
+        let values = base_value
+            .into_iter()
+            .chain(self.values.iter().map(String::as_str))
+            .collect::<Vec<_>>();
+        if values.len() == 1 {
+            return Some(values[0].to_string());
+        }
+
+        if self.discrete {
+            let index = ((progress * values.len() as f32) as usize).min(values.len() - 1);
+            return Some(values[index].to_string());
+        }
+        let segment = progress * (values.len() - 1) as f32;
+        let index = (segment as usize).min(values.len() - 2);
+        let fraction = segment - index as f32;
+        Some(
+            interpolate(values[index], values[index + 1], fraction).unwrap_or_else(|| {
+                let value = if fraction < 0.5 {
+                    values[index]
+                } else {
+                    values[index + 1]
+                };
+                value.to_string()
+            }),
+        )
+    }
+}
+
+/// Parses an SVG document the way the renderer does, so that node ids line up between parses.
+pub fn parse_document(text: &str) -> Result<roxmltree::Document<'_>, roxmltree::Error> {
+    roxmltree::Document::parse_with_options(
+        text,
+        roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        },
+    )
+}
+
+/// Parses a SMIL offset such as `2s`, `250ms`, `0.5` or `00:01.5`, in seconds.
+fn parse_clock_value(value: &str) -> Option<f32> {
+    let value = value.trim();
+    if let Some(milliseconds) = value.strip_suffix("ms") {
+        return Some(milliseconds.trim().parse::<f32>().ok()? / 1000.);
+    }
+    if let Some(minutes) = value.strip_suffix("min") {
+        return Some(minutes.trim().parse::<f32>().ok()? * 60.);
+    }
+    if let Some(hours) = value.strip_suffix('h') {
+        return Some(hours.trim().parse::<f32>().ok()? * 3600.);
+    }
+    if let Some(seconds) = value.strip_suffix('s') {
+        return seconds.trim().parse().ok();
+    }
+    value.split(':').try_fold(0., |total, part| {
+        Some(total * 60. + part.parse::<f32>().ok()?)
+    })
+}
+
+/// Splits a value into its numbers and the text between them.
+fn tokenize(value: &str) -> (Vec<f32>, Vec<&str>) {
+    let mut numbers = Vec::new();
+    let mut separators = Vec::new();
+    let mut separator_start = 0;
+    let bytes = value.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        let starts_number = bytes[index].is_ascii_digit()
+            || (matches!(bytes[index], b'-' | b'+' | b'.')
+                && bytes
+                    .get(index + 1)
+                    .is_some_and(|next| next.is_ascii_digit() || *next == b'.'));
+        if !starts_number {
+            index += 1;
+            continue;
+        }
+        let mut end = index + 1;
+        while end < bytes.len()
+            && (bytes[end].is_ascii_digit()
+                || bytes[end] == b'.'
+                || ((bytes[end] == b'e' || bytes[end] == b'E')
+                    && bytes.get(end + 1).is_some_and(u8::is_ascii_digit)))
+        {
+            end += 1;
+        }
+        if let Ok(number) = value[index..end].parse() {
+            separators.push(&value[separator_start..index]);
+            numbers.push(number);
+            separator_start = end;
+        }
+        index = end;
+    }
+    separators.push(&value[separator_start..]);
+    (numbers, separators)
+}
+
+/// Interpolates between two values made of the same text with different numbers in it, such as
+/// lengths, number lists or path data, as well as between hex colors.
+fn interpolate(from: &str, to: &str, fraction: f32) -> Option<String> {
+    let lerp = |from: f32, to: f32| from + (to - from) * fraction;
+
+    if let (Some(from), Some(to)) = (parse_hex_color(from), parse_hex_color(to)) {
+        let mut color = String::from("#");
+        for (from, to) in from.into_iter().zip(to) {
+            write!(color, "{:02x}", lerp(from as f32, to as f32).round() as u8).ok();
+        }
+        return Some(color);
+    }
+
+    let (from_numbers, from_separators) = tokenize(from);
+    let (to_numbers, to_separators) = tokenize(to);
+    if from_numbers.is_empty() || from_separators != to_separators {
+        return None;
+    }
+    let mut value = String::new();
+    for (index, separator) in from_separators.iter().enumerate() {
+        value.push_str(separator);
+        if let (Some(from), Some(to)) = (from_numbers.get(index), to_numbers.get(index)) {
+            write!(value, "{}", lerp(*from, *to)).ok();
+        }
+    }
+    Some(value)
+}
+
+/// Computes the `to` value of a `by` animation.
+fn add_values(from: &str, by: &str) -> String {
+    let (from_numbers, separators) = tokenize(from);
+    let (by_numbers, _) = tokenize(by);
+    if from_numbers.len() != by_numbers.len() {
+        return from.to_string();
+    }
+    let mut value = String::new();
+    for (index, separator) in separators.iter().enumerate() {
+        value.push_str(separator);
+        if let (Some(from), Some(by)) = (from_numbers.get(index), by_numbers.get(index)) {
+            write!(value, "{}", from + by).ok();
+        }
+    }
+    value
+}
+
+fn parse_hex_color(value: &str) -> Option<[u8; 3]> {
+    let hex = value.trim().strip_prefix('#')?;
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+    match hex.len() {
+        3 => {
+            let mut color = [0; 3];
+            for (channel_value, digit) in color.iter_mut().zip(hex.chars()) {
+                *channel_value = channel(&digit.to_string())? * 17;
+            }
+            Some(color)
+        }
+        6 => Some([
+            channel(hex.get(0..2)?)?,
+            channel(hex.get(2..4)?)?,
+            channel(hex.get(4..6)?)?,
+        ]),
+        _ => None,
+    }
+}
+
+pub(crate) fn is_animation_element(node: Node) -> bool {
+    node.tag_name().namespace() == Some(SVG_NAMESPACE)
+        && matches!(
+            node.tag_name().name(),
+            "animate" | "set" | "animateColor" | "animateTransform" | "animateMotion"
+        )
+}
+
+fn write_element(
+    output: &mut String,
+    node: Node,
+    overrides: &HashMap<NodeId, Vec<(String, String)>>,
+    parent_namespace: Option<&str>,
+) {
+    let namespace = node.tag_name().namespace();
+    output.push('<');
+    output.push_str(node.tag_name().name());
+    if namespace != parent_namespace
+        && let Some(namespace) = namespace
+    {
+        write_attribute(output, "xmlns", namespace);
+    }
+
+    let node_overrides = overrides.get(&node.id());
+    let overridden_value = |name: &str| {
+        node_overrides?
+            .iter()
+            .rev()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    };
+    let mut declared_xlink = false;
+    for attribute in node.attributes() {
+        match attribute.namespace() {
+            None => {
+                let value = overridden_value(attribute.name()).unwrap_or(attribute.value());
+                write_attribute(output, attribute.name(), value);
+            }
+            Some(XLINK_NAMESPACE) => {
+                if !declared_xlink {
+                    write_attribute(output, "xmlns:xlink", XLINK_NAMESPACE);
+                    declared_xlink = true;
+                }
+                write_attribute(
+                    output,
+                    &format!("xlink:{}", attribute.name()),
+                    attribute.value(),
+                );
+            }
+            Some(XML_NAMESPACE) => {
+                write_attribute(
+                    output,
+                    &format!("xml:{}", attribute.name()),
+                    attribute.value(),
+                );
+            }
+            // Attributes in other namespaces are editor metadata that doesn't affect rendering.
+            Some(_) => {}
+        }
+    }
+    let mut added_attributes = HashSet::default();
+    for (name, _) in node_overrides.into_iter().flatten() {
+        if node.attribute(name.as_str()).is_none()
+            && added_attributes.insert(name)
+            && let Some(value) = overridden_value(name)
+        {
+            write_attribute(output, name, value);
+        }
+    }
+
+    let mut children = node
+        .children()
+        .filter(|child| (child.is_element() && !is_animation_element(*child)) || child.is_text())
+        .peekable();
+    if children.peek().is_none() {
+        output.push_str("/>");
+        return;
+    }
+    output.push('>');
+    for child in children {
+        if child.is_element() {
+            write_element(output, child, overrides, namespace);
+        } else if let Some(text) = child.text() {
+            write_escaped(output, text, false);
+        }
+    }
+    output.push_str("</");
+    output.push_str(node.tag_name().name());
+    output.push('>');
+}
+
+fn write_attribute(output: &mut String, name: &str, value: &str) {
+    output.push(' ');
+    output.push_str(name);
+    output.push_str("=\"");
+    write_escaped(output, value, true);
+    output.push('"');
+}
+
+fn write_escaped(output: &mut String, text: &str, is_attribute: bool) {
+    for character in text.chars() {
+        match character {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' if is_attribute => output.push_str("&quot;"),
+            character => output.push(character),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(text: &str, time: f32) -> String {
+        let document = parse_document(text).unwrap();
+        Animations::new(text, &document)
+            .unwrap()
+            .snapshot(time)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_clock_value() {
+        assert_eq!(parse_clock_value("2s"), Some(2.));
+        assert_eq!(parse_clock_value("250ms"), Some(0.25));
+        assert_eq!(parse_clock_value("1.5"), Some(1.5));
+        assert_eq!(parse_clock_value("01:30"), Some(90.));
+        assert_eq!(parse_clock_value("click"), None);
+    }
+
+    #[test]
+    fn test_interpolate() {
+        assert_eq!(interpolate("0", "10", 0.5).as_deref(), Some("5"));
+        assert_eq!(
+            interpolate("M 0 0 L 10 10", "M 10 0 L 20 30", 0.5).as_deref(),
+            Some("M 5 0 L 15 20")
+        );
+        assert_eq!(
+            interpolate("#000", "#ffffff", 0.5).as_deref(),
+            Some("#808080")
+        );
+        assert_eq!(interpolate("red", "blue", 0.5), None);
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let text = r#"<svg xmlns="http://www.w3.org/2000/svg"><rect width="10"><animate attributeName="width" from="10" to="20" dur="2s"/></rect></svg>"#;
+        let document = parse_document(text).unwrap();
+        assert_eq!(Animations::new(text, &document).unwrap().duration(), 2.);
+        assert_eq!(
+            snapshot(text, 1.),
+            r#"<svg xmlns="http://www.w3.org/2000/svg"><rect width="15"/></svg>"#
+        );
+        assert_eq!(
+            snapshot(text, 3.),
+            r#"<svg xmlns="http://www.w3.org/2000/svg"><rect width="10"/></svg>"#
+        );
+
+        let text = r##"<svg xmlns="http://www.w3.org/2000/svg"><g id="a" transform="scale(2)"/><animateTransform href="#a" attributeName="transform" type="rotate" values="0;90" dur="1s" additive="sum" fill="freeze"/></svg>"##;
+        assert_eq!(
+            snapshot(text, 5.),
+            r#"<svg xmlns="http://www.w3.org/2000/svg"><g id="a" transform="scale(2) rotate(90)"/></svg>"#
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Result;
+use fs::Fs;
+use futures::StreamExt;
+use gpui::{
+    App, Context, DismissEvent, EventEmitter, FocusHandle, Focusable, PathPromptOptions, Render,
+    SvgRenderer, Task, Window, size,
+};
+use settings::Settings;
+use ui::prelude::*;
+use workspace::Workspace;
+use workspace::notifications::{Notification, NotificationFrame, NotificationId, SuppressEvent};
+use zed_actions::svg_preview::ExportDirectory;
+
+use crate::SvgPreviewSettings;
+use crate::animation;
+use crate::export::{scaled_export_size, scaled_file_name, write_export};
+use crate::external_resources::{ExternalResources, OpenFiles};
+use crate::fonts::SvgFonts;
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, action: &ExportDirectory, window, cx| {
+        if let Some(directory) = action.directory.clone() {
+            export_directory(workspace, directory, cx);
+            return;
+        }
+        let directory = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some("Export SVGs".into()),
+        });
+        cx.spawn_in(window, async move |workspace, cx| {
+            let Some(directory) = directory.await??.and_then(|paths| paths.into_iter().next())
+            else {
+                return Ok(());
+            };
+            workspace.update(cx, |workspace, cx| {
+                export_directory(workspace, directory, cx)
+            })
+        })
+        .detach_and_log_err(cx);
+    });
+}
+
+/// Exports every SVG in `directory` and its subdirectories to PNGs at the scales configured in
+/// the settings, written next to each SVG, while a notification shows the progress.
+fn export_directory(workspace: &mut Workspace, directory: PathBuf, cx: &mut Context<Workspace>) {
+    let fs = workspace.app_state().fs.clone();
+    workspace.show_notification(NotificationId::unique::<BatchExport>(), cx, |cx| {
+        cx.new(|cx| BatchExport::new(directory, fs, cx))
+    });
+}
+
+struct BatchExport {
+    focus_handle: FocusHandle,
+    directory: PathBuf,
+    /// The number of SVGs found, or `None` while they're being looked for.
+    total: Option<usize>,
+    exported: usize,
+    failures: Vec<(PathBuf, SharedString)>,
+    finished: bool,
+    /// Exporting stops when the notification is dismissed, which drops this task.
+    _task: Task<()>,
+}
+
+impl BatchExport {
+    fn new(directory: PathBuf, fs: Arc<dyn Fs>, cx: &mut Context<Self>) -> Self {
+        let settings = SvgPreviewSettings::get_global(cx);
+        let scales = settings
+            .export_scales
+            .iter()
+            .copied()
+            .filter(|scale| *scale > 0.)
+            .collect::<Vec<_>>();
+        let font_dirs = settings.font_dirs.clone();
+        let fallback_family = settings.fallback_font_family.clone();
+        let renderer = cx.svg_renderer();
+        let task = cx.spawn({
+            let directory = directory.clone();
+            async move |this, cx| {
+                let fonts = cx
+                        .background_spawn({
+                            let renderer = renderer.clone();
+                            async move {
+                                Arc::new(SvgFonts::load(&renderer, &font_dirs, fallback_family))
+                            }
+                        })
+                        .await;
+                let files = svg_files(&fs, &directory).await;
+                let files = match files {
+                    Ok(files) => files,
+                    Err(error) => {
+                        this.update(cx, |this, cx| {
+                            this.total = Some(0);
+                            this.failures.push((directory, error.to_string().into()));
+                            this.finished = true;
+                            cx.notify();
+                        })
+                        .ok();
+                        return;
+                    }
+                };
+                if this
+                    .update(cx, |this, cx| {
+                        this.total = Some(files.len());
+                        cx.notify();
+                    })
+                    .is_err()
+                {
+                    return;
+                }
+
+                for path in files {
+                    let result = export_svg(
+                        &path,
+                        &scales,
+                        &fs,
+                        &renderer,
+                        &fonts,
+                        cx.background_executor(),
+                    )
+                    .await;
+                    let updated = this.update(cx, |this, cx| {
+                        match result {
+                            Ok(()) => this.exported += 1,
+                            Err(error) => this.failures.push((path, error.to_string().into())),
+                        }
+                        cx.notify();
+                    });
+                    if updated.is_err() {
+                        return;
+                    }
+                }
+                this.update(cx, |this, cx| {
+                    this.finished = true;
+                    cx.notify();
+                })
+                .ok();
+            }
+        });
+        Self {
+            focus_handle: cx.focus_handle(),
+            directory,
+            total: None,
+            exported: 0,
+            failures: Vec::new(),
+            finished: false,
+            _task: task,
+        }
+    }
+}
+
+/// Returns the SVGs in `directory` and its subdirectories, sorted by path.
+async fn svg_files(fs: &Arc<dyn Fs>, directory: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut directories = vec![directory.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        let mut entries = fs.read_dir(&directory).await?;
+        while let Some(entry) = entries.next().await {
+            let path = entry?;
+            if fs.is_dir(&path).await {
+                directories.push(path);
+            } else if path
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("svg"))
+            {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Renders the SVG at `path` at each of `scales` on a background thread and writes the PNGs next
+/// to it.
+async fn export_svg(
+    path: &Path,
+    scales: &[f32],
+    fs: &Arc<dyn Fs>,
+    renderer: &SvgRenderer,
+    fonts: &Arc<SvgFonts>,
+    executor: &gpui::BackgroundExecutor,
+) -> Result<()> {
+    let text = fs.load(path).await?;
+    let base_dir = path.parent();
+    let external_resources = {
+        let document = animation::parse_document(&text)?;
+        Arc::new(
+            ExternalResources::load(&document, base_dir, Some(fs), &OpenFiles::default()).await,
+        )
+    };
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let pngs = executor
+        .spawn({
+            let renderer = renderer.clone();
+            let fonts = fonts.clone();
+            let scales = scales.to_vec();
+            async move {
+                let inlined = {
+                    let document = animation::parse_document(&text)?;
+                    external_resources.inline_external_uses(&text, &document)
+                };
+                let text = inlined.unwrap_or(text);
+                let options = external_resources.usvg_options(&renderer, Some(&fonts));
+                let tree = usvg::Tree::from_str(&text, &options)?;
+                let svg_size = size(tree.size().width(), tree.size().height());
+                scales
+                    .into_iter()
+                    .map(|scale| {
+                        let png = renderer.render_png(
+                            &tree,
+                            scaled_export_size(svg_size, scale),
+                            None,
+                        )?;
+                        Ok((scaled_file_name(&stem, scale), png))
+                    })
+                    .collect::<Result<Vec<_>>>()
+            }
+        })
+        .await?;
+    for (file_name, png) in pngs {
+        write_export(
+            Some(fs.clone()),
+            path.with_file_name(file_name),
+            png,
+            executor,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+impl EventEmitter<DismissEvent> for BatchExport {}
+impl EventEmitter<SuppressEvent> for BatchExport {}
+
+impl Focusable for BatchExport {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Notification for BatchExport {}
+
+impl Render for BatchExport {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let message = match self.total {
+            None => format!("Looking for SVGs in {}…", self.directory.display()),
+            Some(total) if !self.finished => format!(
+                "Exported {} of {total} SVGs…",
+                self.exported + self.failures.len()
+            ),
+            Some(total) => format!("Exported {} of {total} SVGs.", self.exported),
+        };
+        let title = if self.finished {
+            "Exported SVGs"
+        } else {
+            "Exporting SVGs"
+        };
+        NotificationFrame::new()
+            .with_title(Some(title))
+            .show_suppress_button(false)
+            .on_close(cx.listener(|_, _, _, cx| cx.emit(DismissEvent)))
+            .with_content(v_flex().gap_1().child(Label::new(message)).children(
+                self.failures.iter().map(|(path, error)| {
+                    Label::new(format!("{}: {error}", path.display()))
+                        .size(LabelSize::Small)
+                        .color(Color::Error)
+                }),
+            ))
+    }
+}
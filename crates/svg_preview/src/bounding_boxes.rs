@@ -0,0 +1,48 @@
+/// Returns the bounds of the elements of `tree` that are nested at most `max_depth` levels deep,
+/// where the top-level elements are at depth 1, in the coordinates of the SVG's size.
+pub fn bounding_boxes(tree: &usvg::Tree, max_depth: u32) -> Vec<usvg::Rect> {
+    let mut boxes = Vec::new();
+    collect_bounding_boxes(tree.root(), max_depth, &mut boxes);
+    boxes
+}
+
+fn collect_bounding_boxes(group: &usvg::Group, depth_left: u32, boxes: &mut Vec<usvg::Rect>) {
+    if depth_left == 0 {
+        return;
+    }
+    for node in group.children() {
+        boxes.push(node.abs_stroke_bounding_box());
+        if let usvg::Node::Group(group) = node {
+            collect_bounding_boxes(group, depth_left - 1, boxes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounding_boxes() {
+        let tree = usvg::Tree::from_str(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10">
+                <g id="group">
+                    <rect width="10" height="10"/>
+                    <rect x="10" width="10" height="5"/>
+                </g>
+                <rect x="5" y="5" width="1" height="1"/>
+            </svg>"#,
+            &usvg::Options::default(),
+        )
+        .unwrap();
+        let sizes = |max_depth| {
+            bounding_boxes(&tree, max_depth)
+                .iter()
+                .map(|rect| (rect.width(), rect.height()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(sizes(0), []);
+        assert_eq!(sizes(1), [(20., 10.), (1., 1.)]);
+        assert_eq!(sizes(2), [(20., 10.), (10., 10.), (10., 5.), (1., 1.)]);
+    }
+}
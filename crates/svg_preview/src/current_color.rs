@@ -0,0 +1,67 @@
+use gpui::{Hsla, Rgba};
+
+/// Returns `text` with a `color` attribute added to the root element of `document`, so that
+/// `currentColor` resolves to `color` as it would when the SVG is embedded in a page. Returns
+/// `None` if the root element already sets its color.
+///
+/// `text` may differ from the text of `document` after the start tag of the root element.
+pub fn with_current_color(
+    text: &str,
+    document: &roxmltree::Document,
+    color: &str,
+) -> Option<String> {
+    let root = document.root_element();
+    let sets_color = root.attribute("color").is_some()
+        || root.attribute("style").is_some_and(|style| {
+            style.split(';').any(|declaration| {
+                declaration
+                    .split(':')
+                    .next()
+                    .is_some_and(|name| name.trim() == "color")
+            })
+        });
+    if sets_color {
+        return None;
+    }
+
+    let tag_start = root.range().start;
+    let name_end = tag_start
+        + 1
+        + text[tag_start + 1..].find(|c: char| c.is_whitespace() || c == '/' || c == '>')?;
+    let mut text = text.to_string();
+    text.insert_str(name_end, &format!(" color=\"{color}\""));
+    Some(text)
+}
+
+/// Formats a color as a `#rrggbb` hex string, ignoring its alpha.
+pub fn hex_color(color: Hsla) -> String {
+    let color = Rgba::from(color);
+    let channel = |value: f32| (value * 255.).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        channel(color.r),
+        channel(color.g),
+        channel(color.b)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_current_color() {
+        let text = r#"<svg xmlns="http://www.w3.org/2000/svg"><path fill="currentColor"/></svg>"#;
+        let document = roxmltree::Document::parse(text).unwrap();
+        assert_eq!(
+            with_current_color(text, &document, "#ff8800").as_deref(),
+            Some(
+                r#"<svg color="#ff8800" xmlns="http://www.w3.org/2000/svg"><path fill="currentColor"/></svg>"#
+            )
+        );
+
+        let text = r#"<svg xmlns="http://www.w3.org/2000/svg" style="fill: red; color: blue"/>"#;
+        let document = roxmltree::Document::parse(text).unwrap();
+        assert_eq!(with_current_color(text, &document, "#ff8800"), None);
+    }
+}
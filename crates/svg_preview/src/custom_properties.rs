@@ -0,0 +1,190 @@
+use collections::HashMap;
+
+/// A CSS custom property referenced by an SVG document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomProperty {
+    /// The name of the property, including the leading `--`.
+    pub name: String,
+    /// The value the document declares for the property, if any.
+    pub value: Option<String>,
+}
+
+/// Returns the custom properties that `text` declares or references with `var()`, in order of
+/// first appearance.
+pub fn custom_properties(text: &str) -> Vec<CustomProperty> {
+    let declarations = declarations(text);
+    let mut properties: Vec<CustomProperty> = Vec::new();
+    let mut add = |name: &str| {
+        if !properties.iter().any(|property| property.name == name) {
+            properties.push(CustomProperty {
+                name: name.to_string(),
+                value: declarations.get(name).cloned(),
+            });
+        }
+    };
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find("--").map(|start| offset + start) {
+        let name = property_name(&text[start..]);
+        offset = start + name.len().max(2);
+        if !name.is_empty() && is_property_start(text, start) {
+            add(name);
+        }
+    }
+    properties
+}
+
+/// Returns `text` with the `var()` references replaced by the value in `overrides`, the value
+/// the document declares, or the reference's fallback, in that order of preference. References
+/// that can't be resolved are kept. Returns `None` if `text` doesn't contain any references.
+///
+/// Declarations aren't scoped to the elements they're declared on, which matches how custom
+/// properties are commonly used in SVGs: declared once on the root element or `:root`.
+pub fn resolve_custom_properties(
+    text: &str,
+    overrides: &HashMap<String, String>,
+) -> Option<String> {
+    if !text.contains("var(") {
+        return None;
+    }
+    let mut values = declarations(text);
+    values.extend(
+        overrides
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone())),
+    );
+    Some(substitute(text, &values, 0))
+}
+
+/// References are resolved recursively, up to this depth, to guard against cycles.
+const MAX_SUBSTITUTION_DEPTH: usize = 8;
+
+fn substitute(text: &str, values: &HashMap<String, String>, depth: usize) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("var(") {
+        result.push_str(&rest[..start]);
+        let arguments_start = start + "var(".len();
+        let Some(arguments_len) = closing_paren(&rest[arguments_start..]) else {
+            result.push_str(&rest[start..]);
+            return result;
+        };
+        let arguments = &rest[arguments_start..arguments_start + arguments_len];
+        let (name, fallback) = match arguments.split_once(',') {
+            Some((name, fallback)) => (name.trim(), Some(fallback.trim())),
+            None => (arguments.trim(), None),
+        };
+        let reference_end = arguments_start + arguments_len + 1;
+        match values.get(name).map(String::as_str).or(fallback) {
+            Some(value) if depth < MAX_SUBSTITUTION_DEPTH => {
+                result.push_str(&substitute(value, values, depth + 1));
+            }
+            _ => result.push_str(&rest[start..reference_end]),
+        }
+        rest = &rest[reference_end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Returns the length of `text` up to the parenthesis closing the one opened before it.
+fn closing_paren(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(index),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the values of the custom property declarations in `text`, such as `--accent: red`.
+fn declarations(text: &str) -> HashMap<String, String> {
+    let mut declarations = HashMap::default();
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find("--").map(|start| offset + start) {
+        let name = property_name(&text[start..]);
+        offset = start + name.len().max(2);
+        if name.is_empty() || !is_property_start(text, start) {
+            continue;
+        }
+        let Some(value) = text[offset..].trim_start().strip_prefix(':') else {
+            continue;
+        };
+        let value_len = value
+            .find(|c| matches!(c, ';' | '}' | '"' | '\'' | '<'))
+            .unwrap_or(value.len());
+        let value = value[..value_len].trim();
+        if !value.is_empty() {
+            declarations
+                .entry(name.to_string())
+                .or_insert_with(|| value.to_string());
+        }
+    }
+    declarations
+}
+
+/// Returns the custom property name at the start of `text`, or an empty string if there's none.
+fn property_name(text: &str) -> &str {
+    let len = text
+        .char_indices()
+        .skip(2)
+        .find(|(_, c)| !(c.is_alphanumeric() || matches!(c, '-' | '_')))
+        .map_or(text.len(), |(index, _)| index);
+    if len > 2 { &text[..len] } else { "" }
+}
+
+/// Whether the `--` at `start` begins a property name rather than being part of an identifier or
+/// an XML comment delimiter.
+fn is_property_start(text: &str, start: usize) -> bool {
+    text[..start]
+        .chars()
+        .next_back()
+        .is_none_or(|c| c.is_whitespace() || matches!(c, '{' | ';' | '(' | '"' | '\'' | ','))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" style="--accent: #ff8800">
+        <!-- A themed icon -->
+        <style>rect { stroke: var(--outline, black); }</style>
+        <rect fill="var(--accent)"/>
+    </svg>"#;
+
+    #[test]
+    fn test_custom_properties() {
+        assert_eq!(
+            custom_properties(TEXT),
+            [
+                CustomProperty {
+                    name: "--accent".into(),
+                    value: Some("#ff8800".into()),
+                },
+                CustomProperty {
+                    name: "--outline".into(),
+                    value: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_custom_properties() {
+        let resolved = resolve_custom_properties(TEXT, &HashMap::default()).unwrap();
+        assert!(resolved.contains("stroke: black;"));
+        assert!(resolved.contains(r##"fill="#ff8800""##));
+
+        let overrides = HashMap::from_iter([("--outline".to_string(), "white".to_string())]);
+        let resolved = resolve_custom_properties(TEXT, &overrides).unwrap();
+        assert!(resolved.contains("stroke: white;"));
+
+        assert_eq!(
+            resolve_custom_properties(r#"<rect fill="red"/>"#, &HashMap::default()),
+            None
+        );
+    }
+}
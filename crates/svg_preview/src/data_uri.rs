@@ -0,0 +1,141 @@
+use std::fmt::Write as _;
+use std::ops::Range;
+
+use base64::Engine as _;
+use base64::prelude::BASE64_STANDARD;
+
+const PREFIX: &str = "data:image/svg+xml";
+
+/// How the SVG in a data URI is encoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DataUriEncoding {
+    /// `data:image/svg+xml;base64,PHN2Zy8+`
+    Base64,
+    /// `data:image/svg+xml,%3Csvg/%3E`
+    Percent,
+}
+
+/// An SVG data URI in a text, such as a CSS `url()`.
+#[derive(Debug, PartialEq)]
+pub struct SvgDataUri {
+    /// The range of the whole URI, from `data:` to the end of the encoded SVG.
+    pub range: Range<usize>,
+    /// The URI up to and including the comma that precedes the encoded SVG.
+    pub header: String,
+    pub encoding: DataUriEncoding,
+    pub svg: String,
+}
+
+impl SvgDataUri {
+    /// Returns the URI with the SVG replaced by `svg`, encoded like the original.
+    pub fn with_svg(&self, svg: &str) -> String {
+        let mut uri = self.header.clone();
+        match self.encoding {
+            DataUriEncoding::Base64 => BASE64_STANDARD.encode_string(svg, &mut uri),
+            DataUriEncoding::Percent => percent_encode(svg, &mut uri),
+        }
+        uri
+    }
+}
+
+/// Finds the SVG data URI around `offset` in `text` and decodes it.
+pub fn svg_data_uri_at(text: &str, offset: usize) -> Option<SvgDataUri> {
+    text.match_indices(PREFIX)
+        .map(|(start, _)| {
+            // The URI ends where its quotes or `url()` do. Unquoted URIs end at whitespace.
+            let end_delimiters: &[char] = match text[..start].chars().next_back() {
+                Some('"') => &['"'],
+                Some('\'') => &['\''],
+                Some('(') => &[')', '"', '\''],
+                _ => &[')', '"', '\'', ' ', '\t', '\n', '\r', ';'],
+            };
+            let end = text[start..]
+                .find(end_delimiters)
+                .map_or(text.len(), |index| start + index);
+            start..end
+        })
+        .find(|range| range.start <= offset && offset <= range.end)
+        .and_then(|range| decode(&text[range.clone()], range))
+}
+
+fn decode(uri: &str, range: Range<usize>) -> Option<SvgDataUri> {
+    let comma = uri.find(',')?;
+    let (header, data) = uri.split_at(comma + 1);
+    let parameters = &header[PREFIX.len()..header.len() - 1];
+    let (encoding, svg) = if parameters
+        .split(';')
+        .any(|parameter| parameter.eq_ignore_ascii_case("base64"))
+    {
+        let bytes = BASE64_STANDARD.decode(data.trim().as_bytes()).ok()?;
+        (DataUriEncoding::Base64, String::from_utf8(bytes).ok()?)
+    } else {
+        (
+            DataUriEncoding::Percent,
+            urlencoding::decode(data).ok()?.into_owned(),
+        )
+    };
+    Some(SvgDataUri {
+        range,
+        header: header.to_string(),
+        encoding,
+        svg,
+    })
+}
+
+/// Percent-encodes the characters of `svg` that aren't safe in a quoted or unquoted CSS `url()`,
+/// leaving the rest readable.
+fn percent_encode(svg: &str, output: &mut String) {
+    for character in svg.chars() {
+        if character.is_ascii_alphanumeric() || "-_.~!*,/:;=?@[]+$&".contains(character) {
+            output.push(character);
+        } else {
+            let mut bytes = [0; 4];
+            for byte in character.encode_utf8(&mut bytes).bytes() {
+                write!(output, "%{byte:02X}").ok();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg_data_uri_at_base64() {
+        let text = r#".icon { background: url("data:image/svg+xml;base64,PHN2Zy8+"); }"#;
+        let start = text.find("data:").unwrap();
+        let end = text.find("\")").unwrap();
+        let data_uri = svg_data_uri_at(text, start + 30).unwrap();
+        assert_eq!(
+            data_uri,
+            SvgDataUri {
+                range: start..end,
+                header: "data:image/svg+xml;base64,".into(),
+                encoding: DataUriEncoding::Base64,
+                svg: "<svg/>".into(),
+            }
+        );
+        assert_eq!(data_uri.with_svg("<svg/>"), &text[start..end]);
+        assert_eq!(svg_data_uri_at(text, 2), None);
+    }
+
+    #[test]
+    fn test_svg_data_uri_at_percent() {
+        let text = r#"background: url("data:image/svg+xml,%3Csvg xmlns='http://www.w3.org/2000/svg'/%3E")"#;
+        let start = text.find("data:").unwrap();
+        let data_uri = svg_data_uri_at(text, start).unwrap();
+        assert_eq!(data_uri.range, start..text.len() - 2);
+        assert_eq!(data_uri.encoding, DataUriEncoding::Percent);
+        assert_eq!(data_uri.svg, "<svg xmlns='http://www.w3.org/2000/svg'/>");
+        assert_eq!(
+            data_uri.with_svg("<svg fill=\"#fff\"/>"),
+            "data:image/svg+xml,%3Csvg%20fill=%22%23fff%22/%3E"
+        );
+
+        let unquoted = "url(data:image/svg+xml,%3Csvg/%3E)";
+        let data_uri = svg_data_uri_at(unquoted, 10).unwrap();
+        assert_eq!(data_uri.range, 4..unquoted.len() - 1);
+        assert_eq!(data_uri.svg, "<svg/>");
+    }
+}
@@ -0,0 +1,427 @@
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Result;
+use fs::Fs;
+use futures::StreamExt;
+use gpui::{
+    App, Context, EventEmitter, FocusHandle, Focusable, ObjectFit, PathPromptOptions, Render,
+    RenderImage, Subscription, SvgRenderer, Task, WeakEntity, Window, img, size,
+};
+use settings::Settings;
+use ui::{Tooltip, prelude::*};
+use workspace::item::Item;
+use workspace::{OpenOptions, Workspace};
+use zed_actions::svg_preview::OpenGallery;
+
+use crate::SvgPreviewSettings;
+use crate::animation;
+use crate::external_resources::{ExternalResources, OpenFiles};
+use crate::fonts::SvgFonts;
+use crate::gallery::{self, GalleryOptions};
+use crate::svg_preview_view::render_whole_svg;
+use crate::svgz::{self, is_svgz_path};
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, action: &OpenGallery, window, cx| {
+        if let Some(directory) = action.directory.clone() {
+            open_gallery(workspace, directory, window, cx);
+            return;
+        }
+        let directory = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some("Open SVG Gallery".into()),
+        });
+        cx.spawn_in(window, async move |workspace, cx| {
+            let Some(directory) = directory.await??.and_then(|paths| paths.into_iter().next())
+            else {
+                return Ok(());
+            };
+            workspace.update_in(cx, |workspace, window, cx| {
+                open_gallery(workspace, directory, window, cx)
+            })
+        })
+        .detach_and_log_err(cx);
+    });
+}
+
+/// Opens a gallery of the SVGs in `directory`, or activates the one that's already open.
+fn open_gallery(
+    workspace: &mut Workspace,
+    directory: PathBuf,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let existing = workspace
+        .items_of_type::<DirectoryGallery>(cx)
+        .find(|gallery| gallery.read(cx).directory == directory);
+    if let Some(gallery) = existing {
+        workspace.activate_item(&gallery, true, true, window, cx);
+        return;
+    }
+    let fs = workspace.app_state().fs.clone();
+    let workspace_handle = cx.weak_entity();
+    let gallery = cx.new(|cx| DirectoryGallery::new(directory, fs, workspace_handle, window, cx));
+    workspace.add_item_to_active_pane(Box::new(gallery), None, true, window, cx);
+}
+
+/// A grid of thumbnails of the SVGs in a directory, which open in an editor when clicked.
+pub struct DirectoryGallery {
+    focus_handle: FocusHandle,
+    directory: PathBuf,
+    workspace: WeakEntity<Workspace>,
+    fs: Arc<dyn Fs>,
+    /// The fonts that the SVGs are rendered with, once they're loaded.
+    fonts: Option<Arc<SvgFonts>>,
+    /// The SVGs in the directory, or `None` while they're being looked for.
+    entries: Option<Vec<GalleryEntry>>,
+    error: Option<SharedString>,
+    options: GalleryOptions,
+    /// Rendering stops when the gallery is closed, which drops this task.
+    _task: Task<()>,
+    _release_subscription: Subscription,
+}
+
+struct GalleryEntry {
+    path: PathBuf,
+    name: SharedString,
+    thumbnail: Thumbnail,
+}
+
+enum Thumbnail {
+    Loading,
+    Rendered(Arc<RenderImage>),
+    Failed(SharedString),
+}
+
+impl DirectoryGallery {
+    fn new(
+        directory: PathBuf,
+        fs: Arc<dyn Fs>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let settings = SvgPreviewSettings::get_global(cx);
+        let font_dirs = settings.font_dirs.clone();
+        let fallback_family = settings.fallback_font_family.clone();
+        let renderer = cx.svg_renderer();
+        let task = cx.spawn_in(window, {
+            let directory = directory.clone();
+            let fs = fs.clone();
+            async move |this, cx| {
+                let fonts = cx
+                    .background_spawn(async move {
+                        Arc::new(SvgFonts::load(&renderer, &font_dirs, fallback_family))
+                    })
+                    .await;
+                let files = svg_files(&fs, &directory).await;
+                this.update_in(cx, |this, window, cx| {
+                    this.fonts = Some(fonts);
+                    match files {
+                        Ok(files) => {
+                            this.entries = Some(
+                                files
+                                    .into_iter()
+                                    .map(|path| GalleryEntry {
+                                        name: path
+                                            .file_name()
+                                            .map(|name| name.to_string_lossy().into_owned())
+                                            .unwrap_or_default()
+                                            .into(),
+                                        path,
+                                        thumbnail: Thumbnail::Loading,
+                                    })
+                                    .collect(),
+                            );
+                            this.render_thumbnails(window, cx);
+                        }
+                        Err(error) => {
+                            this.entries = Some(Vec::new());
+                            this.error = Some(error.to_string().into());
+                        }
+                    }
+                    cx.notify();
+                })
+                .ok();
+            }
+        });
+        Self {
+            focus_handle: cx.focus_handle(),
+            directory,
+            workspace,
+            fs,
+            fonts: None,
+            entries: None,
+            error: None,
+            options: GalleryOptions::default(),
+            _task: task,
+            _release_subscription: cx.on_release_in(window, |this, window, _| {
+                for entry in this.entries.take().into_iter().flatten() {
+                    if let Thumbnail::Rendered(image) = entry.thumbnail {
+                        window.drop_image(image).ok();
+                    }
+                }
+            }),
+        }
+    }
+
+    /// Renders the thumbnails at the size of the tiles one after another in the background. The
+    /// previous thumbnails stay on screen until they're replaced.
+    fn render_thumbnails(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let (Some(entries), Some(fonts)) = (&self.entries, self.fonts.clone()) else {
+            return;
+        };
+        let paths = entries
+            .iter()
+            .map(|entry| entry.path.clone())
+            .collect::<Vec<_>>();
+        let fs = self.fs.clone();
+        let renderer = cx.svg_renderer();
+        let length = self.options.render_size(window);
+        self._task = cx.spawn_in(window, async move |this, cx| {
+            for (index, path) in paths.into_iter().enumerate() {
+                let result = render_thumbnail(
+                    &path,
+                    length,
+                    &fs,
+                    &renderer,
+                    &fonts,
+                    cx.background_executor(),
+                )
+                .await;
+                let thumbnail = match result {
+                    Ok(image) => Thumbnail::Rendered(image),
+                    Err(error) => Thumbnail::Failed(error.to_string().into()),
+                };
+                let updated = this.update_in(cx, |this, window, cx| {
+                    let Some(entry) = this
+                        .entries
+                        .as_mut()
+                        .and_then(|entries| entries.get_mut(index))
+                    else {
+                        return;
+                    };
+                    let previous = mem::replace(&mut entry.thumbnail, thumbnail);
+                    if let Thumbnail::Rendered(image) = previous {
+                        window.drop_image(image).ok();
+                    }
+                    cx.notify();
+                });
+                if updated.is_err() {
+                    return;
+                }
+            }
+        });
+    }
+
+    fn set_options(
+        &mut self,
+        options: GalleryOptions,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let size_changed = options.tile_size != self.options.tile_size;
+        self.options = options;
+        if size_changed {
+            self.render_thumbnails(window, cx);
+        }
+        cx.notify();
+    }
+
+    fn open_entry(&self, path: PathBuf, window: &mut Window, cx: &mut Context<Self>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_abs_path(path, OpenOptions::default(), window, cx)
+                    .detach_and_log_err(cx);
+            })
+            .ok();
+    }
+
+    fn render_entry(
+        &self,
+        index: usize,
+        entry: &GalleryEntry,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let path = entry.path.clone();
+        let thumbnail = match &entry.thumbnail {
+            Thumbnail::Loading => Icon::new(IconName::ArrowCircle)
+                .size(IconSize::Small)
+                .color(Color::Muted)
+                .into_any_element(),
+            Thumbnail::Rendered(image) => img(image.clone())
+                .size_full()
+                .object_fit(ObjectFit::Contain)
+                .into_any_element(),
+            Thumbnail::Failed(_) => Icon::new(IconName::Warning)
+                .size(IconSize::Small)
+                .color(Color::Error)
+                .into_any_element(),
+        };
+        let label_color = match entry.thumbnail {
+            Thumbnail::Failed(_) => Color::Error,
+            _ => Color::Muted,
+        };
+        gallery::render_tile(
+            ("gallery-entry", index),
+            thumbnail,
+            entry.name.clone(),
+            label_color,
+            &self.options,
+            cx,
+        )
+        .tooltip(Tooltip::text(match &entry.thumbnail {
+            Thumbnail::Failed(error) => format!("{}: {error}", entry.path.display()),
+            _ => entry.path.display().to_string(),
+        }))
+        .on_click(cx.listener(move |this, _, window, cx| this.open_entry(path.clone(), window, cx)))
+    }
+}
+
+/// Returns the SVGs and SVGZs directly in `directory`, sorted by path.
+async fn svg_files(fs: &Arc<dyn Fs>, directory: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut entries = fs.read_dir(directory).await?;
+    while let Some(entry) = entries.next().await {
+        let path = entry?;
+        if (is_svgz_path(&path)
+            || path
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("svg")))
+            && !fs.is_dir(&path).await
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Renders a thumbnail of the SVG at `path` whose longer side is `length` device pixels on a
+/// background thread.
+async fn render_thumbnail(
+    path: &Path,
+    length: f32,
+    fs: &Arc<dyn Fs>,
+    renderer: &SvgRenderer,
+    fonts: &Arc<SvgFonts>,
+    executor: &gpui::BackgroundExecutor,
+) -> Result<Arc<RenderImage>> {
+    let text = if is_svgz_path(path) {
+        let bytes = fs.load_bytes(path).await?;
+        executor
+            .spawn(async move { svgz::decompress(&bytes) })
+            .await?
+    } else {
+        fs.load(path).await?
+    };
+    let external_resources = {
+        let document = animation::parse_document(&text)?;
+        Arc::new(
+            ExternalResources::load(&document, path.parent(), Some(fs), &OpenFiles::default())
+                .await,
+        )
+    };
+    executor
+        .spawn({
+            let renderer = renderer.clone();
+            let fonts = fonts.clone();
+            async move {
+                let inlined = {
+                    let document = animation::parse_document(&text)?;
+                    external_resources.inline_external_uses(&text, &document)
+                };
+                let text = inlined.unwrap_or(text);
+                let options = external_resources.usvg_options(&renderer, Some(&fonts));
+                let tree = usvg::Tree::from_str(&text, &options)?;
+                let svg_size = size(tree.size().width(), tree.size().height());
+                Ok(render_whole_svg(&renderer, &tree, svg_size, length)?)
+            }
+        })
+        .await
+}
+
+impl EventEmitter<()> for DirectoryGallery {}
+
+impl Focusable for DirectoryGallery {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for DirectoryGallery {
+    type Event = ();
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        match self.directory.file_name() {
+            Some(name) => format!("SVGs in {}", name.to_string_lossy()).into(),
+            None => "SVG Gallery".into(),
+        }
+    }
+
+    fn tab_tooltip_text(&self, _cx: &App) -> Option<SharedString> {
+        Some(self.directory.display().to_string().into())
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Image))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("svg gallery: open")
+    }
+}
+
+impl Render for DirectoryGallery {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let message = match (&self.entries, &self.error) {
+            (_, Some(error)) => Some(Label::new(error.clone()).color(Color::Error)),
+            (None, None) => Some(Label::new("Looking for SVGs…").color(Color::Muted)),
+            (Some(entries), None) if entries.is_empty() => {
+                Some(Label::new("No SVGs in this folder").color(Color::Muted))
+            }
+            (Some(_), None) => None,
+        };
+        v_flex()
+            .id("svg-gallery")
+            .key_context("SvgGallery")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_4()
+            .gap_2()
+            .overflow_y_scroll()
+            .bg(self.options.background_color(cx))
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        Label::new(self.directory.display().to_string())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(gallery::render_controls(
+                        &self.options,
+                        cx.listener(|this, options: &GalleryOptions, window, cx| {
+                            this.set_options(options.clone(), window, cx)
+                        }),
+                        cx,
+                    )),
+            )
+            .children(message)
+            .child(
+                h_flex().flex_wrap().gap_2().children(
+                    self.entries
+                        .iter()
+                        .flatten()
+                        .enumerate()
+                        .map(|(index, entry)| self.render_entry(index, entry, cx))
+                        .collect::<Vec<_>>(),
+                ),
+            )
+    }
+}
@@ -0,0 +1,136 @@
+use std::ops::Range;
+
+use crate::animation;
+use crate::source_map;
+
+/// Returns `text` with every element hidden except the one with `id` and its descendants, by
+/// setting `visibility` on the root element and on the element. Returns `None` if `text` has no
+/// element with `id`.
+///
+/// Descendants of the element that set their own `visibility`, and elements whose style sheets
+/// set it, keep their visibility.
+pub fn isolate_element(text: &str, id: &str) -> Option<String> {
+    let document = animation::parse_document(text).ok()?;
+    let root = document.root_element();
+    let element = document
+        .descendants()
+        .find(|node| node.is_element() && node.attribute("id") == Some(id))?;
+    if element == root {
+        return Some(text.to_string());
+    }
+
+    let mut edits = [root, element]
+        .into_iter()
+        .zip(["hidden", "visible"])
+        .map(|(node, visibility)| {
+            match node
+                .attributes()
+                .find(|attribute| attribute.name() == "visibility")
+            {
+                Some(attribute) => Some((attribute.range_value(), visibility.to_string())),
+                None => {
+                    let name_end = source_map::tag_name_end(text, node.range().start)?;
+                    Some((name_end..name_end, format!(" visibility=\"{visibility}\"")))
+                }
+            }
+        })
+        .collect::<Option<Vec<(Range<usize>, String)>>>()?;
+    edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+
+    let mut text = text.to_string();
+    for (range, replacement) in edits {
+        text.replace_range(range, &replacement);
+    }
+    Some(text)
+}
+
+/// Returns the id of the topmost element with an id whose bounds contain `point`, given in the
+/// coordinates of the SVG's size.
+pub fn element_id_at(tree: &usvg::Tree, point: (f32, f32)) -> Option<String> {
+    let mut id = None;
+    find_element_at(tree.root(), point, &mut id);
+    id
+}
+
+fn find_element_at(group: &usvg::Group, (x, y): (f32, f32), id: &mut Option<String>) {
+    for node in group.children() {
+        let bounds = node.abs_stroke_bounding_box();
+        let contains =
+            x >= bounds.left() && x <= bounds.right() && y >= bounds.top() && y <= bounds.bottom();
+        if contains && !node.id().is_empty() {
+            *id = Some(node.id().to_string());
+        }
+        if let usvg::Node::Group(group) = node {
+            find_element_at(group, (x, y), id);
+        }
+    }
+}
+
+/// Parses a user-entered element to export: an id such as `arrow`, optionally followed by a
+/// scale such as `@2x`.
+pub fn parse_element_export(text: &str) -> Option<(&str, f32)> {
+    let text = text.trim();
+    let (id, scale) = match text.rsplit_once('@') {
+        Some((id, scale)) => {
+            let scale = scale
+                .trim()
+                .strip_suffix(['x', 'X'])?
+                .trim()
+                .parse::<f32>()
+                .ok()?;
+            (id.trim_end(), scale)
+        }
+        None => (text, 1.),
+    };
+    (!id.is_empty() && scale.is_finite() && scale > 0.).then_some((id, scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_isolate_element() {
+        let text = r#"<svg xmlns="http://www.w3.org/2000/svg"><g id="a" visibility="hidden"/><g id="b"/></svg>"#;
+        assert_eq!(
+            isolate_element(text, "a").as_deref(),
+            Some(
+                r#"<svg visibility="hidden" xmlns="http://www.w3.org/2000/svg"><g id="a" visibility="visible"/><g id="b"/></svg>"#
+            )
+        );
+        assert_eq!(
+            isolate_element(text, "b").as_deref(),
+            Some(
+                r#"<svg visibility="hidden" xmlns="http://www.w3.org/2000/svg"><g id="a" visibility="hidden"/><g visibility="visible" id="b"/></svg>"#
+            )
+        );
+        assert_eq!(isolate_element(text, "c"), None);
+    }
+
+    #[test]
+    fn test_element_id_at() {
+        let tree = usvg::Tree::from_str(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10">
+                <g id="group">
+                    <rect id="left" width="10" height="10"/>
+                    <rect x="10" width="10" height="10"/>
+                </g>
+            </svg>"#,
+            &usvg::Options::default(),
+        )
+        .unwrap();
+        assert_eq!(element_id_at(&tree, (5., 5.)).as_deref(), Some("left"));
+        assert_eq!(element_id_at(&tree, (15., 5.)).as_deref(), Some("group"));
+        assert_eq!(element_id_at(&tree, (25., 5.)), None);
+    }
+
+    #[test]
+    fn test_parse_element_export() {
+        assert_eq!(parse_element_export(" arrow "), Some(("arrow", 1.)));
+        assert_eq!(parse_element_export("arrow@2x"), Some(("arrow", 2.)));
+        assert_eq!(parse_element_export("arrow @ 1.5x"), Some(("arrow", 1.5)));
+        assert_eq!(parse_element_export("arrow@2"), None);
+        assert_eq!(parse_element_export("@2x"), None);
+        assert_eq!(parse_element_export(""), None);
+    }
+}
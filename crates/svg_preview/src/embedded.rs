@@ -0,0 +1,87 @@
+use std::ops::Range;
+
+/// Returns the range of the outermost `<svg>` element around `offset` in `text`, for previewing
+/// SVGs embedded in other languages, such as HTML, JSX or Vue templates. The range covers the
+/// element from the start of its start tag to the end of its end tag.
+pub fn svg_element_at(text: &str, offset: usize) -> Option<Range<usize>> {
+    svg_elements(text)
+        .into_iter()
+        .find(|range| range.start <= offset && offset <= range.end)
+}
+
+/// Returns the ranges of the `<svg>` elements in `text` that aren't nested in other ones.
+fn svg_elements(text: &str) -> Vec<Range<usize>> {
+    let mut elements = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut position = 0;
+    while let Some(tag_start) = text[position..].find('<').map(|index| position + index) {
+        let rest = &text[tag_start..];
+        let is_start_tag = is_tag(rest, "<svg");
+        if !is_start_tag && !is_tag(rest, "</svg") {
+            position = tag_start + 1;
+            continue;
+        }
+        let Some(tag_end) = rest.find('>').map(|index| tag_start + index + 1) else {
+            break;
+        };
+        if is_start_tag {
+            if depth == 0 {
+                start = tag_start;
+            }
+            if text[..tag_end].ends_with("/>") {
+                if depth == 0 {
+                    elements.push(start..tag_end);
+                }
+            } else {
+                depth += 1;
+            }
+        } else if depth > 0 {
+            depth -= 1;
+            if depth == 0 {
+                elements.push(start..tag_end);
+            }
+        }
+        position = tag_end;
+    }
+    elements
+}
+
+/// Whether `text` starts with a tag named like `tag`, as opposed to a tag whose name only starts
+/// the same, such as `<svgIcon>`.
+fn is_tag(text: &str, tag: &str) -> bool {
+    text.strip_prefix(tag).is_some_and(|rest| {
+        rest.chars()
+            .next()
+            .is_some_and(|next| next.is_whitespace() || next == '>' || next == '/')
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg_element_at() {
+        let text = r#"<div><svg viewBox="0 0 1 1"><svg><rect/></svg></svg><svgIcon/><svg/></div>"#;
+        let outer = text.find("<svg").unwrap()..text.find("<svgIcon").unwrap();
+        assert_eq!(svg_element_at(text, outer.start), Some(outer.clone()));
+        assert_eq!(
+            svg_element_at(text, text.find("<rect").unwrap()),
+            Some(outer.clone())
+        );
+        assert_eq!(svg_element_at(text, outer.end), Some(outer));
+        assert_eq!(
+            svg_element_at(text, text.find("<svgIcon").unwrap() + 1),
+            None
+        );
+
+        let self_closing = text.find("<svg/>").unwrap();
+        assert_eq!(
+            svg_element_at(text, self_closing + 2),
+            Some(self_closing..self_closing + "<svg/>".len())
+        );
+        assert_eq!(svg_element_at(text, 0), None);
+        assert_eq!(svg_element_at("<svg><rect/>", 2), None);
+    }
+}
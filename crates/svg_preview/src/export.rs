@@ -0,0 +1,228 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use fs::Fs;
+use gpui::{BackgroundExecutor, DevicePixels, RasterEncoding, Rgba, Size, SvgRenderer, size};
+
+use crate::{SvgPreviewExportFormat, SvgPreviewExportPreset, icon_containers, pdf};
+
+/// The quality of exported JPEGs unless another one is chosen.
+pub const DEFAULT_EXPORT_QUALITY: u8 = 90;
+
+/// Parses a user-entered export size for an SVG of `svg_size`: `1024x768`, a width such as `1024`
+/// for which the height keeps the aspect ratio, or a scale such as `2x` or `@2x`.
+pub fn parse_export_size(text: &str, svg_size: Size<f32>) -> Option<Size<DevicePixels>> {
+    let text = text.trim();
+    let parse = |text: &str| {
+        let value = text.trim().trim_end_matches("px").parse::<f32>().ok()?;
+        (value.is_finite() && value > 0.).then_some(value)
+    };
+    let (width, height) = if let Some(scale) = text
+        .trim_start_matches('@')
+        .strip_suffix(['x', 'X'])
+        .and_then(parse)
+    {
+        (svg_size.width * scale, svg_size.height * scale)
+    } else if let Some((width, height)) = text.split_once(['x', 'X', '×']) {
+        (parse(width)?, parse(height)?)
+    } else {
+        let width = parse(text)?;
+        (width, width * svg_size.height / svg_size.width)
+    };
+    let device_pixels = |length: f32| DevicePixels(length.round().max(1.) as i32);
+    Some(size(device_pixels(width), device_pixels(height)))
+}
+
+pub fn render_ico(renderer: &SvgRenderer, tree: &usvg::Tree) -> anyhow::Result<Vec<u8>> {
+    let images = icon_containers::ICO_SIZES
+        .iter()
+        .map(|&length| Ok((length, render_icon(renderer, tree, length)?)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(icon_containers::ico(&images))
+}
+
+pub fn render_icns(renderer: &SvgRenderer, tree: &usvg::Tree) -> anyhow::Result<Vec<u8>> {
+    let images = icon_containers::ICNS_SIZES
+        .iter()
+        .map(|&length| Ok((length, render_icon(renderer, tree, length)?)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(icon_containers::icns(&images))
+}
+
+/// Renders the file exported by `preset`, returning its contents and its size in pixels, which
+/// is the size of the SVG for formats that don't have a single size.
+pub fn render_preset(
+    renderer: &SvgRenderer,
+    tree: &usvg::Tree,
+    svg_size: Size<f32>,
+    preset: &SvgPreviewExportPreset,
+) -> anyhow::Result<(Vec<u8>, Size<DevicePixels>)> {
+    let encoding = raster_encoding(
+        preset.format,
+        preset.quality.unwrap_or(DEFAULT_EXPORT_QUALITY),
+    );
+    let export_size = match (&preset.size, encoding) {
+        (Some(text), Some(_)) => parse_export_size(text, svg_size)
+            .with_context(|| format!("Invalid size {text:?} in export preset {:?}", preset.name))?,
+        _ => size(
+            DevicePixels(svg_size.width.ceil() as i32),
+            DevicePixels(svg_size.height.ceil() as i32),
+        ),
+    };
+    let contents = match (encoding, preset.format) {
+        (Some(encoding), _) => {
+            let background = preset
+                .background
+                .as_deref()
+                .map(Rgba::try_from)
+                .transpose()?;
+            renderer.render_raster(tree, export_size, background, encoding)?
+        }
+        (None, SvgPreviewExportFormat::Ico) => render_ico(renderer, tree)?,
+        (None, SvgPreviewExportFormat::Icns) => render_icns(renderer, tree)?,
+        (None, _) => pdf::pdf(tree),
+    };
+    Ok((contents, export_size))
+}
+
+/// Returns how images of `format` are encoded, or `None` if it isn't a raster format.
+pub fn raster_encoding(format: SvgPreviewExportFormat, quality: u8) -> Option<RasterEncoding> {
+    match format {
+        SvgPreviewExportFormat::Png => Some(RasterEncoding::Png),
+        SvgPreviewExportFormat::Jpeg => Some(RasterEncoding::Jpeg { quality }),
+        SvgPreviewExportFormat::Webp => Some(RasterEncoding::WebP),
+        SvgPreviewExportFormat::Ico
+        | SvgPreviewExportFormat::Icns
+        | SvgPreviewExportFormat::Pdf => None,
+    }
+}
+
+pub fn export_extension(format: SvgPreviewExportFormat) -> &'static str {
+    match format {
+        SvgPreviewExportFormat::Png => "png",
+        SvgPreviewExportFormat::Jpeg => "jpg",
+        SvgPreviewExportFormat::Webp => "webp",
+        SvgPreviewExportFormat::Ico => "ico",
+        SvgPreviewExportFormat::Icns => "icns",
+        SvgPreviewExportFormat::Pdf => "pdf",
+    }
+}
+
+/// Writes an exported file to `path`, creating its directory if needed. Without a file system to
+/// write it with, it's written on a background thread so as not to block the foreground one.
+pub async fn write_export(
+    fs: Option<Arc<dyn Fs>>,
+    path: PathBuf,
+    contents: Vec<u8>,
+    executor: &BackgroundExecutor,
+) -> anyhow::Result<()> {
+    match fs {
+        Some(fs) => {
+            if let Some(parent) = path.parent() {
+                fs.create_dir(parent).await?;
+            }
+            fs.write(&path, &contents).await
+        }
+        None => {
+            executor
+                .spawn(async move {
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    anyhow::Ok(std::fs::write(&path, &contents)?)
+                })
+                .await
+        }
+    }
+}
+
+/// Returns the path that `preset` exports an SVG named `stem` in `directory` to.
+pub fn preset_output_path(
+    preset: &SvgPreviewExportPreset,
+    directory: &Path,
+    stem: &str,
+    export_size: Size<DevicePixels>,
+) -> PathBuf {
+    let extension = export_extension(preset.format);
+    let output = preset
+        .output
+        .as_deref()
+        .unwrap_or("{name}.{ext}")
+        .replace("{name}", stem)
+        .replace("{ext}", extension)
+        .replace("{width}", &export_size.width.0.to_string())
+        .replace("{height}", &export_size.height.0.to_string());
+    match output.strip_prefix("~/") {
+        Some(output) => util::paths::home_dir().join(output),
+        None => directory.join(output),
+    }
+}
+
+/// Renders an icon of `length` × `length` pixels, fitting the SVG into the square.
+fn render_icon(renderer: &SvgRenderer, tree: &usvg::Tree, length: u32) -> anyhow::Result<Vec<u8>> {
+    let length = DevicePixels(length as i32);
+    renderer.render_png_contained(tree, size(length, length))
+}
+
+/// Returns the size of the PNG exported at `scale` for an SVG of `svg_size`.
+pub fn scaled_export_size(svg_size: Size<f32>, scale: f32) -> Size<DevicePixels> {
+    size(
+        DevicePixels((svg_size.width * scale).round().max(1.) as i32),
+        DevicePixels((svg_size.height * scale).round().max(1.) as i32),
+    )
+}
+
+/// Returns the name of the PNG exported at `scale`, such as `icon@2x.png`.
+pub fn scaled_file_name(stem: &str, scale: f32) -> String {
+    format!("{stem}@{scale}x.png")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_output_path() {
+        let mut preset = SvgPreviewExportPreset {
+            name: "android".into(),
+            ..Default::default()
+        };
+        let export_size = size(DevicePixels(48), DevicePixels(32));
+        assert_eq!(
+            preset_output_path(&preset, Path::new("/icons"), "logo", export_size),
+            PathBuf::from("/icons/logo.png")
+        );
+
+        preset.format = SvgPreviewExportFormat::Pdf;
+        preset.output = Some("out/{name}_{width}x{height}.{ext}".into());
+        assert_eq!(
+            preset_output_path(&preset, Path::new("/icons"), "logo", export_size),
+            PathBuf::from("/icons/out/logo_48x32.pdf")
+        );
+
+        preset.output = Some("/assets/{name}.{ext}".into());
+        assert_eq!(
+            preset_output_path(&preset, Path::new("/icons"), "logo", export_size),
+            PathBuf::from("/assets/logo.pdf")
+        );
+    }
+
+    #[test]
+    fn test_parse_export_size() {
+        let svg_size = size(24., 12.);
+        let device_size = |width, height| Some(size(DevicePixels(width), DevicePixels(height)));
+        assert_eq!(parse_export_size("2x", svg_size), device_size(48, 24));
+        assert_eq!(parse_export_size("@3x", svg_size), device_size(72, 36));
+        assert_eq!(parse_export_size("100x50", svg_size), device_size(100, 50));
+        assert_eq!(parse_export_size("240", svg_size), device_size(240, 120));
+        assert_eq!(parse_export_size("-2x", svg_size), None);
+        assert_eq!(parse_export_size("big", svg_size), None);
+    }
+
+    #[test]
+    fn test_scaled_file_name() {
+        assert_eq!(scaled_file_name("icon", 1.), "icon@1x.png");
+        assert_eq!(scaled_file_name("icon", 1.5), "icon@1.5x.png");
+    }
+}
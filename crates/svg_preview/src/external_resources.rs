@@ -0,0 +1,418 @@
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use collections::HashMap;
+use fs::Fs;
+use gpui::SvgRenderer;
+
+use crate::fonts::SvgFonts;
+
+const XLINK_NS: &str = "http://www.w3.org/1999/xlink";
+
+/// The resources an SVG document references in other files, loaded relative to the document:
+/// the raster images of its `<image>` elements, its external stylesheets and the documents
+/// containing the targets of its `<use>` elements.
+#[derive(Default)]
+pub struct ExternalResources {
+    images: HashMap<String, Arc<Vec<u8>>>,
+    style_sheet: Option<String>,
+    /// The text of the documents referenced by `<use>` elements, keyed by the `href` without its
+    /// fragment.
+    use_documents: HashMap<String, String>,
+    /// The text files the document depends on, whose changes should re-render the document.
+    dependencies: Vec<PathBuf>,
+    /// The raster images the document references, which remote projects have to open to load.
+    image_files: Vec<PathBuf>,
+}
+
+/// The contents of files referenced by a document that are open in the project. They take
+/// precedence over the files on disk, and are the only way to load the files of remote projects.
+#[derive(Default)]
+pub struct OpenFiles {
+    pub texts: HashMap<PathBuf, String>,
+    pub images: HashMap<PathBuf, Arc<Vec<u8>>>,
+}
+
+impl ExternalResources {
+    /// Loads the resources referenced by `document`. Files are taken from `open_files` when
+    /// present there, so that unsaved changes are reflected, and read with `fs` otherwise.
+    pub async fn load(
+        document: &roxmltree::Document<'_>,
+        base_dir: Option<&Path>,
+        fs: Option<&Arc<dyn Fs>>,
+        open_files: &OpenFiles,
+    ) -> Self {
+        let mut this = Self::default();
+        let Some(base_dir) = base_dir else {
+            return this;
+        };
+
+        for href in image_hrefs(document) {
+            let Some(path) = resolve_href(base_dir, &href) else {
+                continue;
+            };
+            if let Some(bytes) = open_files.images.get(&path) {
+                this.images.insert(href, bytes.clone());
+            } else if let Some(fs) = fs
+                && let Ok(bytes) = fs.load_bytes(&path).await
+            {
+                this.images.insert(href, Arc::new(bytes));
+            }
+            this.image_files.push(path);
+        }
+
+        let mut style_sheets = Vec::new();
+        for href in style_sheet_hrefs(document) {
+            let Some(path) = resolve_href(base_dir, &href) else {
+                continue;
+            };
+            style_sheets.extend(load_text(fs, open_files, &path).await);
+            this.dependencies.push(path);
+        }
+        if !style_sheets.is_empty() {
+            this.style_sheet = Some(style_sheets.join("\n"));
+        }
+
+        for href in external_use_files(document) {
+            let Some(path) = resolve_href(base_dir, &href) else {
+                continue;
+            };
+            if let Some(text) = load_text(fs, open_files, &path).await {
+                this.use_documents.insert(href, text);
+            }
+            this.dependencies.push(path);
+        }
+
+        this
+    }
+
+    pub fn dependencies(&self) -> &[PathBuf] {
+        &self.dependencies
+    }
+
+    pub fn image_files(&self) -> &[PathBuf] {
+        &self.image_files
+    }
+
+    /// Returns the text of the document with the targets of `<use>` elements referencing other
+    /// documents copied into it, since the renderer only resolves references within a document.
+    /// Returns `None` if there's nothing to copy.
+    pub fn inline_external_uses(
+        &self,
+        text: &str,
+        document: &roxmltree::Document,
+    ) -> Option<String> {
+        let mut edits = Vec::new();
+        let mut defs = String::new();
+        let mut inlined_ids = HashMap::<&str, String>::default();
+        for node in document
+            .descendants()
+            .filter(|node| node.has_tag_name("use"))
+        {
+            let Some(href) = node.attributes().find(|attribute| {
+                attribute.name() == "href"
+                    && attribute
+                        .namespace()
+                        .is_none_or(|namespace| namespace == XLINK_NS)
+            }) else {
+                continue;
+            };
+            let Some((file, id)) = href.value().trim().split_once('#') else {
+                continue;
+            };
+            if file.is_empty() {
+                continue;
+            }
+            let inlined_id = if let Some(inlined_id) = inlined_ids.get(href.value()) {
+                inlined_id.clone()
+            } else {
+                let Some(source) = self.use_documents.get(file) else {
+                    continue;
+                };
+                let Ok(referenced) = roxmltree::Document::parse_with_options(
+                    source,
+                    roxmltree::ParsingOptions {
+                        allow_dtd: true,
+                        ..Default::default()
+                    },
+                ) else {
+                    continue;
+                };
+                let Some((element, id_attribute)) = referenced.descendants().find_map(|node| {
+                    let id_attribute = node.attribute_node("id")?;
+                    (id_attribute.value() == id).then_some((node, id_attribute))
+                }) else {
+                    continue;
+                };
+                let inlined_id = format!("external-use-{}", inlined_ids.len());
+                let element_range = element.range();
+                let id_range = id_attribute.range_value();
+                defs.push_str(&source[element_range.start..id_range.start]);
+                defs.push_str(&inlined_id);
+                defs.push_str(&source[id_range.end..element_range.end]);
+                inlined_ids.insert(href.value(), inlined_id.clone());
+                inlined_id
+            };
+            edits.push((href.range_value(), format!("#{inlined_id}")));
+        }
+        if edits.is_empty() {
+            return None;
+        }
+
+        // Insert the copied elements before the closing tag of the root element.
+        let root_range = document.root_element().range();
+        let closing_tag_start = root_range.start + text[root_range.clone()].rfind("</")?;
+        edits.push((
+            closing_tag_start..closing_tag_start,
+            format!("<defs xmlns:xlink=\"{XLINK_NS}\">{defs}</defs>"),
+        ));
+
+        let mut text = text.to_string();
+        edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+        for (range, replacement) in edits {
+            text.replace_range(range, &replacement);
+        }
+        Some(text)
+    }
+
+    /// Feeds the loaded images and stylesheets into `state`, to tell apart renderings of the same
+    /// document with different resources.
+    pub fn hash_contents(&self, state: &mut impl Hasher) {
+        let mut images = self.images.iter().collect::<Vec<_>>();
+        images.sort_by_key(|(href, _)| *href);
+        images.hash(state);
+        self.style_sheet.hash(state);
+    }
+
+    /// Returns options for parsing the document which provide the loaded resources and `fonts`
+    /// to the renderer.
+    pub fn usvg_options(
+        self: &Arc<Self>,
+        renderer: &SvgRenderer,
+        fonts: Option<&SvgFonts>,
+    ) -> usvg::Options<'static> {
+        let this = self.clone();
+        let mut options = renderer.usvg_options_with_href_resolver(Box::new(move |href, _| {
+            image_kind(this.images.get(href)?.clone())
+        }));
+        options.style_sheet = self.style_sheet.clone();
+        if let Some(fonts) = fonts {
+            fonts.apply(&mut options);
+        }
+        options
+    }
+}
+
+/// Returns the distinct `href`s of the `<image>` elements of `document` that aren't data URLs.
+fn image_hrefs(document: &roxmltree::Document) -> Vec<String> {
+    let mut hrefs = Vec::new();
+    for node in document.descendants() {
+        if !node.has_tag_name("image") {
+            continue;
+        }
+        let Some(href) = node
+            .attribute("href")
+            .or_else(|| node.attribute((XLINK_NS, "href")))
+        else {
+            continue;
+        };
+        let href = href.trim();
+        if !href.starts_with("data:") && !hrefs.iter().any(|existing| existing == href) {
+            hrefs.push(href.to_string());
+        }
+    }
+    hrefs
+}
+
+/// Returns the distinct files referenced by the `href`s of `<use>` elements of `document`, such
+/// as `icons.svg` for `<use href="icons.svg#gear"/>`.
+fn external_use_files(document: &roxmltree::Document) -> Vec<String> {
+    let mut files = Vec::new();
+    for node in document.descendants() {
+        if !node.has_tag_name("use") {
+            continue;
+        }
+        let Some(href) = node
+            .attribute("href")
+            .or_else(|| node.attribute((XLINK_NS, "href")))
+        else {
+            continue;
+        };
+        let Some((file, _)) = href.trim().split_once('#') else {
+            continue;
+        };
+        if !file.is_empty() && !files.iter().any(|existing| existing == file) {
+            files.push(file.to_string());
+        }
+    }
+    files
+}
+
+/// Loads a text file, preferring the contents of its open buffer from `open_files`.
+async fn load_text(
+    fs: Option<&Arc<dyn Fs>>,
+    open_files: &OpenFiles,
+    path: &Path,
+) -> Option<String> {
+    match open_files.texts.get(path) {
+        Some(text) => Some(text.clone()),
+        None => fs?.load(path).await.ok(),
+    }
+}
+
+/// Returns the `href`s of the stylesheets referenced by `<?xml-stylesheet?>` processing
+/// instructions and `<link rel="stylesheet">` elements of `document`, in document order.
+fn style_sheet_hrefs(document: &roxmltree::Document) -> Vec<String> {
+    let mut hrefs = Vec::new();
+    for node in document.descendants() {
+        let href = if let Some(pi) = node.pi() {
+            if pi.target != "xml-stylesheet" {
+                continue;
+            }
+            let value = pi.value.unwrap_or_default();
+            if pseudo_attribute(value, "type").is_some_and(|ty| ty != "text/css") {
+                continue;
+            }
+            pseudo_attribute(value, "href")
+        } else if node.has_tag_name("link")
+            && node.attribute("rel").is_some_and(|rel| {
+                rel.split_whitespace()
+                    .any(|rel| rel.eq_ignore_ascii_case("stylesheet"))
+            })
+        {
+            node.attribute("href")
+                .or_else(|| node.attribute((XLINK_NS, "href")))
+        } else {
+            continue;
+        };
+        if let Some(href) = href.map(str::trim).filter(|href| !href.is_empty()) {
+            hrefs.push(href.to_string());
+        }
+    }
+    hrefs
+}
+
+/// Returns the value of a pseudo-attribute of a processing instruction, such as the `href` of
+/// `<?xml-stylesheet href="style.css"?>`.
+fn pseudo_attribute<'a>(value: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = value;
+    loop {
+        rest = rest.trim_start();
+        let (key, after_key) = rest.split_once('=')?;
+        let after_key = after_key.trim_start();
+        let quote = after_key
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')?;
+        let (attribute_value, after_value) = after_key[1..].split_once(quote)?;
+        if key.trim() == name {
+            return Some(attribute_value);
+        }
+        rest = after_value;
+    }
+}
+
+/// Resolves an `href` against the directory containing the document, returning `None` for
+/// references to remote resources.
+fn resolve_href(base_dir: &Path, href: &str) -> Option<PathBuf> {
+    let path = if let Some(path) = href.strip_prefix("file://") {
+        path
+    } else if href.contains("://") {
+        return None;
+    } else {
+        href
+    };
+    let path = path.split(['?', '#']).next()?;
+    if path.is_empty() {
+        return None;
+    }
+    Some(base_dir.join(path))
+}
+
+fn image_kind(bytes: Arc<Vec<u8>>) -> Option<usvg::ImageKind> {
+    let kind = if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        usvg::ImageKind::PNG(bytes)
+    } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+        usvg::ImageKind::JPEG(bytes)
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        usvg::ImageKind::GIF(bytes)
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        usvg::ImageKind::WEBP(bytes)
+    } else {
+        return None;
+    };
+    Some(kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_hrefs() {
+        let text = r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
+            <image href="logo.png"/>
+            <image xlink:href="images/photo.jpg"/>
+            <image href="logo.png"/>
+            <image href="data:image/png;base64,AAAA"/>
+        </svg>"#;
+        let document = roxmltree::Document::parse(text).unwrap();
+        assert_eq!(image_hrefs(&document), ["logo.png", "images/photo.jpg"]);
+    }
+
+    #[test]
+    fn test_style_sheet_hrefs() {
+        let text = r#"<?xml-stylesheet type="text/css" href="base.css"?>
+            <?xml-stylesheet href='theme.css'?>
+            <?xml-stylesheet type="text/xsl" href="transform.xsl"?>
+            <svg xmlns="http://www.w3.org/2000/svg">
+                <link xmlns="http://www.w3.org/1999/xhtml" rel="stylesheet" href="icons.css"/>
+                <link rel="icon" href="favicon.png"/>
+            </svg>"#;
+        let document = roxmltree::Document::parse(text).unwrap();
+        assert_eq!(
+            style_sheet_hrefs(&document),
+            ["base.css", "theme.css", "icons.css"]
+        );
+    }
+
+    #[test]
+    fn test_inline_external_uses() {
+        let text = r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink"><use href="icons.svg#gear"/><use xlink:href="icons.svg#gear" x="10"/><use href="#local"/></svg>"##;
+        let document = roxmltree::Document::parse(text).unwrap();
+        assert_eq!(external_use_files(&document), ["icons.svg"]);
+
+        let mut resources = ExternalResources::default();
+        resources.use_documents.insert(
+            "icons.svg".into(),
+            r#"<svg xmlns="http://www.w3.org/2000/svg"><symbol id="gear"><circle r="5"/></symbol></svg>"#.into(),
+        );
+        assert_eq!(
+            resources.inline_external_uses(text, &document).as_deref(),
+            Some(concat!(
+                r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">"##,
+                r##"<use href="#external-use-0"/><use xlink:href="#external-use-0" x="10"/><use href="#local"/>"##,
+                r##"<defs xmlns:xlink="http://www.w3.org/1999/xlink"><symbol id="external-use-0"><circle r="5"/></symbol></defs></svg>"##,
+            ))
+        );
+    }
+
+    #[test]
+    fn test_resolve_href() {
+        let base_dir = Path::new("/project/assets");
+        assert_eq!(
+            resolve_href(base_dir, "logo.png"),
+            Some(PathBuf::from("/project/assets/logo.png"))
+        );
+        assert_eq!(
+            resolve_href(base_dir, "../images/photo.jpg#frame"),
+            Some(PathBuf::from("/project/assets/../images/photo.jpg"))
+        );
+        assert_eq!(
+            resolve_href(base_dir, "file:///images/photo.jpg"),
+            Some(PathBuf::from("/images/photo.jpg"))
+        );
+        assert_eq!(resolve_href(base_dir, "https://zed.dev/logo.png"), None);
+    }
+}
@@ -0,0 +1,66 @@
+use gpui::Rgba;
+
+/// Formats a color as a `#rrggbb` hex string, or `#rrggbbaa` if it's translucent.
+pub fn hex_rgba(color: Rgba) -> String {
+    let channel = |value: f32| (value * 255.).round() as u8;
+    let hex = format!(
+        "#{:02x}{:02x}{:02x}",
+        channel(color.r),
+        channel(color.g),
+        channel(color.b)
+    );
+    match channel(color.a) {
+        255 => hex,
+        alpha => format!("{hex}{alpha:02x}"),
+    }
+}
+
+/// Returns which of the paints of `path`, `"fill"` or `"stroke"`, is the solid color `color`,
+/// ignoring its alpha since opacity and antialiasing only change that.
+pub fn paint_with_color(path: &usvg::Path, color: Rgba) -> Option<&'static str> {
+    let channel = |value: f32| (value * 255.).round() as u8;
+    let matches = |paint: &usvg::Paint| match paint {
+        usvg::Paint::Color(paint) => {
+            (paint.red, paint.green, paint.blue)
+                == (channel(color.r), channel(color.g), channel(color.b))
+        }
+        _ => false,
+    };
+    if path.fill().is_some_and(|fill| matches(fill.paint())) {
+        Some("fill")
+    } else if path.stroke().is_some_and(|stroke| matches(stroke.paint())) {
+        Some("stroke")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_rgba() {
+        let color = |r, g, b, a| Rgba { r, g, b, a };
+        assert_eq!(hex_rgba(color(1., 0.5, 0., 1.)), "#ff8000");
+        assert_eq!(hex_rgba(color(0., 0., 1., 0.5)), "#0000ff80");
+    }
+
+    #[test]
+    fn test_paint_with_color() {
+        let tree = usvg::Tree::from_str(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10">
+                <rect width="10" height="10" fill="#ff0000" stroke="#0000ff"/>
+            </svg>"##,
+            &usvg::Options::default(),
+        )
+        .unwrap();
+        let Some(usvg::Node::Path(path)) = tree.root().children().first() else {
+            panic!("expected a path");
+        };
+        let color = |r, g, b| Rgba { r, g, b, a: 0.5 };
+        assert_eq!(paint_with_color(path, color(1., 0., 0.)), Some("fill"));
+        assert_eq!(paint_with_color(path, color(0., 0., 1.)), Some("stroke"));
+        assert_eq!(paint_with_color(path, color(0., 1., 0.)), None);
+    }
+}
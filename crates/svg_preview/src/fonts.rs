@@ -0,0 +1,48 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use gpui::SvgRenderer;
+use usvg::fontdb;
+
+/// The fonts that text in the previewed SVG is rendered with: the system fonts, the fonts in the
+/// configured font directories and the fallback family.
+pub struct SvgFonts {
+    fallback_family: Option<String>,
+    database: Arc<fontdb::Database>,
+}
+
+impl SvgFonts {
+    /// Loads the fonts in `font_dirs`, which reads every font file in them, so this should be
+    /// called in the background.
+    pub fn load(
+        renderer: &SvgRenderer,
+        font_dirs: &[PathBuf],
+        fallback_family: Option<String>,
+    ) -> Self {
+        let system_fonts = renderer.system_fonts();
+        let database = if font_dirs.is_empty() && fallback_family.is_none() {
+            system_fonts
+        } else {
+            let mut database = (*system_fonts).clone();
+            for dir in font_dirs {
+                database.load_fonts_dir(dir);
+            }
+            // Text whose fonts can't be found falls back to the serif family.
+            if let Some(family) = &fallback_family {
+                database.set_serif_family(family.clone());
+            }
+            Arc::new(database)
+        };
+        Self {
+            fallback_family,
+            database,
+        }
+    }
+
+    pub fn apply(&self, options: &mut usvg::Options) {
+        options.fontdb = self.database.clone();
+        if let Some(family) = &self.fallback_family {
+            options.font_family = family.clone();
+        }
+    }
+}
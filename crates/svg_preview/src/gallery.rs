@@ -0,0 +1,228 @@
+use std::rc::Rc;
+
+use gpui::{App, ElementId, Hsla, Stateful, Window};
+use settings::Settings;
+use ui::{ButtonLike, Tooltip, prelude::*};
+
+use crate::svg_preview_view::resolve_background;
+use crate::{SvgPreviewBackground, SvgPreviewSettings};
+
+/// The sizes that the thumbnails of the galleries step through, from actual size icons to large
+/// details.
+const TILE_SIZES: [f32; 9] = [16., 24., 32., 48., 64., 96., 128., 192., 256.];
+const DEFAULT_TILE_SIZE: f32 = 64.;
+/// The narrowest a tile gets when its label is shown, so that the label stays readable.
+const MIN_LABELED_TILE_WIDTH: f32 = 72.;
+
+/// How the tiles of the sprite and directory galleries are shown.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GalleryOptions {
+    /// The size of the thumbnails in pixels, one of [`TILE_SIZES`].
+    pub tile_size: f32,
+    pub show_labels: bool,
+    /// A background chosen for the gallery, taking precedence over the settings.
+    pub background: Option<SvgPreviewBackground>,
+}
+
+impl Default for GalleryOptions {
+    fn default() -> Self {
+        Self {
+            tile_size: DEFAULT_TILE_SIZE,
+            show_labels: true,
+            background: None,
+        }
+    }
+}
+
+impl GalleryOptions {
+    /// The size in device pixels that thumbnails are rendered at, so that they're shown at their
+    /// actual size rather than scaled.
+    pub fn render_size(&self, window: &Window) -> f32 {
+        self.tile_size * window.scale_factor()
+    }
+
+    pub fn background_color(&self, cx: &App) -> Hsla {
+        match &self.background {
+            Some(background) => resolve_background(background, cx),
+            None => resolve_background(&SvgPreviewSettings::get_global(cx).background, cx),
+        }
+    }
+
+    fn smaller(&self) -> Option<Self> {
+        let tile_size = TILE_SIZES
+            .into_iter()
+            .rev()
+            .find(|size| *size < self.tile_size)?;
+        Some(Self {
+            tile_size,
+            ..self.clone()
+        })
+    }
+
+    fn larger(&self) -> Option<Self> {
+        let tile_size = TILE_SIZES.into_iter().find(|size| *size > self.tile_size)?;
+        Some(Self {
+            tile_size,
+            ..self.clone()
+        })
+    }
+
+    /// Cycles the background between the editor background, white and black.
+    fn next_background(&self, cx: &App) -> Self {
+        let current = self
+            .background
+            .as_ref()
+            .unwrap_or(&SvgPreviewSettings::get_global(cx).background);
+        let background = match current {
+            SvgPreviewBackground::Theme => SvgPreviewBackground::White,
+            SvgPreviewBackground::White => SvgPreviewBackground::Black,
+            SvgPreviewBackground::Black | SvgPreviewBackground::Custom(_) => {
+                SvgPreviewBackground::Theme
+            }
+        };
+        Self {
+            background: Some(background),
+            ..self.clone()
+        }
+    }
+}
+
+/// Renders a tile showing `thumbnail` at the chosen size, with `label` below it if labels are
+/// shown.
+pub fn render_tile(
+    id: impl Into<ElementId>,
+    thumbnail: impl IntoElement,
+    label: SharedString,
+    label_color: Color,
+    options: &GalleryOptions,
+    cx: &App,
+) -> Stateful<Div> {
+    let colors = cx.theme().colors();
+    v_flex()
+        .id(id)
+        .p_1()
+        .gap_1()
+        .items_center()
+        .rounded_sm()
+        .border_1()
+        .border_color(colors.border_variant)
+        .cursor_pointer()
+        .hover(|style| style.border_color(colors.border_focused))
+        .child(
+            div()
+                .size(px(options.tile_size))
+                .flex()
+                .items_center()
+                .justify_center()
+                .child(thumbnail),
+        )
+        .when(options.show_labels, |this| {
+            this.child(
+                h_flex()
+                    .w(px(options.tile_size.max(MIN_LABELED_TILE_WIDTH)))
+                    .justify_center()
+                    .child(
+                        Label::new(label)
+                            .size(LabelSize::XSmall)
+                            .color(label_color)
+                            .truncate(),
+                    ),
+            )
+        })
+}
+
+/// Renders the controls for the size of the tiles, their labels and the background, calling
+/// `on_change` with the changed options.
+pub fn render_controls(
+    options: &GalleryOptions,
+    on_change: impl Fn(&GalleryOptions, &mut Window, &mut App) + 'static,
+    cx: &App,
+) -> impl IntoElement {
+    let on_change = Rc::new(on_change);
+    let smaller = options.smaller();
+    let larger = options.larger();
+    let next_background = options.next_background(cx);
+    let toggled_labels = GalleryOptions {
+        show_labels: !options.show_labels,
+        ..options.clone()
+    };
+
+    h_flex()
+        .gap_1()
+        .child(
+            IconButton::new("gallery-smaller", IconName::Dash)
+                .icon_size(IconSize::Small)
+                .disabled(smaller.is_none())
+                .tooltip(Tooltip::text("Smaller Thumbnails"))
+                .on_click({
+                    let on_change = on_change.clone();
+                    move |_, window, cx| {
+                        if let Some(smaller) = &smaller {
+                            on_change(smaller, window, cx);
+                        }
+                    }
+                }),
+        )
+        .child(
+            Label::new(format!("{}px", options.tile_size))
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+        )
+        .child(
+            IconButton::new("gallery-larger", IconName::Plus)
+                .icon_size(IconSize::Small)
+                .disabled(larger.is_none())
+                .tooltip(Tooltip::text("Larger Thumbnails"))
+                .on_click({
+                    let on_change = on_change.clone();
+                    move |_, window, cx| {
+                        if let Some(larger) = &larger {
+                            on_change(larger, window, cx);
+                        }
+                    }
+                }),
+        )
+        .child(
+            IconButton::new("gallery-labels", IconName::CaseSensitive)
+                .icon_size(IconSize::Small)
+                .toggle_state(options.show_labels)
+                .tooltip(Tooltip::text("Show Names"))
+                .on_click({
+                    let on_change = on_change.clone();
+                    move |_, window, cx| on_change(&toggled_labels, window, cx)
+                }),
+        )
+        .child(
+            ButtonLike::new("gallery-background")
+                .tooltip(Tooltip::text("Background"))
+                .on_click(move |_, window, cx| on_change(&next_background, window, cx))
+                .child(
+                    div()
+                        .size_3()
+                        .rounded_sm()
+                        .border_1()
+                        .border_color(cx.theme().colors().border)
+                        .bg(options.background_color(cx)),
+                ),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tile_size_steps() {
+        let options = |tile_size| GalleryOptions {
+            tile_size,
+            ..GalleryOptions::default()
+        };
+        assert_eq!(options(64.).smaller().unwrap().tile_size, 48.);
+        assert_eq!(options(64.).larger().unwrap().tile_size, 96.);
+        assert_eq!(options(16.).smaller(), None);
+        assert_eq!(options(256.).larger(), None);
+        // Sizes between the steps snap to the nearest step in the direction of the change.
+        assert_eq!(options(100.).smaller().unwrap().tile_size, 96.);
+        assert_eq!(options(100.).larger().unwrap().tile_size, 128.);
+    }
+}
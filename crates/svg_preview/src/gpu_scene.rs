@@ -0,0 +1,484 @@
+use gpui::{
+    Background, Bounds, Hsla, Path, Pixels, Point, Rgba, Window, linear_color_stop,
+    linear_gradient, point, px, size,
+};
+use lyon::tessellation::{BuffersBuilder, FillOptions, FillTessellator, FillVertex, VertexBuffers};
+use usvg::tiny_skia_path::{self, PathSegment, PathStroker};
+use usvg::{BlendMode, FillRule, Group, Node, Paint, PaintOrder, SpreadMethod, Transform, Tree};
+
+/// How far the tessellated outlines may deviate from the SVG's curves, in device pixels.
+const TOLERANCE: f32 = 0.1;
+
+/// An SVG tessellated into triangles, which the GPU fills at any zoom level without rasterizing
+/// the SVG again.
+///
+/// Only what the GPU can draw path by path is tessellated: paths and text with solid fills and
+/// strokes, and linear gradients with up to two stops along the x or y axis. SVGs using anything
+/// else, such as filters, masks, clip paths, group opacity, images, patterns or radial gradients,
+/// have to be rasterized on the CPU.
+pub struct GpuScene {
+    shapes: Vec<Shape>,
+    /// The number of device pixels per SVG pixel that the curves were flattened for.
+    pub scale: f32,
+}
+
+/// A fill or stroke of a path.
+struct Shape {
+    /// The triangles covering the shape, in SVG pixels.
+    triangles: Vec<[Point<f32>; 3]>,
+    /// The bounds of the triangles, in SVG pixels.
+    bounds: Bounds<f32>,
+    paint: ShapePaint,
+}
+
+enum ShapePaint {
+    Color(Hsla),
+    /// A gradient between two colors at two points, in SVG pixels, that lie on a line parallel to
+    /// the x axis, or to the y axis if it's `vertical`.
+    AxialGradient {
+        start: (Point<f32>, Hsla),
+        end: (Point<f32>, Hsla),
+        vertical: bool,
+    },
+}
+
+impl GpuScene {
+    /// Tessellates `tree`, flattening its curves finely enough for `scale` device pixels per SVG
+    /// pixel. Returns `None` if the SVG uses features that can't be drawn on the GPU.
+    pub fn new(tree: &Tree, scale: f32) -> Option<Self> {
+        let mut tessellator = SceneTessellator {
+            shapes: Vec::new(),
+            scale,
+            fill_tessellator: FillTessellator::new(),
+        };
+        tessellator.group(tree.root())?;
+        Some(Self {
+            shapes: tessellator.shapes,
+            scale,
+        })
+    }
+
+    /// Paints the scene with the SVG's top left corner at `origin` and `scale` pixels per SVG
+    /// pixel, skipping the shapes outside of `visible`.
+    pub fn paint(
+        &self,
+        origin: Point<Pixels>,
+        scale: f32,
+        visible: Bounds<Pixels>,
+        window: &mut Window,
+    ) {
+        let map = |point: Point<f32>| {
+            gpui::point(
+                origin.x + px(point.x * scale),
+                origin.y + px(point.y * scale),
+            )
+        };
+        for shape in &self.shapes {
+            let bounds = Bounds::new(
+                map(shape.bounds.origin),
+                shape.bounds.size.map(|length| px(length * scale)),
+            );
+            let Some(first) = shape.triangles.first() else {
+                continue;
+            };
+            if !bounds.intersects(&visible) {
+                continue;
+            }
+            let mut path = Path::new(map(first[0]));
+            for [a, b, c] in &shape.triangles {
+                path.push_triangle(
+                    (map(*a), map(*b), map(*c)),
+                    (point(0., 1.), point(0., 1.), point(0., 1.)),
+                );
+            }
+            window.paint_path(path, shape.paint.background(bounds, &map));
+        }
+    }
+}
+
+impl ShapePaint {
+    /// Returns the background that paints a shape within `bounds`, where `map` maps SVG pixels to
+    /// the window.
+    fn background(
+        &self,
+        bounds: Bounds<Pixels>,
+        map: &impl Fn(Point<f32>) -> Point<Pixels>,
+    ) -> Background {
+        match self {
+            ShapePaint::Color(color) => (*color).into(),
+            ShapePaint::AxialGradient {
+                start: (start, start_color),
+                end: (end, end_color),
+                vertical,
+            } => {
+                let (start, end) = (map(*start), map(*end));
+                // Gradients run across the bounds of what they paint, at 90 degrees from left to
+                // right and at 180 degrees from top to bottom.
+                let (angle, position, length, start, end) = if *vertical {
+                    (180., bounds.origin.y, bounds.size.height, start.y, end.y)
+                } else {
+                    (90., bounds.origin.x, bounds.size.width, start.x, end.x)
+                };
+                if length <= px(0.) {
+                    return (*start_color).into();
+                }
+                linear_gradient(
+                    angle,
+                    linear_color_stop(*start_color, (start - position) / length),
+                    linear_color_stop(*end_color, (end - position) / length),
+                )
+            }
+        }
+    }
+}
+
+struct SceneTessellator {
+    shapes: Vec<Shape>,
+    scale: f32,
+    fill_tessellator: FillTessellator,
+}
+
+impl SceneTessellator {
+    fn group(&mut self, group: &Group) -> Option<()> {
+        // These apply to the group's content as a whole, which can't be drawn path by path,
+        // except for the opacity of a single shape, such as an element with an `opacity`.
+        let opacity = group.opacity().get();
+        let is_single_shape = matches!(
+            group.children(),
+            [Node::Path(path)] if path.fill().is_none() || path.stroke().is_none()
+        );
+        if (opacity < 1. && !is_single_shape)
+            || group.blend_mode() != BlendMode::Normal
+            || group.clip_path().is_some()
+            || group.mask().is_some()
+            || !group.filters().is_empty()
+        {
+            return None;
+        }
+        for node in group.children() {
+            match node {
+                Node::Group(group) => self.group(group)?,
+                Node::Path(path) => self.path(path, opacity)?,
+                Node::Text(text) => self.group(text.flattened())?,
+                Node::Image(_) => return None,
+            }
+        }
+        Some(())
+    }
+
+    fn path(&mut self, path: &usvg::Path, opacity: f32) -> Option<()> {
+        if !path.is_visible() {
+            return Some(());
+        }
+        match path.paint_order() {
+            PaintOrder::FillAndStroke => {
+                self.fill(path, opacity)?;
+                self.stroke(path, opacity)
+            }
+            PaintOrder::StrokeAndFill => {
+                self.stroke(path, opacity)?;
+                self.fill(path, opacity)
+            }
+        }
+    }
+
+    fn fill(&mut self, path: &usvg::Path, opacity: f32) -> Option<()> {
+        let Some(fill) = path.fill() else {
+            return Some(());
+        };
+        let transform = path.abs_transform();
+        let paint = shape_paint(fill.paint(), opacity * fill.opacity().get(), transform)?;
+        let rule = match fill.rule() {
+            FillRule::NonZero => lyon::tessellation::FillRule::NonZero,
+            FillRule::EvenOdd => lyon::tessellation::FillRule::EvenOdd,
+        };
+        let Some(data) = path.data().clone().transform(transform) else {
+            return Some(());
+        };
+        self.push_shape(&data, rule, paint)
+    }
+
+    fn stroke(&mut self, path: &usvg::Path, opacity: f32) -> Option<()> {
+        let Some(stroke) = path.stroke() else {
+            return Some(());
+        };
+        let transform = path.abs_transform();
+        let paint = shape_paint(stroke.paint(), opacity * stroke.opacity().get(), transform)?;
+        // The stroke is outlined in the path's own coordinates, where its width applies, precisely
+        // enough for the scale it ends up at.
+        let resolution_scale = PathStroker::compute_resolution_scale(&transform) * self.scale;
+        let stroke = stroke.to_tiny_skia();
+        let dashed = match &stroke.dash {
+            Some(dash) => path.data().dash(dash, resolution_scale),
+            None => None,
+        };
+        let Some(outline) = dashed
+            .as_ref()
+            .unwrap_or(path.data())
+            .stroke(&stroke, resolution_scale)
+            .and_then(|outline| outline.transform(transform))
+        else {
+            return Some(());
+        };
+        self.push_shape(&outline, lyon::tessellation::FillRule::NonZero, paint)
+    }
+
+    fn push_shape(
+        &mut self,
+        data: &tiny_skia_path::Path,
+        rule: lyon::tessellation::FillRule,
+        paint: ShapePaint,
+    ) -> Option<()> {
+        let to_lyon = |point: tiny_skia_path::Point| lyon::math::point(point.x, point.y);
+        let mut builder = lyon::path::Path::builder();
+        let mut open = false;
+        for segment in data.segments() {
+            match segment {
+                PathSegment::MoveTo(point) => {
+                    if open {
+                        builder.end(false);
+                    }
+                    builder.begin(to_lyon(point));
+                    open = true;
+                }
+                PathSegment::LineTo(point) => {
+                    builder.line_to(to_lyon(point));
+                }
+                PathSegment::QuadTo(control, point) => {
+                    builder.quadratic_bezier_to(to_lyon(control), to_lyon(point));
+                }
+                PathSegment::CubicTo(control1, control2, point) => {
+                    builder.cubic_bezier_to(to_lyon(control1), to_lyon(control2), to_lyon(point));
+                }
+                PathSegment::Close => {
+                    if open {
+                        builder.end(true);
+                        open = false;
+                    }
+                }
+            }
+        }
+        if open {
+            builder.end(false);
+        }
+
+        let mut buffers: VertexBuffers<lyon::math::Point, u32> = VertexBuffers::new();
+        let options = FillOptions::tolerance(TOLERANCE / self.scale).with_fill_rule(rule);
+        self.fill_tessellator
+            .tessellate_path(
+                &builder.build(),
+                &options,
+                &mut BuffersBuilder::new(&mut buffers, |vertex: FillVertex| vertex.position()),
+            )
+            .ok()?;
+
+        let vertex = |index: &u32| {
+            let vertex = buffers.vertices[*index as usize];
+            point(vertex.x, vertex.y)
+        };
+        let triangles = buffers
+            .indices
+            .chunks_exact(3)
+            .map(|indices| {
+                [
+                    vertex(&indices[0]),
+                    vertex(&indices[1]),
+                    vertex(&indices[2]),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let Some(bounds) = triangle_bounds(&triangles) else {
+            return Some(());
+        };
+        self.shapes.push(Shape {
+            triangles,
+            bounds,
+            paint,
+        });
+        Some(())
+    }
+}
+
+/// Returns how to paint a shape with `paint` at `opacity`, where `transform` maps the user space
+/// of its path to SVG pixels, or `None` if the GPU can't draw it.
+fn shape_paint(paint: &Paint, opacity: f32, transform: Transform) -> Option<ShapePaint> {
+    let gradient = match paint {
+        Paint::Color(color) => return Some(ShapePaint::Color(hsla(*color, opacity))),
+        Paint::LinearGradient(gradient) => gradient,
+        Paint::RadialGradient(_) | Paint::Pattern(_) => return None,
+    };
+    let (start_stop, end_stop) = match gradient.stops() {
+        [stop] => {
+            let color = hsla(stop.color(), opacity * stop.opacity().get());
+            return Some(ShapePaint::Color(color));
+        }
+        [start, end] => (start, end),
+        _ => return None,
+    };
+    if gradient.spread_method() != SpreadMethod::Pad {
+        return None;
+    }
+    // Gradients are drawn along the x or y axis of the window, so the gradient's line has to stay
+    // parallel to one in SVG pixels.
+    let transform = transform.pre_concat(gradient.transform());
+    if transform.kx != 0. || transform.ky != 0. {
+        return None;
+    }
+    let map = |x: f32, y: f32| {
+        let mut point = tiny_skia_path::Point::from_xy(x, y);
+        transform.map_point(&mut point);
+        point
+    };
+    let (from, to) = (
+        map(gradient.x1(), gradient.y1()),
+        map(gradient.x2(), gradient.y2()),
+    );
+    let vertical = from.x == to.x;
+    if from == to || (!vertical && from.y != to.y) {
+        return None;
+    }
+    let stop = |stop: &usvg::Stop| {
+        let offset = stop.offset().get();
+        (
+            point(
+                from.x + (to.x - from.x) * offset,
+                from.y + (to.y - from.y) * offset,
+            ),
+            hsla(stop.color(), opacity * stop.opacity().get()),
+        )
+    };
+    Some(ShapePaint::AxialGradient {
+        start: stop(start_stop),
+        end: stop(end_stop),
+        vertical,
+    })
+}
+
+fn hsla(color: usvg::Color, opacity: f32) -> Hsla {
+    Rgba {
+        r: color.red as f32 / 255.,
+        g: color.green as f32 / 255.,
+        b: color.blue as f32 / 255.,
+        a: opacity,
+    }
+    .into()
+}
+
+fn triangle_bounds(triangles: &[[Point<f32>; 3]]) -> Option<Bounds<f32>> {
+    let mut points = triangles.iter().flatten();
+    let first = *points.next()?;
+    let (min, max) = points.fold((first, first), |(min, max), point| {
+        (min.min(point), max.max(point))
+    });
+    Some(Bounds::new(min, size(max.x - min.x, max.y - min.y)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gpu_scene(svg: &str) -> Option<GpuScene> {
+        let tree = Tree::from_str(svg, &usvg::Options::default()).unwrap();
+        GpuScene::new(&tree, 1.)
+    }
+
+    #[test]
+    fn test_gpu_scene() {
+        let scene = gpu_scene(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10">
+                <rect width="10" height="10" fill="red" fill-opacity="0.5"/>
+                <path d="M10 0 L20 10" fill="none" stroke="blue" stroke-width="2"/>
+            </svg>"#,
+        )
+        .unwrap();
+        assert_eq!(scene.shapes.len(), 2);
+
+        let rect = &scene.shapes[0];
+        assert_eq!(rect.bounds, Bounds::new(point(0., 0.), size(10., 10.)));
+        let area = rect
+            .triangles
+            .iter()
+            .map(|[a, b, c]| ((b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)).abs() / 2.)
+            .sum::<f32>();
+        assert!((area - 100.).abs() < 0.01);
+        let ShapePaint::Color(color) = rect.paint else {
+            panic!("expected a solid color");
+        };
+        assert_eq!(Rgba::from(color).a, 0.5);
+
+        // The stroke is outlined, so it extends beyond the line by half its width.
+        let stroke = &scene.shapes[1];
+        assert!(stroke.bounds.origin.x < 10.);
+        assert!(stroke.bounds.origin.x + stroke.bounds.size.width > 20.);
+
+        // The opacity of a single shape is applied to its paint.
+        let scene = gpu_scene(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10">
+                <rect width="10" height="10" opacity="0.5"/>
+            </svg>"#,
+        )
+        .unwrap();
+        let ShapePaint::Color(color) = scene.shapes[0].paint else {
+            panic!("expected a solid color");
+        };
+        assert_eq!(Rgba::from(color).a, 0.5);
+    }
+
+    #[test]
+    fn test_gpu_scene_gradients() {
+        let scene = gpu_scene(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10">
+                <linearGradient id="g" x1="0" y1="0" x2="0" y2="1">
+                    <stop offset="0.25" stop-color="red"/>
+                    <stop offset="1" stop-color="blue"/>
+                </linearGradient>
+                <rect y="2" width="10" height="8" fill="url(#g)"/>
+            </svg>"#,
+        )
+        .unwrap();
+        let ShapePaint::AxialGradient {
+            start: (start, _),
+            end: (end, _),
+            vertical,
+        } = scene.shapes[0].paint
+        else {
+            panic!("expected a gradient");
+        };
+        assert!(vertical);
+        assert_eq!(start.y, 4.);
+        assert_eq!(end.y, 10.);
+
+        // Diagonal gradients can't be drawn along an axis.
+        assert!(
+            gpu_scene(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10">
+                    <linearGradient id="g" x2="1" y2="1">
+                        <stop offset="0" stop-color="red"/>
+                        <stop offset="1" stop-color="blue"/>
+                    </linearGradient>
+                    <rect width="10" height="10" fill="url(#g)"/>
+                </svg>"#,
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_gpu_scene_unsupported_features() {
+        for content in [
+            r#"<radialGradient id="g">
+                   <stop offset="0" stop-color="red"/>
+                   <stop offset="1" stop-color="blue"/>
+               </radialGradient>
+               <rect width="10" height="10" fill="url(#g)"/>"#,
+            r#"<filter id="f"><feGaussianBlur stdDeviation="2"/></filter>
+               <rect width="10" height="10" filter="url(#f)"/>"#,
+            r#"<g opacity="0.5"><rect width="10" height="10"/><rect width="5" height="5"/></g>"#,
+        ] {
+            let svg = format!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10">{content}</svg>"#
+            );
+            assert!(gpu_scene(&svg).is_none(), "{content}");
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use gpui::{Axis, point};
+
+use crate::user_space::{UserSpace, format_units};
+
+/// A line across the preview at a user unit coordinate, dragged out of one of the rulers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Guide {
+    /// The axis that `position` is measured along. Guides dragged out of the top ruler are
+    /// horizontal lines marking a y coordinate, so their axis is [`Axis::Vertical`].
+    pub axis: Axis,
+    pub position: f32,
+}
+
+impl Guide {
+    /// Describes the position of the guide and its distance to the nearest of `edges`, which are
+    /// measured along the same axis.
+    pub fn label(&self, edges: &[f32]) -> String {
+        let name = match self.axis {
+            Axis::Horizontal => "x",
+            Axis::Vertical => "y",
+        };
+        let position = format_units(self.position);
+        match nearest_edge(self.position, edges) {
+            Some(edge) if format_units(edge - self.position) == "0" => {
+                format!("{name} {position} · on edge")
+            }
+            Some(edge) => format!(
+                "{name} {position} · {} from edge",
+                format_units((edge - self.position).abs())
+            ),
+            None => format!("{name} {position}"),
+        }
+    }
+}
+
+/// Returns the user unit coordinates of the edges of `boxes`, given in the coordinates of the
+/// SVG's size, along `axis`.
+pub fn edges(boxes: &[usvg::Rect], axis: Axis, user_space: &UserSpace) -> Vec<f32> {
+    boxes
+        .iter()
+        .flat_map(|rect| {
+            let start = user_space.to_user(point(rect.left(), rect.top()));
+            let end = user_space.to_user(point(rect.right(), rect.bottom()));
+            match axis {
+                Axis::Horizontal => [start.x, end.x],
+                Axis::Vertical => [start.y, end.y],
+            }
+        })
+        .collect()
+}
+
+/// Snaps `position` to the nearest of `edges` if it's within `threshold` of it, or else to the
+/// nearest whole user unit.
+pub fn snap(position: f32, edges: &[f32], threshold: f32) -> f32 {
+    nearest_edge(position, edges)
+        .filter(|edge| (edge - position).abs() <= threshold)
+        .unwrap_or_else(|| position.round())
+}
+
+fn nearest_edge(position: f32, edges: &[f32]) -> Option<f32> {
+    edges
+        .iter()
+        .copied()
+        .min_by(|a, b| (a - position).abs().total_cmp(&(b - position).abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snap() {
+        let edges = [2., 10.5];
+        assert_eq!(snap(10.2, &edges, 0.5), 10.5);
+        assert_eq!(snap(7.4, &edges, 0.5), 7.);
+        assert_eq!(snap(2.9, &edges, 1.), 2.);
+        assert_eq!(snap(2.9, &[], 1.), 3.);
+    }
+
+    #[test]
+    fn test_guide_label() {
+        let guide = Guide {
+            axis: Axis::Vertical,
+            position: 12.,
+        };
+        assert_eq!(guide.label(&[4., 12.5]), "y 12 · 0.5 from edge");
+        assert_eq!(guide.label(&[12.]), "y 12 · on edge");
+        assert_eq!(guide.label(&[]), "y 12");
+    }
+
+    #[test]
+    fn test_edges() {
+        let rect = usvg::Rect::from_xywh(10., 20., 30., 40.).unwrap();
+        let user_space = UserSpace::default();
+        assert_eq!(edges(&[rect], Axis::Horizontal, &user_space), [10., 40.]);
+        assert_eq!(edges(&[rect], Axis::Vertical, &user_space), [20., 60.]);
+    }
+}
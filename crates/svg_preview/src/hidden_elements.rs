@@ -0,0 +1,189 @@
+use std::ops::Range;
+
+use crate::animation;
+use crate::source_map;
+
+/// The prefix of the ids that [`reveal_hidden_elements`] gives to hidden elements without one.
+const HIDDEN_ID_PREFIX: &str = "zed-hidden-";
+
+/// Elements whose contents are only rendered where they're referenced, if at all, so that hiding
+/// their descendants has no effect.
+const NON_RENDERED_ELEMENTS: &[&str] = &[
+    "clipPath",
+    "defs",
+    "filter",
+    "linearGradient",
+    "marker",
+    "mask",
+    "pattern",
+    "radialGradient",
+    "symbol",
+];
+
+/// The elements of an SVG that are hidden with `display="none"` or `opacity="0"`, rendered as if
+/// they weren't, with everything else hidden.
+pub struct HiddenElements {
+    pub tree: usvg::Tree,
+    /// The bounds of the hidden elements, in the coordinates of the SVG's size.
+    pub bounds: Vec<usvg::Rect>,
+}
+
+impl HiddenElements {
+    /// Returns `None` if the SVG has no hidden elements.
+    pub fn new(text: &str, options: &usvg::Options) -> Option<Self> {
+        let (text, ids) = reveal_hidden_elements(text)?;
+        let tree = usvg::Tree::from_str(&text, options).ok()?;
+        let bounds = ids
+            .iter()
+            .filter_map(|id| tree.node_by_id(id))
+            .map(|node| node.abs_stroke_bounding_box())
+            .collect();
+        Some(Self { tree, bounds })
+    }
+}
+
+/// Returns `text` with its hidden elements shown and everything else hidden with `visibility`,
+/// along with the ids of the hidden elements. Returns `None` if `text` has no hidden elements.
+///
+/// Only elements that are hidden by their own attributes or inline styles are revealed, not ones
+/// hidden by style sheets.
+pub fn reveal_hidden_elements(text: &str) -> Option<(String, Vec<String>)> {
+    let document = animation::parse_document(text).ok()?;
+    let root = document.root_element();
+    let hidden = root
+        .descendants()
+        .skip(1)
+        .filter(|node| node.is_element() && is_hidden(*node))
+        .filter(|node| {
+            !node
+                .ancestors()
+                .any(|ancestor| NON_RENDERED_ELEMENTS.contains(&ancestor.tag_name().name()))
+        })
+        .collect::<Vec<_>>();
+    if hidden.is_empty() {
+        return None;
+    }
+
+    let mut edits = Vec::<(Range<usize>, String)>::new();
+    let mut ids = Vec::new();
+    for node in &hidden {
+        let mut insertion = String::new();
+        for attribute in node.attributes() {
+            let revealed = match attribute.name() {
+                "display" if attribute.value().trim() == "none" => "inline".to_string(),
+                "opacity" if is_zero(attribute.value()) => "1".to_string(),
+                "style" => reveal_style(attribute.value()),
+                "visibility" => "visible".to_string(),
+                _ => continue,
+            };
+            edits.push((attribute.range_value(), revealed));
+        }
+        if node.attribute("visibility").is_none() {
+            insertion.push_str(" visibility=\"visible\"");
+        }
+        match node.attribute("id") {
+            Some(id) => ids.push(id.to_string()),
+            None => {
+                let id = format!("{HIDDEN_ID_PREFIX}{}", node.range().start);
+                insertion.push_str(&format!(" id=\"{id}\""));
+                ids.push(id);
+            }
+        }
+        if !insertion.is_empty() {
+            let name_end = source_map::tag_name_end(text, node.range().start)?;
+            edits.push((name_end..name_end, insertion));
+        }
+    }
+    match root
+        .attributes()
+        .find(|attribute| attribute.name() == "visibility")
+    {
+        Some(attribute) => edits.push((attribute.range_value(), "hidden".to_string())),
+        None => {
+            let name_end = source_map::tag_name_end(text, root.range().start)?;
+            edits.push((name_end..name_end, " visibility=\"hidden\"".to_string()));
+        }
+    }
+    edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+
+    let mut text = text.to_string();
+    for (range, replacement) in edits {
+        text.replace_range(range, &replacement);
+    }
+    Some((text, ids))
+}
+
+fn is_hidden(node: roxmltree::Node<'_, '_>) -> bool {
+    node.attribute("display")
+        .is_some_and(|display| display.trim() == "none")
+        || node.attribute("opacity").is_some_and(is_zero)
+        || node
+            .attribute("style")
+            .is_some_and(|style| reveal_style(style) != style)
+}
+
+/// Returns `style` with its `display: none` and `opacity: 0` declarations replaced by ones that
+/// show the element.
+fn reveal_style(style: &str) -> String {
+    style
+        .split(';')
+        .map(|declaration| {
+            let Some((name, value)) = declaration.split_once(':') else {
+                return declaration.to_string();
+            };
+            match name.trim() {
+                "display" if value.trim() == "none" => format!("{name}:inline"),
+                "opacity" if is_zero(value) => format!("{name}:1"),
+                _ => declaration.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+fn is_zero(value: &str) -> bool {
+    value
+        .trim()
+        .trim_end_matches('%')
+        .parse::<f32>()
+        .is_ok_and(|value| value == 0.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reveal_hidden_elements() {
+        let text = r#"<svg xmlns="http://www.w3.org/2000/svg"><rect id="a" display="none"/><rect style="fill: red; opacity: 0"/><rect opacity="0.5"/><defs><rect display="none"/></defs></svg>"#;
+        let (revealed, ids) = reveal_hidden_elements(text).unwrap();
+        assert_eq!(
+            revealed,
+            r#"<svg visibility="hidden" xmlns="http://www.w3.org/2000/svg"><rect visibility="visible" id="a" display="inline"/><rect visibility="visible" id="zed-hidden-69" style="fill: red; opacity:1"/><rect opacity="0.5"/><defs><rect display="none"/></defs></svg>"#
+        );
+        assert_eq!(ids, ["a", "zed-hidden-69"]);
+
+        assert!(
+            reveal_hidden_elements(r#"<svg xmlns="http://www.w3.org/2000/svg"><rect/></svg>"#)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_hidden_elements_bounds() {
+        let hidden = HiddenElements::new(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10">
+                <rect width="10" height="10"/>
+                <rect x="10" width="10" height="5" display="none"/>
+            </svg>"#,
+            &usvg::Options::default(),
+        )
+        .unwrap();
+        let bounds = hidden
+            .bounds
+            .iter()
+            .map(|rect| (rect.x(), rect.y(), rect.width(), rect.height()))
+            .collect::<Vec<_>>();
+        assert_eq!(bounds, [(10., 0., 10., 5.)]);
+    }
+}
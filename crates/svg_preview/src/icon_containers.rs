@@ -0,0 +1,103 @@
+/// The side lengths, in pixels, of the images packed into exported `.ico` files.
+pub const ICO_SIZES: [u32; 7] = [16, 24, 32, 48, 64, 128, 256];
+
+/// The side lengths, in pixels, of the images packed into exported `.icns` files.
+pub const ICNS_SIZES: [u32; 7] = [16, 32, 64, 128, 256, 512, 1024];
+
+/// Packs square PNG images, given with their side length, into a Windows icon. Windows Vista and
+/// later read PNG payloads directly, so the images don't need to be converted to bitmaps.
+pub fn ico(images: &[(u32, Vec<u8>)]) -> Vec<u8> {
+    const HEADER_LEN: usize = 6;
+    const ENTRY_LEN: usize = 16;
+
+    let mut ico = Vec::new();
+    ico.extend_from_slice(&0u16.to_le_bytes());
+    // The resource type, 1 for icons.
+    ico.extend_from_slice(&1u16.to_le_bytes());
+    ico.extend_from_slice(&(images.len() as u16).to_le_bytes());
+
+    let mut offset = HEADER_LEN + ENTRY_LEN * images.len();
+    for (length, png) in images {
+        // Lengths are stored in a byte, where 0 means 256.
+        let length = if *length >= 256 { 0 } else { *length as u8 };
+        ico.extend_from_slice(&[length, length, 0, 0]);
+        // The color planes and bits per pixel.
+        ico.extend_from_slice(&1u16.to_le_bytes());
+        ico.extend_from_slice(&32u16.to_le_bytes());
+        ico.extend_from_slice(&(png.len() as u32).to_le_bytes());
+        ico.extend_from_slice(&(offset as u32).to_le_bytes());
+        offset += png.len();
+    }
+    for (_, png) in images {
+        ico.extend_from_slice(png);
+    }
+    ico
+}
+
+/// Packs square PNG images, given with their side length, into a macOS icon. Images whose size
+/// has no icon type are skipped; images that also serve as the Retina variant of a smaller size
+/// are stored under both types.
+pub fn icns(images: &[(u32, Vec<u8>)]) -> Vec<u8> {
+    const HEADER_LEN: usize = 8;
+
+    let mut chunks = Vec::new();
+    for (length, png) in images {
+        for icon_type in icns_types(*length) {
+            chunks.extend_from_slice(icon_type);
+            chunks.extend_from_slice(&((HEADER_LEN + png.len()) as u32).to_be_bytes());
+            chunks.extend_from_slice(png);
+        }
+    }
+
+    let mut icns = Vec::with_capacity(HEADER_LEN + chunks.len());
+    icns.extend_from_slice(b"icns");
+    icns.extend_from_slice(&((HEADER_LEN + chunks.len()) as u32).to_be_bytes());
+    icns.extend_from_slice(&chunks);
+    icns
+}
+
+/// Returns the PNG icon types for images of `length` × `length` pixels.
+fn icns_types(length: u32) -> &'static [&'static [u8; 4]] {
+    match length {
+        16 => &[b"icp4"],
+        32 => &[b"icp5", b"ic11"],
+        64 => &[b"icp6", b"ic12"],
+        128 => &[b"ic07"],
+        256 => &[b"ic08", b"ic13"],
+        512 => &[b"ic09", b"ic14"],
+        1024 => &[b"ic10"],
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ico() {
+        let ico = ico(&[(16, vec![1, 2]), (256, vec![3])]);
+        assert_eq!(&ico[..6], &[0, 0, 1, 0, 2, 0]);
+        // The first entry's size, byte count and offset.
+        assert_eq!(&ico[6..8], &[16, 16]);
+        assert_eq!(&ico[14..18], &2u32.to_le_bytes());
+        assert_eq!(&ico[18..22], &38u32.to_le_bytes());
+        // The second entry stores 256 as 0.
+        assert_eq!(&ico[22..24], &[0, 0]);
+        assert_eq!(&ico[34..38], &40u32.to_le_bytes());
+        assert_eq!(&ico[38..], &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_icns() {
+        let icns = icns(&[(16, vec![1]), (32, vec![2, 3]), (20, vec![4])]);
+        assert_eq!(&icns[..4], b"icns");
+        assert_eq!(&icns[4..8], &(icns.len() as u32).to_be_bytes());
+        assert_eq!(&icns[8..12], b"icp4");
+        assert_eq!(&icns[12..16], &9u32.to_be_bytes());
+        assert_eq!(icns[16], 1);
+        assert_eq!(&icns[17..21], b"icp5");
+        assert_eq!(&icns[27..31], b"ic11");
+        assert_eq!(icns.len(), 8 + 9 + 10 + 10);
+    }
+}
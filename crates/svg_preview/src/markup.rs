@@ -0,0 +1,77 @@
+use crate::animation;
+
+/// Whether `text` is an SVG document, as opposed to arbitrary text or another XML document.
+pub fn is_svg_markup(text: &str) -> bool {
+    animation::parse_document(text.trim())
+        .is_ok_and(|document| document.root_element().tag_name().name() == "svg")
+}
+
+/// How many characters at the start of a buffer are looked at to tell whether it's an SVG.
+pub const SVG_SNIFF_LEN: usize = 4096;
+
+/// Whether the first element of `text` is an `<svg>` element, skipping the XML declaration,
+/// comments, processing instructions and the doctype before it. Unlike [`is_svg_markup`], this
+/// doesn't need the whole document, so it also works on a prefix of it.
+pub fn starts_with_svg_root(text: &str) -> bool {
+    let mut rest = text.trim_start_matches('\u{feff}');
+    loop {
+        rest = rest.trim_start();
+        let end = if rest.starts_with("<!--") {
+            rest.find("-->").map(|end| end + "-->".len())
+        } else if rest.starts_with("<?") {
+            rest.find("?>").map(|end| end + "?>".len())
+        } else if rest.starts_with("<!") {
+            // Doctypes may contain an internal subset in brackets.
+            match (rest.find('['), rest.find('>')) {
+                (Some(bracket), Some(end)) if bracket < end => {
+                    rest.find("]>").map(|end| end + "]>".len())
+                }
+                (_, end) => end.map(|end| end + ">".len()),
+            }
+        } else {
+            break;
+        };
+        let Some(end) = end else {
+            return false;
+        };
+        rest = &rest[end..];
+    }
+    rest.strip_prefix("<svg").is_some_and(|rest| {
+        rest.chars()
+            .next()
+            .is_some_and(|next| next.is_whitespace() || next == '>' || next == '/')
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_svg_markup() {
+        assert!(is_svg_markup(
+            "\n<svg xmlns=\"http://www.w3.org/2000/svg\"><rect/></svg>\n"
+        ));
+        assert!(is_svg_markup(
+            "<?xml version=\"1.0\"?><svg xmlns=\"http://www.w3.org/2000/svg\"/>"
+        ));
+        assert!(!is_svg_markup("<html><svg/></html>"));
+        assert!(!is_svg_markup("<svg><rect></svg>"));
+        assert!(!is_svg_markup("Hello"));
+    }
+
+    #[test]
+    fn test_starts_with_svg_root() {
+        assert!(starts_with_svg_root(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\">"
+        ));
+        assert!(starts_with_svg_root("\u{feff}\n  <svg/>"));
+        assert!(starts_with_svg_root(
+            "<?xml version=\"1.0\"?>\n<!-- icon -->\n<!DOCTYPE svg [<!ENTITY a \"b\">]>\n<svg>"
+        ));
+        assert!(!starts_with_svg_root("<svgfoo/>"));
+        assert!(!starts_with_svg_root("<html><svg/></html>"));
+        assert!(!starts_with_svg_root("<!-- <svg> "));
+        assert!(!starts_with_svg_root("Hello"));
+    }
+}
@@ -0,0 +1,61 @@
+use gpui::{Point, point};
+
+use crate::user_space::{UserSpace, format_units};
+
+/// A line measured across an SVG, between two points in the coordinates of its intrinsic size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Measurement {
+    pub start: Point<f32>,
+    pub end: Point<f32>,
+}
+
+impl Measurement {
+    /// Describes the distance between the ends, along with its horizontal and vertical parts,
+    /// in user units and in pixels at `export_scale`.
+    pub fn label(&self, user_space: &UserSpace, export_scale: f32) -> String {
+        let start = user_space.to_user(self.start);
+        let end = user_space.to_user(self.end);
+        let units = point(end.x - start.x, end.y - start.y);
+        let pixels = point(
+            (self.end.x - self.start.x) * export_scale,
+            (self.end.y - self.start.y) * export_scale,
+        );
+        format!(
+            "{} (Δx {}, Δy {}) · {} px at {}x",
+            format_units(units.x.hypot(units.y)),
+            format_units(units.x),
+            format_units(units.y),
+            format_units(pixels.x.hypot(pixels.y)),
+            format_units(export_scale),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measurement_label() {
+        let measurement = Measurement {
+            start: point(10., 20.),
+            end: point(40., 60.),
+        };
+        assert_eq!(
+            measurement.label(&UserSpace::default(), 1.),
+            "50 (Δx 30, Δy 40) · 50 px at 1x"
+        );
+        assert_eq!(
+            measurement.label(&UserSpace::default(), 1.5),
+            "50 (Δx 30, Δy 40) · 75 px at 1.5x"
+        );
+        let reversed = Measurement {
+            start: measurement.end,
+            end: measurement.start,
+        };
+        assert_eq!(
+            reversed.label(&UserSpace::default(), 2.),
+            "50 (Δx -30, Δy -40) · 100 px at 2x"
+        );
+    }
+}
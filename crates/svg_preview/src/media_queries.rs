@@ -0,0 +1,212 @@
+use gpui::Size;
+
+/// The color scheme that `prefers-color-scheme` media queries in the previewed SVG are
+/// evaluated against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+impl ColorScheme {
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Light => Self::Dark,
+            Self::Dark => Self::Light,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Light => "light",
+            Self::Dark => "dark",
+        }
+    }
+}
+
+/// The environment that media queries in the previewed SVG are evaluated against.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MediaContext {
+    pub color_scheme: ColorScheme,
+    /// The simulated viewport size in CSS pixels, or `None` if the size features of media
+    /// queries, such as `max-width`, can't be evaluated.
+    pub viewport: Option<Size<f32>>,
+}
+
+/// Returns `text` with its `@media` rules evaluated against `context`, since the renderer ignores
+/// media queries: the rules of matching queries are kept and the others are removed. Queries
+/// that can't be evaluated, e.g. because they use unsupported features, are left as is. Returns
+/// `None` if no query was evaluated.
+pub fn apply_media_queries(text: &str, context: &MediaContext) -> Option<String> {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    let mut changed = false;
+    while let Some(start) = rest.find("@media") {
+        let Some(block_start) = rest[start..].find('{').map(|index| start + index) else {
+            break;
+        };
+        let Some(block_len) = closing_brace(&rest[block_start + 1..]) else {
+            break;
+        };
+        let block_end = block_start + 1 + block_len;
+
+        result.push_str(&rest[..start]);
+        let query = &rest[start + "@media".len()..block_start];
+        match evaluate_query_list(&query.to_ascii_lowercase(), context) {
+            Some(matches) => {
+                changed = true;
+                if matches {
+                    result.push_str(&rest[block_start + 1..block_end]);
+                }
+            }
+            None => result.push_str(&rest[start..=block_end]),
+        }
+        rest = &rest[block_end + 1..];
+    }
+    result.push_str(rest);
+    changed.then_some(result)
+}
+
+/// Evaluates a comma-separated list of media queries, which matches if any of them matches.
+fn evaluate_query_list(queries: &str, context: &MediaContext) -> Option<bool> {
+    let mut matches = false;
+    for query in queries.split(',') {
+        matches |= evaluate_query(query.trim(), context)?;
+    }
+    Some(matches)
+}
+
+fn evaluate_query(query: &str, context: &MediaContext) -> Option<bool> {
+    let (negated, query) = match query.strip_prefix("not ") {
+        Some(query) => (true, query),
+        None => (false, query.strip_prefix("only ").unwrap_or(query)),
+    };
+    let mut matches = true;
+    for condition in query.split(" and ") {
+        let condition = condition.trim();
+        matches &= match condition
+            .strip_prefix('(')
+            .and_then(|condition| condition.strip_suffix(')'))
+        {
+            Some(feature) => evaluate_feature(feature, context)?,
+            None => match condition {
+                "all" | "screen" => true,
+                "print" => false,
+                _ => return None,
+            },
+        };
+    }
+    Some(matches != negated)
+}
+
+fn evaluate_feature(feature: &str, context: &MediaContext) -> Option<bool> {
+    let (name, value) = feature.split_once(':')?;
+    let (name, value) = (name.trim(), value.trim());
+    if name == "prefers-color-scheme" {
+        return Some(value == context.color_scheme.name());
+    }
+
+    let viewport = context.viewport?;
+    if name == "orientation" {
+        let is_portrait = viewport.height >= viewport.width;
+        return match value {
+            "portrait" => Some(is_portrait),
+            "landscape" => Some(!is_portrait),
+            _ => None,
+        };
+    }
+    let length = parse_length(value)?;
+    match name {
+        "width" => Some(viewport.width == length),
+        "min-width" => Some(viewport.width >= length),
+        "max-width" => Some(viewport.width <= length),
+        "height" => Some(viewport.height == length),
+        "min-height" => Some(viewport.height >= length),
+        "max-height" => Some(viewport.height <= length),
+        _ => None,
+    }
+}
+
+/// Parses a CSS length in pixels, treating `em` and `rem` as 16 pixels.
+fn parse_length(value: &str) -> Option<f32> {
+    if let Some(value) = value.strip_suffix("px") {
+        value.trim().parse().ok()
+    } else if let Some(value) = value
+        .strip_suffix("rem")
+        .or_else(|| value.strip_suffix("em"))
+    {
+        value.trim().parse::<f32>().ok().map(|value| value * 16.)
+    } else {
+        value.parse().ok()
+    }
+}
+
+/// Returns the length of `text` up to the brace closing the one opened before it.
+fn closing_brace(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(index),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::size;
+
+    #[test]
+    fn test_apply_color_scheme() {
+        let text = "path { fill: black; } \
+            @media (prefers-color-scheme: dark) { path { fill: white; } } \
+            @media print { path { fill: gray; } }";
+        let dark = MediaContext {
+            color_scheme: ColorScheme::Dark,
+            viewport: None,
+        };
+        assert_eq!(
+            apply_media_queries(text, &dark).as_deref(),
+            Some("path { fill: black; }  path { fill: white; } ")
+        );
+        let light = MediaContext {
+            color_scheme: ColorScheme::Light,
+            viewport: None,
+        };
+        assert_eq!(
+            apply_media_queries(text, &light).as_deref(),
+            Some("path { fill: black; }  ")
+        );
+        assert_eq!(apply_media_queries("path { fill: black; }", &dark), None);
+    }
+
+    #[test]
+    fn test_apply_viewport_queries() {
+        let text = "@media (max-width: 32px) { .detail { display: none; } }";
+        let context = |viewport| MediaContext {
+            color_scheme: ColorScheme::Light,
+            viewport,
+        };
+        assert_eq!(
+            apply_media_queries(text, &context(Some(size(16., 16.)))).as_deref(),
+            Some(" .detail { display: none; } ")
+        );
+        assert_eq!(
+            apply_media_queries(text, &context(Some(size(64., 64.)))).as_deref(),
+            Some("")
+        );
+        assert_eq!(apply_media_queries(text, &context(None)), None);
+        assert_eq!(
+            apply_media_queries(
+                "@media screen and (orientation: landscape), (min-height: 10em) { a {} }",
+                &context(Some(size(100., 200.)))
+            )
+            .as_deref(),
+            Some(" a {} ")
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use std::rc::Rc;
+
+use collections::HashMap;
+use gpui::{App, EntityId, Global, RenderImage};
+
+/// The bytes of the rasterized images that each SVG preview retains, so that their total can be
+/// kept within the budget set by the `svg_preview.memory_budget_mb` setting.
+#[derive(Default)]
+pub struct RetainedImages {
+    previews: HashMap<EntityId, RetainedByPreview>,
+}
+
+impl Global for RetainedImages {}
+
+struct RetainedByPreview {
+    bytes: usize,
+    /// The bytes of the images that aren't on screen, such as cached tiles, which `evict` drops.
+    evictable_bytes: usize,
+    evict: Rc<dyn Fn(&mut App)>,
+}
+
+/// Returns the number of bytes that the pixels of `image` take up.
+pub fn image_bytes(image: &RenderImage) -> usize {
+    (0..image.frame_count())
+        .map(|frame_index| {
+            let size = image.size(frame_index);
+            size.width.0.max(0) as usize * size.height.0.max(0) as usize * 4
+        })
+        .sum()
+}
+
+impl RetainedImages {
+    /// Records the images that `preview` retains, then evicts the images of the previews with the
+    /// most evictable bytes until the total is within `budget`, starting with the largest.
+    pub fn update(
+        preview: EntityId,
+        bytes: usize,
+        evictable_bytes: usize,
+        evict: impl Fn(&mut App) + 'static,
+        budget: usize,
+        cx: &mut App,
+    ) {
+        let this = cx.default_global::<Self>();
+        this.previews.insert(
+            preview,
+            RetainedByPreview {
+                bytes,
+                evictable_bytes,
+                evict: Rc::new(evict),
+            },
+        );
+
+        let mut total = this.total_bytes();
+        if total <= budget {
+            return;
+        }
+        let mut previews = this.previews.values_mut().collect::<Vec<_>>();
+        previews.sort_by_key(|preview| std::cmp::Reverse(preview.evictable_bytes));
+        let mut evictions = Vec::new();
+        for preview in previews {
+            if total <= budget || preview.evictable_bytes == 0 {
+                break;
+            }
+            total -= preview.evictable_bytes;
+            preview.bytes -= preview.evictable_bytes;
+            preview.evictable_bytes = 0;
+            evictions.push(preview.evict.clone());
+        }
+        // The previews are evicted later, as the one being updated can't be updated again now.
+        for evict in evictions {
+            cx.defer(move |cx| evict(cx));
+        }
+    }
+
+    pub fn remove(preview: EntityId, cx: &mut App) {
+        if cx.has_global::<Self>() {
+            cx.global_mut::<Self>().previews.remove(&preview);
+        }
+    }
+
+    /// Returns how many bytes `preview` can retain within `budget`, given the images that the
+    /// other previews retain.
+    pub fn available(preview: EntityId, budget: usize, cx: &App) -> usize {
+        let others = cx.try_global::<Self>().map_or(0, |this| {
+            this.previews
+                .iter()
+                .filter(|(id, _)| **id != preview)
+                .map(|(_, preview)| preview.bytes)
+                .sum()
+        });
+        budget.saturating_sub(others)
+    }
+
+    fn total_bytes(&self) -> usize {
+        self.previews.values().map(|preview| preview.bytes).sum()
+    }
+}
@@ -0,0 +1,889 @@
+use std::cmp::Reverse;
+use std::ops::Range;
+
+use roxmltree::{Document, Node, NodeType};
+
+use crate::animation;
+
+/// Namespaces, or prefixes of namespaces, that editors store their own data in, which doesn't
+/// affect rendering.
+const EDITOR_NAMESPACES: &[&str] = &[
+    "http://www.inkscape.org/namespaces/inkscape",
+    "http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd",
+    "http://ns.adobe.com/",
+    "http://www.bohemiancoding.com/sketch/ns",
+    "http://www.serif.com/",
+    "http://www.w3.org/1999/02/22-rdf-syntax-ns#",
+    "http://purl.org/dc/elements/1.1/",
+    "http://creativecommons.org/ns#",
+    "http://web.resource.org/cc/",
+];
+
+/// The attributes whose numbers are rounded. Transforms are left alone, since rounding their
+/// scales and rotations distorts everything they apply to.
+const ROUNDED_ATTRIBUTES: &[&str] = &[
+    "cx",
+    "cy",
+    "d",
+    "dx",
+    "dy",
+    "fx",
+    "fy",
+    "height",
+    "points",
+    "r",
+    "rx",
+    "ry",
+    "stroke-width",
+    "viewBox",
+    "width",
+    "x",
+    "x1",
+    "x2",
+    "y",
+    "y1",
+    "y2",
+];
+
+/// The number of decimals that numbers are rounded to.
+const PRECISION: usize = 3;
+
+/// A transformation that makes SVGs smaller without changing how they look.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pass {
+    /// Removes comments, `<metadata>`, empty `<defs>` and the elements and attributes of editors.
+    StripMetadata,
+    /// Replaces groups without attributes by their children.
+    CollapseGroups,
+    /// Merges adjacent paths with the same attributes that don't overlap.
+    MergePaths,
+    /// Rounds the numbers of coordinates and sizes.
+    RoundNumbers,
+}
+
+impl Pass {
+    pub const ALL: [Pass; 4] = [
+        Pass::StripMetadata,
+        Pass::CollapseGroups,
+        Pass::MergePaths,
+        Pass::RoundNumbers,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Pass::StripMetadata => "Strip editor metadata",
+            Pass::CollapseGroups => "Collapse groups",
+            Pass::MergePaths => "Merge paths",
+            Pass::RoundNumbers => "Round numbers",
+        }
+    }
+
+    /// Returns `text` transformed by the pass, or `None` if the pass doesn't change it or `text`
+    /// can't be parsed.
+    pub fn apply(self, text: &str) -> Option<String> {
+        let document = animation::parse_document(text).ok()?;
+        let edits = match self {
+            Pass::StripMetadata => strip_metadata(text, &document),
+            Pass::CollapseGroups => collapse_groups(text, &document),
+            Pass::MergePaths => merge_paths(text, &document),
+            Pass::RoundNumbers => round_numbers(text, &document),
+        };
+        apply_edits(text, edits)
+    }
+}
+
+/// What a pass saved when applied to an SVG.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PassSavings {
+    pub pass: Pass,
+    pub bytes: usize,
+    pub elements: usize,
+}
+
+/// Returns `text` transformed by `passes`, or `None` if none of them change it.
+pub fn optimize(text: &str, passes: &[Pass]) -> Option<String> {
+    let mut optimized = None::<String>;
+    for &pass in passes {
+        if let Some(text) = pass.apply(optimized.as_deref().unwrap_or(text)) {
+            optimized = Some(text);
+        }
+    }
+    optimized
+}
+
+/// Applies all passes to `text` in order, and reports what each of them saved.
+pub fn savings(text: &str) -> Vec<PassSavings> {
+    let mut text = text.to_string();
+    let mut elements = element_count(&text);
+    Pass::ALL
+        .into_iter()
+        .map(|pass| {
+            let Some(optimized) = pass.apply(&text) else {
+                return PassSavings {
+                    pass,
+                    bytes: 0,
+                    elements: 0,
+                };
+            };
+            let optimized_elements = element_count(&optimized);
+            let savings = PassSavings {
+                pass,
+                bytes: text.len().saturating_sub(optimized.len()),
+                elements: elements.saturating_sub(optimized_elements),
+            };
+            text = optimized;
+            elements = optimized_elements;
+            savings
+        })
+        .collect()
+}
+
+fn element_count(text: &str) -> usize {
+    animation::parse_document(text).map_or(0, |document| {
+        document
+            .descendants()
+            .filter(|node| node.is_element())
+            .count()
+    })
+}
+
+fn apply_edits(text: &str, mut edits: Vec<(Range<usize>, String)>) -> Option<String> {
+    if edits.is_empty() {
+        return None;
+    }
+    edits.sort_by_key(|(range, _)| Reverse(range.start));
+    let mut text = text.to_string();
+    for (range, replacement) in edits {
+        text.replace_range(range, &replacement);
+    }
+    Some(text)
+}
+
+/// Extends `range` to the whitespace before it, so that removing it doesn't leave a blank line or
+/// a double space behind.
+fn with_leading_whitespace(text: &str, range: Range<usize>) -> Range<usize> {
+    text[..range.start].trim_end().len()..range.end
+}
+
+fn is_editor_namespace(namespace: &str) -> bool {
+    EDITOR_NAMESPACES
+        .iter()
+        .any(|editor_namespace| namespace.starts_with(editor_namespace))
+}
+
+fn is_whitespace(node: Node) -> bool {
+    node.is_text() && node.text().is_some_and(|text| text.trim().is_empty())
+}
+
+fn strip_metadata(text: &str, document: &Document) -> Vec<(Range<usize>, String)> {
+    let mut edits = Vec::new();
+    let mut removed_until = 0;
+    for node in document.root().descendants() {
+        let range = node.range();
+        if range.start < removed_until {
+            continue;
+        }
+        if is_metadata(node) {
+            removed_until = range.end;
+            edits.push((with_leading_whitespace(text, range), String::new()));
+            continue;
+        }
+        if !node.is_element() {
+            continue;
+        }
+        for attribute in node.attributes() {
+            if attribute.namespace().is_some_and(is_editor_namespace) {
+                edits.push((
+                    with_leading_whitespace(text, attribute.range()),
+                    String::new(),
+                ));
+            }
+        }
+        for (range, namespace) in namespace_declarations(text, node) {
+            if is_editor_namespace(namespace) {
+                edits.push((range, String::new()));
+            }
+        }
+    }
+    edits
+}
+
+/// Whether `node` is a comment, editor data, or a `<defs>` without anything else in it.
+fn is_metadata(node: Node) -> bool {
+    match node.node_type() {
+        NodeType::Comment => true,
+        NodeType::Element => match node.tag_name().name() {
+            "metadata" => true,
+            "defs" => node
+                .children()
+                .all(|child| is_whitespace(child) || is_metadata(child)),
+            _ => node.tag_name().namespace().is_some_and(is_editor_namespace),
+        },
+        _ => false,
+    }
+}
+
+/// Returns the namespace declarations in the start tag of `node` with the ranges to remove them
+/// at, which roxmltree doesn't expose.
+fn namespace_declarations<'a>(text: &'a str, node: Node) -> Vec<(Range<usize>, &'a str)> {
+    const PREFIX: &str = "xmlns:";
+    let tag_start = node.range().start;
+    let tag_end = node
+        .first_child()
+        .map_or(node.range().end, |child| child.range().start);
+    let tag = &text[tag_start..tag_end];
+    let mut declarations = Vec::new();
+    let mut search_start = 0;
+    while let Some(index) = tag[search_start..].find(PREFIX) {
+        let start = search_start + index;
+        search_start = start + PREFIX.len();
+        if !tag[..start].ends_with(char::is_whitespace) {
+            continue;
+        }
+        let Some(equals) = tag[start..].find('=') else {
+            break;
+        };
+        let value = tag[start + equals + 1..].trim_start();
+        let value_start = tag.len() - value.len();
+        let Some(quote) = value.chars().next().filter(|c| matches!(*c, '"' | '\'')) else {
+            continue;
+        };
+        let Some(value_len) = value[1..].find(quote) else {
+            break;
+        };
+        let end = value_start + value_len + 2;
+        declarations.push((
+            with_leading_whitespace(text, tag_start + start..tag_start + end),
+            &value[1..value_len + 1],
+        ));
+    }
+    declarations
+}
+
+fn collapse_groups(text: &str, document: &Document) -> Vec<(Range<usize>, String)> {
+    let mut edits = Vec::new();
+    if styles_may_match_groups(document) {
+        return edits;
+    }
+    for node in document.descendants() {
+        // Children of a `<switch>` are alternatives, and animations of a group animate the group.
+        if !node.is_element()
+            || node
+                .parent_element()
+                .is_some_and(|parent| parent.tag_name().name() == "switch")
+            || node.children().any(animation::is_animation_element)
+        {
+            continue;
+        }
+        let range = node.range();
+        let Some(start_tag_end) = text[range.clone()]
+            .find('>')
+            .map(|end| range.start + end + 1)
+        else {
+            continue;
+        };
+        // Namespace declarations aren't attributes in roxmltree, so check the tag itself.
+        let start_tag = &text[range.start..start_tag_end];
+        if start_tag
+            .trim_end_matches('>')
+            .trim_end_matches('/')
+            .trim_end()
+            != "<g"
+        {
+            continue;
+        }
+        if start_tag.ends_with("/>") || node.children().all(is_whitespace) {
+            edits.push((with_leading_whitespace(text, range), String::new()));
+            continue;
+        }
+        let Some(end_tag_start) = text[range.clone()]
+            .rfind("</")
+            .map(|start| range.start + start)
+        else {
+            continue;
+        };
+        edits.push((range.start..start_tag_end, String::new()));
+        edits.push((end_tag_start..range.end, String::new()));
+    }
+    edits
+}
+
+/// Whether a `<style>` element has a rule that could select a bare `<g>` or depend on where
+/// elements are in the tree, such as `g`, `*`, `g > rect` or `rect:first-child`, which unwrapping
+/// groups would change the result of.
+fn styles_may_match_groups(document: &Document) -> bool {
+    document
+        .descendants()
+        .filter(|node| node.is_element() && node.tag_name().name() == "style")
+        .any(|style| {
+            let css = style
+                .children()
+                .filter_map(|child| child.text())
+                .collect::<String>();
+            selectors(&without_comments(&css)).any(|selector| {
+                selector.contains(['*', '>', '+', '~', ':'])
+                    || selector.split_whitespace().any(|compound| {
+                        compound
+                            .split(['.', '#', '['])
+                            .next()
+                            .is_some_and(|element| element.eq_ignore_ascii_case("g"))
+                    })
+            })
+        })
+}
+
+/// Returns the selectors of the rules in `css`, including the ones nested in at-rules.
+fn selectors(css: &str) -> impl Iterator<Item = &str> {
+    let mut css = css;
+    let mut preludes = Vec::new();
+    while let Some(end) = css.find(['{', '}', ';']) {
+        let prelude = css[..end].trim();
+        if css[end..].starts_with('{') && !prelude.starts_with('@') {
+            preludes.push(prelude);
+        }
+        css = &css[end + 1..];
+    }
+    preludes
+        .into_iter()
+        .flat_map(|prelude| prelude.split(','))
+        .map(str::trim)
+}
+
+fn without_comments(css: &str) -> String {
+    let mut stripped = String::new();
+    let mut css = css;
+    while let Some(start) = css.find("/*") {
+        stripped.push_str(&css[..start]);
+        css = match css[start + 2..].find("*/") {
+            Some(end) => &css[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+    stripped.push_str(css);
+    stripped
+}
+
+/// A path that can be merged with adjacent ones with the same attributes.
+struct MergeablePath<'a> {
+    /// The attributes of the path other than `d`.
+    attributes: Vec<(Option<&'a str>, &'a str, &'a str)>,
+    d: &'a str,
+    d_range: Range<usize>,
+    /// The bounds of the path including its stroke, as min x, min y, max x and max y.
+    bounds: [f32; 4],
+    /// The range to remove the path at once it's merged into another.
+    removal: Range<usize>,
+}
+
+/// Adjacent paths that are merged into the first one.
+struct PathRun<'a> {
+    first: MergeablePath<'a>,
+    d: String,
+    bounds: [f32; 4],
+    removals: Vec<Range<usize>>,
+}
+
+impl<'a> PathRun<'a> {
+    fn new(first: MergeablePath<'a>) -> Self {
+        Self {
+            d: first.d.trim().to_string(),
+            bounds: first.bounds,
+            first,
+            removals: Vec::new(),
+        }
+    }
+
+    /// Whether `path` looks the same merged into the run. Paths that overlap aren't merged, since
+    /// the combined path could be filled differently where they do.
+    fn can_merge(&self, path: &MergeablePath) -> bool {
+        path.attributes == self.first.attributes && are_disjoint(self.bounds, path.bounds)
+    }
+
+    fn push(&mut self, path: MergeablePath) {
+        if let Some(d) = with_absolute_moveto(path.d) {
+            self.d.push(' ');
+            self.d.push_str(&d);
+            self.bounds = [
+                self.bounds[0].min(path.bounds[0]),
+                self.bounds[1].min(path.bounds[1]),
+                self.bounds[2].max(path.bounds[2]),
+                self.bounds[3].max(path.bounds[3]),
+            ];
+            self.removals.push(path.removal);
+        }
+    }
+
+    fn into_edits(self) -> Vec<(Range<usize>, String)> {
+        if self.removals.is_empty() {
+            return Vec::new();
+        }
+        let mut edits = vec![(self.first.d_range, self.d)];
+        edits.extend(
+            self.removals
+                .into_iter()
+                .map(|range| (range, String::new())),
+        );
+        edits
+    }
+}
+
+fn merge_paths(text: &str, document: &Document) -> Vec<(Range<usize>, String)> {
+    let mut edits = Vec::new();
+    for parent in document.descendants().filter(|node| node.is_element()) {
+        let mut run = None::<PathRun>;
+        for child in parent.children() {
+            if is_whitespace(child) {
+                continue;
+            }
+            match (&mut run, mergeable_path(text, child)) {
+                (Some(current), Some(path)) if current.can_merge(&path) => current.push(path),
+                (current, path) => {
+                    edits.extend(current.take().into_iter().flat_map(PathRun::into_edits));
+                    *current = path.map(PathRun::new);
+                }
+            }
+        }
+        edits.extend(run.into_iter().flat_map(PathRun::into_edits));
+    }
+    edits
+}
+
+fn mergeable_path<'a>(text: &str, node: Node<'a, 'a>) -> Option<MergeablePath<'a>> {
+    if !node.is_element() || node.tag_name().name() != "path" || node.has_children() {
+        return None;
+    }
+    // Markers are drawn at the start and end of every subpath, and are inherited.
+    let has_markers = node.ancestors().any(|node| {
+        node.attributes().any(|attribute| {
+            attribute.name().starts_with("marker")
+                || attribute.name() == "style" && attribute.value().contains("marker")
+        })
+    });
+    if has_markers {
+        return None;
+    }
+
+    let mut d = None;
+    let mut attributes = Vec::new();
+    for attribute in node.attributes() {
+        // Escaped values can't be copied between attributes as they are.
+        if text[attribute.range_value()].contains('&') {
+            return None;
+        }
+        match attribute.name() {
+            "d" => d = Some((attribute.value(), attribute.range_value())),
+            // Elements with ids may be referenced, and `url(…)` paints may depend on the bounds.
+            "id" | "pathLength" => return None,
+            _ if attribute.value().contains("url(") => return None,
+            _ => attributes.push((attribute.namespace(), attribute.name(), attribute.value())),
+        }
+    }
+    let (d, d_range) = d?;
+
+    // Miter joins reach up to the default miter limit of 4 half stroke widths from the path.
+    let stroke_width = node
+        .ancestors()
+        .find_map(|node| node.attribute("stroke-width"))
+        .and_then(|width| width.trim().trim_end_matches("px").parse::<f32>().ok())
+        .unwrap_or(1.);
+    let margin = stroke_width.abs() * 2.;
+    let bounds = path_bounds(d)?;
+    Some(MergeablePath {
+        attributes,
+        d,
+        d_range,
+        bounds: [
+            bounds[0] - margin,
+            bounds[1] - margin,
+            bounds[2] + margin,
+            bounds[3] + margin,
+        ],
+        removal: with_leading_whitespace(text, node.range()),
+    })
+}
+
+fn are_disjoint(a: [f32; 4], b: [f32; 4]) -> bool {
+    a[2] < b[0] || b[2] < a[0] || a[3] < b[1] || b[3] < a[1]
+}
+
+/// Returns `d` starting with an absolute moveto, so that it can be appended to other path data.
+/// A leading relative moveto is relative to the origin on its own, but to the end of the previous
+/// subpath once appended.
+fn with_absolute_moveto(d: &str) -> Option<String> {
+    let commands = path_commands(d)?;
+    let (command, parameters) = commands.first()?;
+    if *command != 'm' {
+        return Some(d.trim().to_string());
+    }
+    let (x, y) = (&d[parameters[0].clone()], &d[parameters[1].clone()]);
+    let rest = &d[parameters[1].end..];
+    // Further coordinate pairs of a moveto are linetos.
+    let lineto = if parameters.len() > 2 { " l" } else { "" };
+    Some(format!("M{x} {y}{lineto}{rest}").trim_end().to_string())
+}
+
+/// Returns the bounds of the points and control points of `d`, as min x, min y, max x and max y.
+fn path_bounds(d: &str) -> Option<[f32; 4]> {
+    fn include(bounds: &mut [f32; 4], point: [f32; 2], margin: f32) {
+        bounds[0] = bounds[0].min(point[0] - margin);
+        bounds[1] = bounds[1].min(point[1] - margin);
+        bounds[2] = bounds[2].max(point[0] + margin);
+        bounds[3] = bounds[3].max(point[1] + margin);
+    }
+
+    let mut bounds = [
+        f32::INFINITY,
+        f32::INFINITY,
+        f32::NEG_INFINITY,
+        f32::NEG_INFINITY,
+    ];
+    let mut current = [0., 0.];
+    let mut subpath_start = current;
+    for (command, parameters) in path_commands(d)? {
+        let values = parameters
+            .iter()
+            .map(|range| d[range.clone()].parse::<f32>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        let relative = command.is_ascii_lowercase();
+        let command = command.to_ascii_uppercase();
+        let arity = match command {
+            'Z' => 0,
+            'H' | 'V' => 1,
+            'M' | 'L' | 'T' => 2,
+            'S' | 'Q' => 4,
+            'C' => 6,
+            'A' => 7,
+            _ => return None,
+        };
+        if arity == 0 {
+            if !values.is_empty() {
+                return None;
+            }
+            current = subpath_start;
+            continue;
+        }
+        if values.is_empty() || values.len() % arity != 0 {
+            return None;
+        }
+        for (index, values) in values.chunks(arity).enumerate() {
+            let origin = if relative { current } else { [0., 0.] };
+            let point = |x: f32, y: f32| [origin[0] + x, origin[1] + y];
+            let end = match command {
+                'H' => [origin[0] + values[0], current[1]],
+                'V' => [current[0], origin[1] + values[0]],
+                'A' => {
+                    // The arc lies on an ellipse through both ends, which is no wider than its
+                    // diameter, or than the distance between the ends if its radii are too small.
+                    let end = point(values[5], values[6]);
+                    let reach = (2. * values[0].abs().max(values[1].abs()))
+                        .max((end[0] - current[0]).hypot(end[1] - current[1]));
+                    include(&mut bounds, current, reach);
+                    include(&mut bounds, end, reach);
+                    end
+                }
+                _ => {
+                    for pair in values.chunks(2) {
+                        include(&mut bounds, point(pair[0], pair[1]), 0.);
+                    }
+                    point(values[arity - 2], values[arity - 1])
+                }
+            };
+            include(&mut bounds, end, 0.);
+            current = end;
+            if command == 'M' && index == 0 {
+                subpath_start = end;
+            }
+        }
+    }
+    (bounds[0] <= bounds[2]).then_some(bounds)
+}
+
+/// Splits path data into its commands and the ranges of their parameters, or returns `None` if
+/// it's malformed.
+fn path_commands(d: &str) -> Option<Vec<(char, Vec<Range<usize>>)>> {
+    let bytes = d.as_bytes();
+    let mut commands = Vec::<(char, Vec<Range<usize>>)>::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        if byte.is_ascii_whitespace() || byte == b',' {
+            index += 1;
+        } else if byte.is_ascii_alphabetic() {
+            commands.push((byte as char, Vec::new()));
+            index += 1;
+        } else {
+            let (command, parameters) = commands.last_mut()?;
+            let start = index;
+            // The flags of arcs don't need to be separated from what follows them.
+            if is_arc_flag(*command, parameters.len()) {
+                if !matches!(byte, b'0' | b'1') {
+                    return None;
+                }
+                index += 1;
+            } else {
+                index = number_end(bytes, index)?;
+            }
+            parameters.push(start..index);
+        }
+    }
+    Some(commands)
+}
+
+fn is_arc_flag(command: char, parameter_index: usize) -> bool {
+    command.eq_ignore_ascii_case(&'a') && matches!(parameter_index % 7, 3 | 4)
+}
+
+/// Returns the end of the number that starts at `start`, if one does.
+fn number_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let digits = |index: &mut usize| {
+        let digits_start = *index;
+        while bytes.get(*index).is_some_and(u8::is_ascii_digit) {
+            *index += 1;
+        }
+        *index > digits_start
+    };
+    let mut index = start;
+    if matches!(bytes.get(index), Some(b'+' | b'-')) {
+        index += 1;
+    }
+    let mut has_digits = digits(&mut index);
+    if bytes.get(index) == Some(&b'.') {
+        index += 1;
+        has_digits |= digits(&mut index);
+    }
+    if !has_digits {
+        return None;
+    }
+    if matches!(bytes.get(index), Some(b'e' | b'E')) {
+        let mut exponent_end = index + 1;
+        if matches!(bytes.get(exponent_end), Some(b'+' | b'-')) {
+            exponent_end += 1;
+        }
+        if digits(&mut exponent_end) {
+            index = exponent_end;
+        }
+    }
+    Some(index)
+}
+
+fn round_numbers(text: &str, document: &Document) -> Vec<(Range<usize>, String)> {
+    let mut edits = Vec::new();
+    for node in document.descendants().filter(|node| node.is_element()) {
+        for attribute in node.attributes() {
+            if attribute.namespace().is_some() || !ROUNDED_ATTRIBUTES.contains(&attribute.name()) {
+                continue;
+            }
+            let range = attribute.range_value();
+            let value = &text[range.clone()];
+            if value.contains('&') {
+                continue;
+            }
+            let numbers = if attribute.name() == "d" {
+                let Some(commands) = path_commands(value) else {
+                    continue;
+                };
+                commands
+                    .into_iter()
+                    .flat_map(|(command, parameters)| {
+                        parameters
+                            .into_iter()
+                            .enumerate()
+                            .filter(move |(index, _)| !is_arc_flag(command, *index))
+                            .map(|(_, range)| range)
+                    })
+                    .collect()
+            } else {
+                number_ranges(value)
+            };
+            if let Some(rounded) = round_numbers_in(value, numbers) {
+                edits.push((range, rounded));
+            }
+        }
+    }
+    edits
+}
+
+fn number_ranges(value: &str) -> Vec<Range<usize>> {
+    let bytes = value.as_bytes();
+    let mut ranges = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        if matches!(bytes[index], b'0'..=b'9' | b'.' | b'+' | b'-')
+            && let Some(end) = number_end(bytes, index)
+        {
+            ranges.push(index..end);
+            index = end;
+        } else {
+            index += 1;
+        }
+    }
+    ranges
+}
+
+/// Returns `value` with the numbers at `ranges` rounded, or `None` if that doesn't shorten any of
+/// them.
+fn round_numbers_in(value: &str, ranges: Vec<Range<usize>>) -> Option<String> {
+    let mut rounded_value = String::with_capacity(value.len());
+    let mut changed = false;
+    let mut copied_until = 0;
+    for range in ranges {
+        rounded_value.push_str(&value[copied_until..range.start]);
+        copied_until = range.end;
+        let number = &value[range.clone()];
+        let rounded = number
+            .parse::<f64>()
+            .ok()
+            .map(round_number)
+            .filter(|rounded| rounded.len() < number.len());
+        let Some(rounded) = rounded else {
+            rounded_value.push_str(number);
+            continue;
+        };
+        // Numbers only need separating where they'd otherwise run together.
+        if rounded.starts_with(|c: char| c.is_ascii_digit())
+            && rounded_value.ends_with(|c: char| c.is_ascii_digit() || c == '.')
+        {
+            rounded_value.push(' ');
+        }
+        rounded_value.push_str(&rounded);
+        if !rounded.contains('.') && value[range.end..].starts_with('.') {
+            rounded_value.push(' ');
+        }
+        changed = true;
+    }
+    rounded_value.push_str(&value[copied_until..]);
+    changed.then_some(rounded_value)
+}
+
+fn round_number(number: f64) -> String {
+    let rounded = format!("{:.*}", PRECISION, number);
+    let rounded = rounded.trim_end_matches('0').trim_end_matches('.');
+    if rounded == "-0" {
+        "0".to_string()
+    } else {
+        rounded.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_metadata() {
+        let text = r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" xmlns:sodipodi="http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd">
+  <!-- Generator: Inkscape -->
+  <metadata><rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"/></metadata>
+  <sodipodi:namedview id="view"/>
+  <defs>
+    <inkscape:path-effect id="effect"/>
+  </defs>
+  <rect inkscape:label="Background" width="10" height="10"/>
+</svg>"#;
+        assert_eq!(
+            Pass::StripMetadata.apply(text).as_deref(),
+            Some(
+                r#"<svg xmlns="http://www.w3.org/2000/svg">
+  <rect width="10" height="10"/>
+</svg>"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_collapse_groups() {
+        let text = r#"<svg xmlns="http://www.w3.org/2000/svg"><g><g><rect/></g><g/></g><g fill="red"><rect/></g><g><set attributeName="opacity" to="0"/><rect/></g></svg>"#;
+        assert_eq!(
+            Pass::CollapseGroups.apply(text).as_deref(),
+            Some(
+                r#"<svg xmlns="http://www.w3.org/2000/svg"><rect/><g fill="red"><rect/></g><g><set attributeName="opacity" to="0"/><rect/></g></svg>"#
+            )
+        );
+
+        // Unwrapping groups would change what these rules select.
+        for style in [
+            "g { fill: red }",
+            "@media print { svg > rect { fill: red } }",
+        ] {
+            let text = format!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg"><style>{style}</style><g><rect/></g></svg>"#
+            );
+            assert_eq!(Pass::CollapseGroups.apply(&text), None, "{style}");
+        }
+        let text = r#"<svg xmlns="http://www.w3.org/2000/svg"><style>/* g */ .a, svg rect { fill: red }</style><g><rect/></g></svg>"#;
+        assert_eq!(
+            Pass::CollapseGroups.apply(text).as_deref(),
+            Some(
+                r#"<svg xmlns="http://www.w3.org/2000/svg"><style>/* g */ .a, svg rect { fill: red }</style><rect/></svg>"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_merge_paths() {
+        let text = r#"<svg xmlns="http://www.w3.org/2000/svg">
+  <path fill="red" d="M0 0h10v10z"/>
+  <path fill="red" d="m20 0 10 0v10z"/>
+  <path fill="red" d="M25 5h10v10z"/>
+  <path fill="blue" d="M50 0h10v10z"/>
+</svg>"#;
+        assert_eq!(
+            Pass::MergePaths.apply(text).as_deref(),
+            Some(
+                r#"<svg xmlns="http://www.w3.org/2000/svg">
+  <path fill="red" d="M0 0h10v10z M20 0 l 10 0v10z"/>
+  <path fill="red" d="M25 5h10v10z"/>
+  <path fill="blue" d="M50 0h10v10z"/>
+</svg>"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_round_numbers() {
+        let text = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24.00001 24"><path d="M1.23456.5a1 1 0 011.0001.5L-0.00001 2e-9" transform="scale(1.23456)"/></svg>"#;
+        assert_eq!(
+            Pass::RoundNumbers.apply(text).as_deref(),
+            Some(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><path d="M1.235.5a1 1 0 01 1 .5L0 0" transform="scale(1.23456)"/></svg>"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_savings() {
+        let text = r#"<svg xmlns="http://www.w3.org/2000/svg"><metadata/><g><path d="M0 0h1"/><path d="M5 5h1"/></g></svg>"#;
+        assert_eq!(
+            savings(text),
+            [
+                PassSavings {
+                    pass: Pass::StripMetadata,
+                    bytes: 11,
+                    elements: 1,
+                },
+                PassSavings {
+                    pass: Pass::CollapseGroups,
+                    bytes: 7,
+                    elements: 1,
+                },
+                PassSavings {
+                    pass: Pass::MergePaths,
+                    bytes: 11,
+                    elements: 1,
+                },
+                PassSavings {
+                    pass: Pass::RoundNumbers,
+                    bytes: 0,
+                    elements: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_path_bounds() {
+        assert_eq!(path_bounds("M1 2 l3 4 H0 V-1 z"), Some([0., -1., 4., 6.]));
+        assert_eq!(path_bounds("M0 0 C 1 2 3 4 5 6"), Some([0., 0., 5., 6.]));
+        assert_eq!(path_bounds("M0 0 L1"), None);
+    }
+}
@@ -0,0 +1,76 @@
+use usvg::tiny_skia_path::{PathSegment, Point};
+
+/// The points that define a path, in the coordinates of the SVG's size.
+#[derive(Debug, Default, PartialEq)]
+pub struct PathPoints {
+    /// The points that segments start and end at.
+    pub anchors: Vec<(f32, f32)>,
+    /// The control points of curves.
+    pub controls: Vec<(f32, f32)>,
+    /// The lines from each control point to the anchor it steers away from or towards.
+    pub handles: Vec<((f32, f32), (f32, f32))>,
+}
+
+pub fn path_points(path: &usvg::Path) -> PathPoints {
+    let transform = path.abs_transform();
+    let map = |mut point: Point| {
+        transform.map_point(&mut point);
+        (point.x, point.y)
+    };
+
+    let mut points = PathPoints::default();
+    let mut current = (0., 0.);
+    for segment in path.data().segments() {
+        match segment {
+            PathSegment::MoveTo(point) | PathSegment::LineTo(point) => {
+                current = map(point);
+                points.anchors.push(current);
+            }
+            PathSegment::QuadTo(control, point) => {
+                let (control, point) = (map(control), map(point));
+                points.controls.push(control);
+                points.handles.push((current, control));
+                points.handles.push((control, point));
+                points.anchors.push(point);
+                current = point;
+            }
+            PathSegment::CubicTo(control1, control2, point) => {
+                let (control1, control2, point) = (map(control1), map(control2), map(point));
+                points.controls.extend([control1, control2]);
+                points.handles.push((current, control1));
+                points.handles.push((control2, point));
+                points.anchors.push(point);
+                current = point;
+            }
+            PathSegment::Close => {}
+        }
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_points() {
+        let tree = usvg::Tree::from_str(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20">
+                <path transform="translate(1 2)" d="M0 0 L10 0 C10 5 5 10 0 10 Z"/>
+            </svg>"#,
+            &usvg::Options::default(),
+        )
+        .unwrap();
+        let Some(usvg::Node::Path(path)) = tree.root().children().first() else {
+            panic!("expected a path");
+        };
+        assert_eq!(
+            path_points(path),
+            PathPoints {
+                anchors: vec![(1., 2.), (11., 2.), (1., 12.)],
+                controls: vec![(11., 7.), (6., 12.)],
+                handles: vec![((11., 2.), (11., 7.)), ((6., 12.), (1., 12.))],
+            }
+        );
+    }
+}
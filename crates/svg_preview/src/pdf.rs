@@ -0,0 +1,299 @@
+use std::fmt::Write as _;
+
+use usvg::tiny_skia_path::{PathSegment, Point};
+use usvg::{
+    FillRule, Group, LineCap, LineJoin, Node, Paint, Path, Transform, Tree, tiny_skia_path,
+};
+
+/// Converts `tree` to a single-page vector PDF, where a user unit of the SVG maps to a point.
+///
+/// Only what PDF can express without extra machinery is converted: paths with solid fills and
+/// strokes, and text as its outlines. Gradients and patterns are approximated with the color of
+/// their first stop or a neutral gray, group opacity is applied to each path separately, and
+/// images, filters, clip paths and masks are skipped.
+pub fn pdf(tree: &Tree) -> Vec<u8> {
+    let mut writer = ContentWriter::default();
+    let (width, height) = (tree.size().width(), tree.size().height());
+    // PDF's y axis points up, whereas SVG's points down.
+    writeln!(writer.content, "1 0 0 -1 0 {} cm", number(height)).ok();
+    writer.group(tree.root(), 1.);
+
+    let mut graphics_states = String::new();
+    for (index, (fill_opacity, stroke_opacity)) in writer.graphics_states.iter().enumerate() {
+        write!(
+            graphics_states,
+            "/GS{index} << /ca {} /CA {} >> ",
+            number(*fill_opacity),
+            number(*stroke_opacity)
+        )
+        .ok();
+    }
+
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+            /Resources << /ExtGState << {graphics_states}>> >> /Contents 4 0 R >>",
+            number(width),
+            number(height)
+        ),
+        format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            writer.content.len(),
+            writer.content
+        ),
+    ];
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        write!(pdf, "{} 0 obj\n{object}\nendobj\n", index + 1).ok();
+    }
+    let xref_offset = pdf.len();
+    write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).ok();
+    for offset in offsets {
+        write!(pdf, "{offset:010} 00000 n \n").ok();
+    }
+    write!(
+        pdf,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+        objects.len() + 1
+    )
+    .ok();
+    pdf.into_bytes()
+}
+
+#[derive(Default)]
+struct ContentWriter {
+    content: String,
+    /// The fill and stroke opacities of the graphics states the content refers to by index.
+    graphics_states: Vec<(f32, f32)>,
+}
+
+impl ContentWriter {
+    fn group(&mut self, group: &Group, opacity: f32) {
+        let opacity = opacity * group.opacity().get();
+        for node in group.children() {
+            match node {
+                Node::Group(group) => self.group(group, opacity),
+                Node::Path(path) => self.path(path, opacity),
+                Node::Text(text) => self.group(text.flattened(), opacity),
+                Node::Image(_) => {}
+            }
+        }
+    }
+
+    fn path(&mut self, path: &Path, opacity: f32) {
+        if !path.is_visible() || (path.fill().is_none() && path.stroke().is_none()) {
+            return;
+        }
+
+        let mut fill_opacity = opacity;
+        let mut stroke_opacity = opacity;
+        self.content.push_str("q\n");
+        write_transform(&mut self.content, path.abs_transform());
+        if let Some(fill) = path.fill() {
+            let (color, paint_opacity) = paint_color(fill.paint());
+            fill_opacity *= fill.opacity().get() * paint_opacity;
+            writeln!(self.content, "{} rg", color_components(color)).ok();
+        }
+        if let Some(stroke) = path.stroke() {
+            let (color, paint_opacity) = paint_color(stroke.paint());
+            stroke_opacity *= stroke.opacity().get() * paint_opacity;
+            writeln!(self.content, "{} RG", color_components(color)).ok();
+            writeln!(self.content, "{} w", number(stroke.width().get())).ok();
+            let cap = match stroke.linecap() {
+                LineCap::Butt => 0,
+                LineCap::Round => 1,
+                LineCap::Square => 2,
+            };
+            let join = match stroke.linejoin() {
+                LineJoin::Miter | LineJoin::MiterClip => 0,
+                LineJoin::Round => 1,
+                LineJoin::Bevel => 2,
+            };
+            writeln!(
+                self.content,
+                "{cap} J {join} j {} M",
+                number(stroke.miterlimit().get())
+            )
+            .ok();
+            if let Some(dash_array) = stroke.dasharray() {
+                let dash_array = dash_array
+                    .iter()
+                    .map(|dash| number(*dash))
+                    .collect::<Vec<_>>();
+                writeln!(
+                    self.content,
+                    "[{}] {} d",
+                    dash_array.join(" "),
+                    number(stroke.dashoffset())
+                )
+                .ok();
+            }
+        }
+        if fill_opacity < 1. || stroke_opacity < 1. {
+            let index = self.graphics_state(fill_opacity, stroke_opacity);
+            writeln!(self.content, "/GS{index} gs").ok();
+        }
+
+        write_segments(&mut self.content, path.data());
+        let operator = match (path.fill().map(|fill| fill.rule()), path.stroke().is_some()) {
+            (Some(FillRule::NonZero), true) => "B",
+            (Some(FillRule::EvenOdd), true) => "B*",
+            (Some(FillRule::NonZero), false) => "f",
+            (Some(FillRule::EvenOdd), false) => "f*",
+            (None, _) => "S",
+        };
+        writeln!(self.content, "{operator}\nQ").ok();
+    }
+
+    fn graphics_state(&mut self, fill_opacity: f32, stroke_opacity: f32) -> usize {
+        let state = (fill_opacity, stroke_opacity);
+        match self
+            .graphics_states
+            .iter()
+            .position(|other| *other == state)
+        {
+            Some(index) => index,
+            None => {
+                self.graphics_states.push(state);
+                self.graphics_states.len() - 1
+            }
+        }
+    }
+}
+
+fn write_transform(content: &mut String, transform: Transform) {
+    if !transform.is_identity() {
+        writeln!(
+            content,
+            "{} {} {} {} {} {} cm",
+            number(transform.sx),
+            number(transform.ky),
+            number(transform.kx),
+            number(transform.sy),
+            number(transform.tx),
+            number(transform.ty)
+        )
+        .ok();
+    }
+}
+
+fn write_segments(content: &mut String, path: &tiny_skia_path::Path) {
+    let mut current = Point::zero();
+    for segment in path.segments() {
+        match segment {
+            PathSegment::MoveTo(point) => {
+                writeln!(content, "{} {} m", number(point.x), number(point.y)).ok();
+                current = point;
+            }
+            PathSegment::LineTo(point) => {
+                writeln!(content, "{} {} l", number(point.x), number(point.y)).ok();
+                current = point;
+            }
+            PathSegment::QuadTo(control, point) => {
+                // PDF only has cubic curves, which can represent quadratic ones exactly.
+                let control1 = current + (control - current).scaled(2. / 3.);
+                let control2 = point + (control - point).scaled(2. / 3.);
+                write_cubic(content, control1, control2, point);
+                current = point;
+            }
+            PathSegment::CubicTo(control1, control2, point) => {
+                write_cubic(content, control1, control2, point);
+                current = point;
+            }
+            PathSegment::Close => content.push_str("h\n"),
+        }
+    }
+}
+
+fn write_cubic(content: &mut String, control1: Point, control2: Point, point: Point) {
+    writeln!(
+        content,
+        "{} {} {} {} {} {} c",
+        number(control1.x),
+        number(control1.y),
+        number(control2.x),
+        number(control2.y),
+        number(point.x),
+        number(point.y)
+    )
+    .ok();
+}
+
+/// Returns the solid color that represents `paint`, and its opacity.
+fn paint_color(paint: &Paint) -> (usvg::Color, f32) {
+    let first_stop = match paint {
+        Paint::Color(color) => return (*color, 1.),
+        Paint::LinearGradient(gradient) => gradient.stops().first(),
+        Paint::RadialGradient(gradient) => gradient.stops().first(),
+        Paint::Pattern(_) => None,
+    };
+    match first_stop {
+        Some(stop) => (stop.color(), stop.opacity().get()),
+        None => (usvg::Color::new_rgb(128, 128, 128), 1.),
+    }
+}
+
+fn color_components(color: usvg::Color) -> String {
+    format!(
+        "{} {} {}",
+        number(color.red as f32 / 255.),
+        number(color.green as f32 / 255.),
+        number(color.blue as f32 / 255.)
+    )
+}
+
+/// Formats `value` with up to four decimals and without trailing zeros, as PDF doesn't accept
+/// exponents.
+fn number(value: f32) -> String {
+    let formatted = format!("{value:.4}");
+    let formatted = formatted.trim_end_matches('0').trim_end_matches('.');
+    match formatted {
+        "-0" | "" => "0".to_string(),
+        _ => formatted.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pdf() {
+        let tree = Tree::from_str(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10">
+                <rect width="10" height="10" fill="red" fill-opacity="0.5"/>
+                <path d="M0 0 Q 10 10 20 0" fill="none" stroke="blue"/>
+            </svg>"#,
+            &usvg::Options::default(),
+        )
+        .unwrap();
+        let pdf = String::from_utf8(pdf(&tree)).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.contains("/MediaBox [0 0 20 10]"));
+        assert!(pdf.contains("1 0 0 rg"));
+        assert!(pdf.contains("/GS0 << /ca 0.5 /CA 1 >>"));
+        assert!(pdf.contains("0 0 1 RG"));
+        assert!(pdf.contains(" c\nS\n"));
+
+        // The cross-reference table points at each object.
+        let xref = pdf.split("xref\n").nth(1).unwrap();
+        for (index, entry) in xref.lines().skip(2).take(4).enumerate() {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj", index + 1)));
+        }
+    }
+
+    #[test]
+    fn test_number() {
+        assert_eq!(number(1.), "1");
+        assert_eq!(number(0.5), "0.5");
+        assert_eq!(number(-0.00001), "0");
+        assert_eq!(number(1e-7), "0");
+        assert_eq!(number(12.34567), "12.3457");
+    }
+}
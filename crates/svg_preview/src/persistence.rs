@@ -0,0 +1,116 @@
+use std::path::PathBuf;
+
+use db::{
+    query,
+    sqlez::{domain::Domain, thread_safe_connection::ThreadSafeConnection},
+    sqlez_macros::sql,
+};
+use workspace::{ItemId, WorkspaceDb, WorkspaceId};
+
+pub struct SvgPreviewDb(ThreadSafeConnection);
+
+impl Domain for SvgPreviewDb {
+    const NAME: &str = stringify!(SvgPreviewDb);
+
+    const MIGRATIONS: &[&str] = &[sql!(
+            CREATE TABLE svg_previews (
+                workspace_id INTEGER,
+                item_id INTEGER UNIQUE,
+
+                svg_path BLOB,
+                following INTEGER NOT NULL,
+                scale_factor REAL NOT NULL,
+                offset_x REAL NOT NULL,
+                offset_y REAL NOT NULL,
+                fit_to_window INTEGER NOT NULL,
+
+                PRIMARY KEY(workspace_id, item_id),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+            ) STRICT;
+    )];
+}
+
+db::static_connection!(SVG_PREVIEW, SvgPreviewDb, [WorkspaceDb]);
+
+/// The state of a preview that's restored along with its workspace.
+pub struct SerializedSvgPreview {
+    /// The SVG the preview is on, if any.
+    pub svg_path: Option<PathBuf>,
+    /// Whether the preview follows the last active SVG editor.
+    pub following: bool,
+    pub scale_factor: f32,
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub fit_to_window: bool,
+}
+
+impl SvgPreviewDb {
+    pub async fn save_svg_preview(
+        &self,
+        item_id: ItemId,
+        workspace_id: WorkspaceId,
+        preview: SerializedSvgPreview,
+    ) -> anyhow::Result<()> {
+        self.save_svg_preview_row(
+            item_id,
+            workspace_id,
+            preview.svg_path,
+            preview.following,
+            preview.scale_factor,
+            preview.offset_x,
+            preview.offset_y,
+            preview.fit_to_window,
+        )
+        .await
+    }
+
+    pub fn get_svg_preview(
+        &self,
+        item_id: ItemId,
+        workspace_id: WorkspaceId,
+    ) -> anyhow::Result<Option<SerializedSvgPreview>> {
+        Ok(self.get_svg_preview_row(item_id, workspace_id)?.map(
+            |(svg_path, following, scale_factor, offset_x, offset_y, fit_to_window)| {
+                SerializedSvgPreview {
+                    svg_path,
+                    following,
+                    scale_factor,
+                    offset_x,
+                    offset_y,
+                    fit_to_window,
+                }
+            },
+        ))
+    }
+
+    query! {
+        async fn save_svg_preview_row(
+            item_id: ItemId,
+            workspace_id: WorkspaceId,
+            svg_path: Option<PathBuf>,
+            following: bool,
+            scale_factor: f32,
+            offset_x: f32,
+            offset_y: f32,
+            fit_to_window: bool
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO svg_previews(
+                item_id, workspace_id, svg_path, following, scale_factor, offset_x, offset_y,
+                fit_to_window
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        }
+    }
+
+    query! {
+        fn get_svg_preview_row(
+            item_id: ItemId,
+            workspace_id: WorkspaceId
+        ) -> Result<Option<(Option<PathBuf>, bool, f32, f32, f32, bool)>> {
+            SELECT svg_path, following, scale_factor, offset_x, offset_y, fit_to_window
+            FROM svg_previews
+            WHERE item_id = ? AND workspace_id = ?
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use gpui::{Pixels, px};
+
+/// The thickness of the rulers drawn along the edges of the preview.
+pub const RULER_SIZE: Pixels = px(24.);
+/// The minimum distance between two labeled ticks.
+const MIN_MAJOR_TICK_SPACING: f32 = 60.;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tick {
+    /// The distance of the tick from the start of the ruler.
+    pub offset: Pixels,
+    /// The user unit coordinate the tick marks, if it is a labeled tick.
+    pub label: Option<f32>,
+}
+
+/// Computes the ticks of a ruler that is `length` pixels long, where the start of the ruler
+/// corresponds to the user unit coordinate `start` and one user unit spans `pixels_per_unit`
+/// pixels. Returns the ticks and the spacing between labeled ticks in user units.
+pub fn ticks(start: f32, pixels_per_unit: f32, length: Pixels) -> (Vec<Tick>, f32) {
+    if !(pixels_per_unit.is_finite() && pixels_per_unit > 0.) {
+        return (Vec::new(), 0.);
+    }
+
+    let (major_step, subdivisions) = major_step(MIN_MAJOR_TICK_SPACING / pixels_per_unit);
+    let minor_step = major_step / subdivisions as f32;
+    let end = start + f32::from(length) / pixels_per_unit;
+
+    let mut ticks = Vec::new();
+    let mut index = (start / minor_step).ceil() as i64;
+    loop {
+        let value = index as f32 * minor_step;
+        if value > end {
+            break;
+        }
+        let is_major = index.rem_euclid(subdivisions) == 0;
+        ticks.push(Tick {
+            offset: px((value - start) * pixels_per_unit),
+            label: is_major.then_some(value),
+        });
+        index += 1;
+    }
+    (ticks, major_step)
+}
+
+/// Rounds `min_step` up to the next "nice" step of the form 1, 2 or 5 times a power of ten and
+/// returns it along with the number of subdivisions to draw between labeled ticks.
+fn major_step(min_step: f32) -> (f32, i64) {
+    let magnitude = 10f32.powf(min_step.log10().floor());
+    match min_step / magnitude {
+        mantissa if mantissa <= 1. => (magnitude, 5),
+        mantissa if mantissa <= 2. => (2. * magnitude, 4),
+        mantissa if mantissa <= 5. => (5. * magnitude, 5),
+        _ => (10. * magnitude, 5),
+    }
+}
+
+/// Formats a tick label with just enough decimals to distinguish ticks that are `step` apart.
+pub fn format_tick_label(value: f32, step: f32) -> String {
+    let decimals = (-step.log10().floor()).max(0.) as usize;
+    let label = format!("{value:.decimals$}");
+    match label.strip_prefix('-') {
+        Some(unsigned) if unsigned.chars().all(|c| c == '0' || c == '.') => unsigned.to_string(),
+        _ => label,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ticks() {
+        let (ticks, step) = ticks(-5., 10., px(200.));
+        assert_eq!(step, 10.);
+        let labels = ticks
+            .iter()
+            .filter_map(|tick| tick.label)
+            .collect::<Vec<_>>();
+        assert_eq!(labels, vec![0., 10.]);
+        assert_eq!(ticks[0].offset, px(10.));
+        assert_eq!(ticks.len(), 10);
+    }
+
+    #[test]
+    fn test_format_tick_label() {
+        assert_eq!(format_tick_label(120., 50.), "120");
+        assert_eq!(format_tick_label(0.5, 0.5), "0.5");
+        assert_eq!(format_tick_label(-0.001, 0.1), "0.0");
+    }
+}
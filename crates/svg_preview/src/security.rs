@@ -0,0 +1,131 @@
+use std::ops::Range;
+
+use roxmltree::{Document, Node};
+
+use crate::animation;
+
+/// Returns the parts of `document` that would run code or make requests if it were opened in a
+/// browser: scripts, event handler attributes, and the hosts of resources loaded over the network.
+pub fn security_risks(document: &Document) -> Vec<String> {
+    let mut risks = Vec::new();
+    let mut add = |risk: String| {
+        if !risks.contains(&risk) {
+            risks.push(risk);
+        }
+    };
+    for node in document.descendants().filter(|node| node.is_element()) {
+        if node.tag_name().name() == "script" {
+            add("scripts".to_string());
+        }
+        for attribute in node.attributes() {
+            if is_event_handler(attribute.name()) {
+                add("event handlers".to_string());
+            } else if let Some(host) = network_host(attribute.name(), attribute.value()) {
+                add(format!("requests to {host}"));
+            }
+        }
+    }
+    risks
+}
+
+/// Returns `text` without its scripts, event handler attributes and references to resources on
+/// the network, or `None` if it has none of them or can't be parsed.
+pub fn sandbox(text: &str) -> Option<String> {
+    let document = animation::parse_document(text).ok()?;
+    let mut removals = Vec::<Range<usize>>::new();
+    for node in document.descendants().filter(|node| node.is_element()) {
+        if node.ancestors().skip(1).any(is_script) {
+            continue;
+        }
+        if is_script(node) {
+            removals.push(node.range());
+            continue;
+        }
+        for attribute in node.attributes() {
+            if is_event_handler(attribute.name())
+                || network_host(attribute.name(), attribute.value()).is_some()
+            {
+                // Removes the whitespace separating the attribute from the previous one too.
+                let range = attribute.range();
+                let start = text[..range.start].trim_end().len();
+                removals.push(start..range.end);
+            }
+        }
+    }
+    if removals.is_empty() {
+        return None;
+    }
+    removals.sort_by_key(|range| std::cmp::Reverse(range.start));
+
+    let mut text = text.to_string();
+    for range in removals {
+        text.replace_range(range, "");
+    }
+    Some(text)
+}
+
+fn is_script(node: Node) -> bool {
+    node.is_element() && node.tag_name().name() == "script"
+}
+
+fn is_event_handler(attribute_name: &str) -> bool {
+    attribute_name.len() > 2 && attribute_name.starts_with("on")
+}
+
+/// Returns the host that the `href` or `src` attribute with `value` loads a resource from, if it's
+/// loaded over the network.
+fn network_host<'a>(attribute_name: &str, value: &'a str) -> Option<&'a str> {
+    if !matches!(attribute_name, "href" | "src") {
+        return None;
+    }
+    let value = value.trim();
+    let url = ["http://", "https://", "//"].iter().find_map(|scheme| {
+        value
+            .get(..scheme.len())
+            .filter(|prefix| prefix.eq_ignore_ascii_case(scheme))
+            .map(|_| &value[scheme.len()..])
+    })?;
+    let host = url.split(['/', '?', '#']).next().unwrap_or(url);
+    (!host.is_empty()).then_some(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNTRUSTED: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" onload="steal()"><script>steal()</script><image href="https://tracker.example/pixel.png?id=1"/><use xlink:href="//cdn.example/icons.svg#gear"/><image href="logo.png" onclick="steal()"/></svg>"#;
+
+    #[test]
+    fn test_security_risks() {
+        let document = Document::parse(UNTRUSTED).unwrap();
+        assert_eq!(
+            security_risks(&document),
+            [
+                "event handlers",
+                "scripts",
+                "requests to tracker.example",
+                "requests to cdn.example",
+            ]
+        );
+
+        let document = Document::parse(
+            r#"<svg xmlns="http://www.w3.org/2000/svg"><image href="logo.png"/></svg>"#,
+        )
+        .unwrap();
+        assert!(security_risks(&document).is_empty());
+    }
+
+    #[test]
+    fn test_sandbox() {
+        assert_eq!(
+            sandbox(UNTRUSTED).as_deref(),
+            Some(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink"><image/><use/><image href="logo.png"/></svg>"#
+            )
+        );
+        assert_eq!(
+            sandbox(r#"<svg xmlns="http://www.w3.org/2000/svg"><rect/></svg>"#),
+            None
+        );
+    }
+}
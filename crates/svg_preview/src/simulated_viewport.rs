@@ -0,0 +1,49 @@
+use gpui::{Size, size};
+
+/// Parses a user-entered viewport size such as `320x240` or `64` for a square viewport. Returns
+/// `Some(None)` for empty text, which turns the simulation off.
+pub fn parse_viewport_size(text: &str) -> Option<Option<Size<f32>>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Some(None);
+    }
+    let parse_length = |text: &str| {
+        let length = text.trim().trim_end_matches("px").parse::<f32>().ok()?;
+        (length.is_finite() && length > 0.).then_some(length)
+    };
+    let viewport = match text.split_once(['x', 'X', '×']) {
+        Some((width, height)) => size(parse_length(width)?, parse_length(height)?),
+        None => {
+            let length = parse_length(text)?;
+            size(length, length)
+        }
+    };
+    Some(Some(viewport))
+}
+
+/// Sets the size that percentage sizes of the root element resolve against.
+pub fn apply_simulated_viewport(options: &mut usvg::Options, viewport: Option<Size<f32>>) {
+    if let Some(viewport) = viewport
+        && let Some(size) = usvg::Size::from_wh(viewport.width, viewport.height)
+    {
+        options.default_size = size;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_viewport_size() {
+        assert_eq!(parse_viewport_size("320x240"), Some(Some(size(320., 240.))));
+        assert_eq!(
+            parse_viewport_size(" 320px × 240px "),
+            Some(Some(size(320., 240.)))
+        );
+        assert_eq!(parse_viewport_size("64"), Some(Some(size(64., 64.))));
+        assert_eq!(parse_viewport_size(""), Some(None));
+        assert_eq!(parse_viewport_size("0x10"), None);
+        assert_eq!(parse_viewport_size("wide"), None);
+    }
+}
@@ -0,0 +1,321 @@
+use std::ops::Range;
+
+use collections::HashSet;
+
+use crate::animation;
+use crate::element_export;
+
+/// The prefix of the ids that [`with_source_ids`] gives to elements without one.
+const SOURCE_ID_PREFIX: &str = "zed-source-";
+
+/// Maps the elements of an SVG's source to the nodes of a tree parsed from it, in both
+/// directions.
+pub struct SourceMap {
+    /// A tree parsed from the source with an id on every element, since trees don't otherwise
+    /// know where their nodes came from.
+    tree: usvg::Tree,
+    /// The elements of the source, in document order.
+    elements: Vec<SourceElement>,
+}
+
+pub struct SourceElement {
+    pub range: Range<usize>,
+    /// The number of elements the element is nested in.
+    pub depth: usize,
+    /// The element's tag name, id and classes, such as `path#arrow .stroke-2`.
+    pub label: String,
+    /// The element's id in [`SourceMap::tree`].
+    id: String,
+    /// The element's own id and classes, as CSS selectors such as `#arrow` and `.stroke-2`.
+    selectors: Vec<String>,
+}
+
+impl SourceMap {
+    pub fn new(text: &str, options: &usvg::Options) -> Option<Self> {
+        let document = animation::parse_document(text).ok()?;
+        let elements = document
+            .descendants()
+            .filter(|node| node.is_element())
+            .map(|node| {
+                let id = match node.attribute("id") {
+                    Some(id) => id.to_string(),
+                    None => source_id(node.range().start),
+                };
+                SourceElement {
+                    range: node.range(),
+                    depth: node
+                        .ancestors()
+                        .skip(1)
+                        .filter(|node| node.is_element())
+                        .count(),
+                    label: element_label(node),
+                    id,
+                    selectors: element_selectors(node),
+                }
+            })
+            .collect();
+        let tree = usvg::Tree::from_str(&with_source_ids(text, &document)?, options).ok()?;
+        Some(Self { tree, elements })
+    }
+
+    /// Returns the offset in the source of the start tag of the topmost element at `point`, given
+    /// in the coordinates of the SVG's size.
+    pub fn offset_at(&self, point: (f32, f32)) -> Option<usize> {
+        Some(self.element_at(point)?.range.start)
+    }
+
+    /// Returns the tag name, id and classes of the topmost element at `point`, such as
+    /// `path#arrow .stroke-2`.
+    pub fn label_at(&self, point: (f32, f32)) -> Option<&str> {
+        Some(&self.element_at(point)?.label)
+    }
+
+    /// Returns the tag name, id and classes of the topmost element at `point`, along with the path
+    /// it's rendered as, if it's rendered as a single path.
+    pub fn label_and_path_at(&self, point: (f32, f32)) -> Option<(&str, Option<&usvg::Path>)> {
+        let element = self.element_at(point)?;
+        let path = match self.tree.node_by_id(&element.id) {
+            Some(usvg::Node::Path(path)) => Some(path.as_ref()),
+            _ => None,
+        };
+        Some((&element.label, path))
+    }
+
+    /// Returns the elements of the source, in document order.
+    pub fn elements(&self) -> &[SourceElement] {
+        &self.elements
+    }
+
+    /// Returns the innermost element whose markup contains `offset`.
+    pub fn element_containing(&self, offset: usize) -> Option<&SourceElement> {
+        self.elements_containing(offset).next()
+    }
+
+    /// Returns the elements whose markup contains `offset`, from the innermost one outward.
+    fn elements_containing(&self, offset: usize) -> impl Iterator<Item = &SourceElement> {
+        // Elements are nested in document order, so the innermost containing element comes last.
+        self.elements
+            .iter()
+            .rev()
+            .filter(move |element| element.range.start <= offset && offset < element.range.end)
+    }
+
+    /// Returns the ids and classes of the elements, as CSS selectors such as `#arrow` and
+    /// `.stroke-2`, in the order they first appear.
+    pub fn selectors(&self) -> Vec<&str> {
+        let mut seen = HashSet::default();
+        self.elements
+            .iter()
+            .flat_map(|element| &element.selectors)
+            .map(String::as_str)
+            .filter(|selector| seen.insert(*selector))
+            .collect()
+    }
+
+    /// Returns the bounds of the rendered elements that `selector`, as returned by
+    /// [`Self::selectors`], matches, in the coordinates of the SVG's size.
+    pub fn selector_bounds(&self, selector: &str) -> Option<usvg::Rect> {
+        self.elements
+            .iter()
+            .filter(|element| element.selectors.iter().any(|other| other == selector))
+            .filter_map(|element| self.tree.node_by_id(&element.id))
+            .map(|node| node.abs_stroke_bounding_box())
+            .reduce(|bounds, other| {
+                usvg::Rect::from_ltrb(
+                    bounds.left().min(other.left()),
+                    bounds.top().min(other.top()),
+                    bounds.right().max(other.right()),
+                    bounds.bottom().max(other.bottom()),
+                )
+                .unwrap_or(bounds)
+            })
+    }
+
+    /// Returns the path rendered for the innermost rendered element whose markup contains
+    /// `offset`, if it's rendered as a single path, as shapes such as `rect`s are.
+    pub fn path_at(&self, offset: usize) -> Option<&usvg::Path> {
+        match self
+            .elements_containing(offset)
+            .find_map(|element| self.tree.node_by_id(&element.id))?
+        {
+            usvg::Node::Path(path) => Some(path.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Returns the solid colors that the text elements are filled with, along with the offsets
+    /// of their start tags.
+    pub fn text_fills(&self) -> Vec<(usize, gpui::Rgba)> {
+        let mut fills = self
+            .elements
+            .iter()
+            .filter_map(|element| match self.tree.node_by_id(&element.id)? {
+                usvg::Node::Text(text) => Some((element.range.start, text)),
+                _ => None,
+            })
+            .flat_map(|(offset, text)| {
+                text.chunks()
+                    .iter()
+                    .flat_map(|chunk| chunk.spans())
+                    .filter_map(|span| match span.fill()?.paint() {
+                        usvg::Paint::Color(color) => Some(*color),
+                        _ => None,
+                    })
+                    .map(move |color| (offset, color))
+            })
+            .collect::<Vec<_>>();
+        fills.dedup();
+        fills
+            .into_iter()
+            .map(|(offset, color)| {
+                let channel = |value: u8| value as f32 / 255.;
+                let color = gpui::Rgba {
+                    r: channel(color.red),
+                    g: channel(color.green),
+                    b: channel(color.blue),
+                    a: 1.,
+                };
+                (offset, color)
+            })
+            .collect()
+    }
+
+    fn element_at(&self, point: (f32, f32)) -> Option<&SourceElement> {
+        let id = element_export::element_id_at(&self.tree, point)?;
+        self.elements.iter().find(|element| element.id == id)
+    }
+
+    /// Returns the bounds of the innermost rendered element whose markup contains `offset`, in
+    /// the coordinates of the SVG's size.
+    pub fn bounds_at(&self, offset: usize) -> Option<usvg::Rect> {
+        self.elements_containing(offset)
+            .find_map(|element| self.tree.node_by_id(&element.id))
+            .map(|node| node.abs_stroke_bounding_box())
+    }
+}
+
+fn element_label(node: roxmltree::Node<'_, '_>) -> String {
+    let mut label = node.tag_name().name().to_string();
+    if let Some(id) = node.attribute("id") {
+        label.push('#');
+        label.push_str(id);
+    }
+    for class in node
+        .attribute("class")
+        .unwrap_or_default()
+        .split_whitespace()
+    {
+        label.push_str(" .");
+        label.push_str(class);
+    }
+    label
+}
+
+fn element_selectors(node: roxmltree::Node<'_, '_>) -> Vec<String> {
+    let id = node.attribute("id").map(|id| format!("#{id}"));
+    let classes = node
+        .attribute("class")
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(|class| format!(".{class}"));
+    id.into_iter().chain(classes).collect()
+}
+
+fn source_id(offset: usize) -> String {
+    format!("{SOURCE_ID_PREFIX}{offset}")
+}
+
+/// Returns `text` with an id on every element that has none, which encodes the offset of the
+/// element in `text`.
+fn with_source_ids(text: &str, document: &roxmltree::Document<'_>) -> Option<String> {
+    let mut insertions = document
+        .descendants()
+        .filter(|node| node.is_element() && node.attribute("id").is_none())
+        .map(|node| {
+            let start = node.range().start;
+            Some((tag_name_end(text, start)?, start))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    insertions.sort_by_key(|(position, _)| std::cmp::Reverse(*position));
+
+    let mut text = text.to_string();
+    for (position, start) in insertions {
+        text.insert_str(position, &format!(" id=\"{}\"", source_id(start)));
+    }
+    Some(text)
+}
+
+/// Returns the offset just past the name of the tag that starts at `tag_start` in `text`, where
+/// attributes can be inserted.
+pub(crate) fn tag_name_end(text: &str, tag_start: usize) -> Option<usize> {
+    let name_len = text
+        .get(tag_start + 1..)?
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')?;
+    Some(tag_start + 1 + name_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_map() {
+        let text = r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10">
+    <rect id="left" class="a  b" width="10" height="10"/>
+    <g><rect x="10" width="10" height="10"/></g>
+    <defs><rect id="unused" width="5" height="5"/></defs>
+</svg>"#;
+        let source_map = SourceMap::new(text, &usvg::Options::default()).unwrap();
+
+        let left = text.find("<rect id").unwrap();
+        let right = text.find("<rect x").unwrap();
+        assert_eq!(source_map.offset_at((5., 5.)), Some(left));
+        assert_eq!(source_map.offset_at((15., 5.)), Some(right));
+        assert_eq!(source_map.offset_at((25., 5.)), None);
+        assert_eq!(source_map.label_at((5., 5.)), Some("rect#left .a .b"));
+        assert_eq!(source_map.label_at((15., 5.)), Some("rect"));
+
+        let bounds = source_map.bounds_at(right + 3).unwrap();
+        assert_eq!(
+            (bounds.x(), bounds.y(), bounds.width(), bounds.height()),
+            (10., 0., 10., 10.)
+        );
+        // The group containing the rect.
+        let bounds = source_map.bounds_at(right - 1).unwrap();
+        assert_eq!((bounds.x(), bounds.width()), (10., 10.));
+        assert!(
+            source_map
+                .bounds_at(text.find("<defs").unwrap() + 8)
+                .is_none()
+        );
+        assert!(source_map.bounds_at(0).is_none());
+
+        let depths = source_map
+            .elements()
+            .iter()
+            .map(|element| (element.label.as_str(), element.depth))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            depths,
+            [
+                ("svg", 0),
+                ("rect#left .a .b", 1),
+                ("g", 1),
+                ("rect", 2),
+                ("defs", 1),
+                ("rect#unused", 2)
+            ]
+        );
+        assert_eq!(source_map.selectors(), ["#left", ".a", ".b", "#unused"]);
+        let bounds = source_map.selector_bounds(".a").unwrap();
+        assert_eq!((bounds.x(), bounds.width()), (0., 10.));
+        assert!(source_map.selector_bounds("#unused").is_none());
+
+        assert_eq!(
+            source_map
+                .element_containing(right + 3)
+                .map(|element| element.range.start),
+            Some(right)
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use std::fmt::Write as _;
+
+use crate::animation;
+
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+/// Returns the ids of the entries of a sprite: its `<symbol>` elements, or if it has none, the
+/// `<g>` elements with ids at its top level. Returns nothing for documents with fewer than two
+/// entries, which aren't sprites.
+pub fn sprite_ids(document: &roxmltree::Document) -> Vec<String> {
+    let with_id = |node: roxmltree::Node| node.attribute("id").map(str::to_string);
+    let mut ids = document
+        .descendants()
+        .filter(|node| node.tag_name().name() == "symbol")
+        .filter_map(with_id)
+        .collect::<Vec<_>>();
+    if ids.is_empty() {
+        ids = document
+            .root_element()
+            .children()
+            .filter(|node| node.tag_name().name() == "g")
+            .filter_map(with_id)
+            .collect();
+    }
+    if ids.len() < 2 {
+        ids.clear();
+    }
+    ids
+}
+
+/// Returns a standalone SVG that shows only the entry of the sprite `text` with `id`, sized to
+/// the entry's `viewBox` if it has one. The rest of the sprite is kept in `<defs>`, so that the
+/// entry can still reference its gradients, styles and other symbols.
+pub fn entry_svg(text: &str, id: &str) -> Option<String> {
+    let document = animation::parse_document(text).ok()?;
+    let root = document.root_element();
+    let entry = document
+        .descendants()
+        .find(|node| node.is_element() && node.attribute("id") == Some(id))?;
+
+    let mut svg = String::from("<svg");
+    if root.lookup_namespace_uri(None).is_none() {
+        write!(svg, " xmlns=\"{SVG_NAMESPACE}\"").ok()?;
+    }
+    for namespace in root.namespaces() {
+        match namespace.name() {
+            Some("xml") => {}
+            Some(name) => write!(svg, " xmlns:{name}=\"{}\"", namespace.uri()).ok()?,
+            None => write!(svg, " xmlns=\"{}\"", namespace.uri()).ok()?,
+        }
+    }
+    if let Some(view_box) = entry
+        .attribute("viewBox")
+        .or_else(|| root.attribute("viewBox"))
+    {
+        write!(svg, " viewBox=\"{view_box}\"").ok()?;
+    }
+    svg.push_str("><defs>");
+    if let (Some(first), Some(last)) = (root.first_child(), root.last_child()) {
+        svg.push_str(&text[first.range().start..last.range().end]);
+    }
+    write!(svg, "</defs><use href=\"#{id}\"/></svg>").ok()?;
+    Some(svg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sprite_ids() {
+        let ids = |text: &str| sprite_ids(&animation::parse_document(text).unwrap());
+        assert_eq!(
+            ids(
+                r#"<svg xmlns="http://www.w3.org/2000/svg"><defs><symbol id="a"/><symbol id="b"/></defs><g id="c"/><g id="d"/></svg>"#
+            ),
+            ["a", "b"]
+        );
+        assert_eq!(
+            ids(
+                r#"<svg xmlns="http://www.w3.org/2000/svg"><g id="c"><g id="e"/></g><g/><g id="d"/></svg>"#
+            ),
+            ["c", "d"]
+        );
+        assert!(
+            ids(r#"<svg xmlns="http://www.w3.org/2000/svg"><g id="c"/><rect id="d"/></svg>"#)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_entry_svg() {
+        let text = r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="0" height="0"><symbol id="a" viewBox="0 0 16 16"><rect/></symbol><symbol id="b"/></svg>"#;
+        assert_eq!(
+            entry_svg(text, "a").unwrap(),
+            r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" viewBox="0 0 16 16"><defs><symbol id="a" viewBox="0 0 16 16"><rect/></symbol><symbol id="b"/></defs><use href="#a"/></svg>"##
+        );
+        assert_eq!(entry_svg(text, "c"), None);
+
+        // Sprites inlined in HTML often leave out the namespace.
+        assert_eq!(
+            entry_svg(r#"<svg viewBox="0 0 8 8"><g id="a"/></svg>"#, "a").unwrap(),
+            r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 8 8"><defs><g id="a"/></defs><use href="#a"/></svg>"##
+        );
+    }
+}
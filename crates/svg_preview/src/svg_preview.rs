@@ -1,7 +1,54 @@
-use gpui::{App, actions};
+use gpui::{Action, App, actions};
+use schemars::JsonSchema;
+use serde::Deserialize;
 use workspace::Workspace;
 
+mod accessibility;
+mod animation;
+mod batch_export;
+mod bounding_boxes;
+mod current_color;
+mod custom_properties;
+mod data_uri;
+mod directory_gallery;
+mod element_export;
+mod element_finder;
+mod embedded;
+mod export;
+mod external_resources;
+mod eyedropper;
+mod fonts;
+mod gallery;
+mod gpu_scene;
+mod guides;
+mod hidden_elements;
+mod icon_containers;
+mod markup;
+mod measure;
+mod media_queries;
+mod memory_budget;
+mod optimize;
+mod path_points;
+mod pdf;
+mod persistence;
+mod rulers;
+mod security;
+mod simulated_viewport;
+mod source_map;
+mod sprite;
+mod svg_preview_settings;
+pub mod svg_preview_toolbar;
 pub mod svg_preview_view;
+mod svgz;
+mod tile_cache;
+mod tiles;
+mod unsupported_features;
+mod user_space;
+mod validation;
+mod visual_diff;
+pub mod zoom_indicator;
+
+pub use crate::svg_preview_settings::*;
 
 actions!(
     svg,
@@ -10,17 +57,180 @@ actions!(
         OpenPreview,
         /// Opens an SVG preview in a split pane.
         OpenPreviewToTheSide,
+        /// Closes the SVG preview of the current file if it's visible, reveals it if it's open
+        /// but hidden, and otherwise opens one in a split pane.
+        TogglePreview,
         /// Opens a following SVG preview that syncs with the editor.
-        OpenFollowingPreview
+        OpenFollowingPreview,
+        /// Opens another SVG preview for the current file, even if one is already open, with its
+        /// own zoom and position.
+        OpenAdditionalPreview,
+        /// Opens the SVG markup on the clipboard in a new scratch buffer, with a preview of it.
+        PreviewClipboard,
+        /// Toggles whether the SVG preview is pinned to its SVG or follows the last active SVG
+        /// editor.
+        TogglePinned,
+        /// Opens the previewed SVG in an editor, or focuses the editor it's open in.
+        OpenSource,
+        /// Zooms the SVG preview so that the whole image fits into the pane.
+        FitToWindow,
+        /// Zooms into the SVG preview.
+        ZoomIn,
+        /// Zooms out of the SVG preview.
+        ZoomOut,
+        /// Resets the SVG preview zoom to 100%.
+        ZoomReset,
+        /// Pans the SVG preview to the left.
+        PanLeft,
+        /// Pans the SVG preview to the right.
+        PanRight,
+        /// Pans the SVG preview up.
+        PanUp,
+        /// Pans the SVG preview down.
+        PanDown,
+        /// Resets the zoom and position of the SVG preview.
+        ResetView,
+        /// Toggles rulers showing SVG user units along the edges of the preview.
+        ToggleRulers,
+        /// Toggles outlining the edges of the SVG's canvas and `viewBox`, with a shadow around
+        /// the canvas.
+        ToggleCanvasOutline,
+        /// Toggles showing the anchors and control points of the path under the editor's cursor.
+        TogglePathPoints,
+        /// Toggles measuring distances by dragging across the SVG preview.
+        ToggleMeasure,
+        /// Toggles picking colors by clicking on the SVG preview, which copies their hex values.
+        ToggleEyedropper,
+        /// Toggles showing the elements hidden with `display="none"` or `opacity="0"` as faint,
+        /// hatched ghosts.
+        ToggleHiddenElements,
+        /// Toggles a gallery of the symbols of an SVG sprite, showing each as a labeled tile.
+        ToggleGallery,
+        /// Toggles a panel listing accessibility problems in the SVG, such as a missing title or
+        /// low contrast text.
+        AuditAccessibility,
+        /// Optimizes the SVG by stripping editor metadata, collapsing groups, merging paths and
+        /// rounding numbers, rewriting its buffer.
+        OptimizeSvg,
+        /// Removes editor metadata from the SVG, such as Inkscape and Illustrator namespaces,
+        /// comments and empty `<defs>`, rewriting its buffer.
+        CleanSvg,
+        /// Toggles a panel reporting what each pass of Optimize SVG saves, where the passes it
+        /// applies are chosen.
+        ShowOptimizationReport,
+        /// Toggles comparing the SVG with its version in the last commit, side by side with
+        /// the changes highlighted, as an onion skin, split by a swipe divider or blinking.
+        CompareWithHead,
+        /// Cycles the preview background between the editor background, white and black.
+        ToggleBackground,
+        /// Plays the animations of the SVG.
+        PlayAnimation,
+        /// Pauses the animations of the SVG.
+        PauseAnimation,
+        /// Restarts the animations of the SVG from the beginning.
+        RestartAnimation,
+        /// Toggles the panel for overriding the CSS custom properties of the SVG.
+        ToggleCustomProperties,
+        /// Toggles an overlay showing how long the last render took to parse and rasterize the
+        /// SVG, the size it was rasterized at and its number of elements.
+        ToggleRenderStats,
+        /// Toggles the outline of the SVG's elements.
+        ToggleOutline,
+        /// Toggles outlining the bounding boxes of the SVG's elements, down to the depth
+        /// configured in the settings.
+        ToggleBoundingBoxes,
+        /// Picks an id or class of the SVG and zooms the preview to the elements it matches.
+        FindElement,
+        /// Toggles whether the SVG's `prefers-color-scheme` styles are evaluated for a light or a
+        /// dark color scheme.
+        ToggleColorScheme,
+        /// Exports the SVG as a PNG at a chosen size.
+        ExportPng,
+        /// Exports the SVG as a JPEG at a chosen size and quality.
+        ExportJpeg,
+        /// Exports the SVG as a WebP at a chosen size.
+        ExportWebp,
+        /// Exports the SVG as a set of PNGs at the scales configured in the settings, such as
+        /// `icon@1x.png`, `icon@2x.png` and `icon@3x.png`.
+        ExportPngSet,
+        /// Exports the SVG as a Windows icon containing the standard icon sizes.
+        ExportIco,
+        /// Exports the SVG as a macOS icon containing the standard icon sizes.
+        ExportIcns,
+        /// Exports the SVG as a single-page vector PDF.
+        ExportPdf,
+        /// Copies the rendered SVG to the clipboard as an image.
+        CopyImage
     ]
 );
 
+/// Exports a single element of the SVG as a PNG cropped to its bounds, prompting for the element's
+/// id, which is initially the given one.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = svg)]
+#[serde(deny_unknown_fields)]
+pub struct ExportElement {
+    #[serde(default)]
+    pub id: Option<String>,
+}
+
+/// Opens the SVG of the `data:image/svg+xml` URI under the cursor, such as one in a CSS `url()`,
+/// in a new scratch buffer with a preview of it.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = svg)]
+#[serde(deny_unknown_fields)]
+pub struct PreviewDataUri {
+    /// Whether edits to the scratch buffer are encoded back into the data URI.
+    #[serde(default)]
+    pub write_back: bool,
+}
+
+/// Exports the SVG with the export preset of the given name from the settings, or with every
+/// preset if no name is given.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = svg)]
+#[serde(deny_unknown_fields)]
+pub struct ExportWithPreset {
+    #[serde(default)]
+    pub preset: Option<String>,
+}
+
+/// Zooms the SVG preview by a factor, such as `{"by": 0.5}` to zoom out to half the size, or to a
+/// zoom level, such as `{"to": 2.0}` for 200%.
+#[derive(Clone, Debug, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = svg)]
+#[serde(untagged)]
+pub enum Zoom {
+    /// Multiplies the zoom level by the given factor.
+    By { by: f32 },
+    /// Sets the zoom level to the given scale, where 1.0 is 100%.
+    To { to: f32 },
+}
+
+/// Pans the SVG preview by a number of pixels, such as `{"by": [0, 40]}` to pan down by 40
+/// pixels, or to a point of the SVG in its user units, such as `{"to": [12, 24]}` to center it.
+#[derive(Clone, Debug, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = svg)]
+#[serde(untagged)]
+pub enum Pan {
+    /// Pans right and down by the given number of pixels, or left and up by negative ones.
+    By { by: [f32; 2] },
+    /// Centers the given point of the SVG, in the user units of its `viewBox`.
+    To { to: [f32; 2] },
+}
+
 pub fn init(cx: &mut App) {
+    crate::validation::SvgDiagnostics::init(cx);
+    workspace::register_project_item::<crate::svg_preview_view::SvgPreviewView>(cx);
+    workspace::register_serializable_item::<crate::svg_preview_view::SvgPreviewView>(cx);
+    workspace::FollowableViewRegistry::register::<crate::svg_preview_view::SvgPreviewView>(cx);
     cx.observe_new(|workspace: &mut Workspace, window, cx| {
         let Some(window) = window else {
             return;
         };
         crate::svg_preview_view::SvgPreviewView::register(workspace, window, cx);
+        crate::batch_export::register(workspace);
+        crate::directory_gallery::register(workspace);
     })
     .detach();
 }
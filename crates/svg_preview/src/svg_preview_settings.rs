@@ -0,0 +1,158 @@
+use std::path::PathBuf;
+
+use settings::{RegisterSetting, Settings};
+pub use settings::{
+    SvgPreviewAutoOpen, SvgPreviewBackground, SvgPreviewCurrentColor, SvgPreviewDefaultMode,
+    SvgPreviewDefaultZoom, SvgPreviewExportFormat, SvgPreviewExportPreset, SvgPreviewFollowScope,
+    SvgPreviewRenderer, SvgPreviewScrollBehavior,
+};
+
+/// The settings for the SVG preview.
+#[derive(Clone, Debug, RegisterSetting)]
+pub struct SvgPreviewSettings {
+    /// What scrolling the mouse wheel over the preview does.
+    ///
+    /// Default: "zoom"
+    pub scroll_behavior: SvgPreviewScrollBehavior,
+    /// The color drawn behind the SVG.
+    ///
+    /// Default: "theme"
+    pub background: SvgPreviewBackground,
+    /// How long to wait after the last edit before re-rendering the preview.
+    ///
+    /// Default: 250
+    pub render_debounce_ms: u64,
+    /// The largest SVG file that is rendered, in bytes.
+    ///
+    /// Default: 10 MB
+    pub max_file_size: u64,
+    /// SVG files larger than this, in bytes, are only rendered after confirming it, unless it's 0.
+    ///
+    /// Default: 2 MB
+    pub large_file_size: u64,
+    /// SVGs with more elements than this are only rendered after confirming it, unless it's 0.
+    ///
+    /// Default: 50000
+    pub large_element_count: u64,
+    /// How many bytes the images rendered by all SVG previews may take up together. Cached tiles
+    /// are evicted and zoomed in previews are rendered at a lower resolution to stay within it.
+    ///
+    /// Default: 512 MB
+    pub memory_budget: usize,
+    /// How new previews are zoomed, unless the SVG was previewed before.
+    ///
+    /// Default: "fit"
+    pub default_zoom: SvgPreviewDefaultZoom,
+    /// What the preview actions open.
+    ///
+    /// Default: "pinned"
+    pub default_mode: SvgPreviewDefaultMode,
+    /// Directories to load fonts from, in addition to the system fonts.
+    ///
+    /// Default: []
+    pub font_dirs: Vec<PathBuf>,
+    /// The font family used for text whose fonts aren't available.
+    ///
+    /// Default: null
+    pub fallback_font_family: Option<String>,
+    /// The color that `currentColor` resolves to.
+    ///
+    /// Default: "foreground"
+    pub current_color: SvgPreviewCurrentColor,
+    /// The scales that exporting a PNG set renders the SVG at.
+    ///
+    /// Default: [1, 2, 3]
+    pub export_scales: Vec<f32>,
+    /// Named sets of export options.
+    ///
+    /// Default: []
+    pub export_presets: Vec<SvgPreviewExportPreset>,
+    /// How many levels of elements the bounding box overlay outlines.
+    ///
+    /// Default: 1
+    pub bounding_box_depth: u32,
+    /// Whether to remove scripts, event handlers and references to resources on the network from
+    /// SVGs before rendering them.
+    ///
+    /// Default: false
+    pub sandbox: bool,
+    /// Which editors a following preview follows.
+    ///
+    /// Default: "workspace"
+    pub follow_scope: SvgPreviewFollowScope,
+    /// Whether a following preview clears when an item other than an SVG editor becomes active.
+    ///
+    /// Default: false
+    pub clear_when_inactive: bool,
+    /// Whether to close a preview that isn't following when the last editor of its SVG is closed.
+    ///
+    /// Default: false
+    pub close_with_editor: bool,
+    /// Whether to open a preview automatically when an SVG file is opened.
+    ///
+    /// Default: "off"
+    pub auto_open: SvgPreviewAutoOpen,
+    /// Whether new previews show how long the last render took and how large the SVG is.
+    ///
+    /// Default: false
+    pub show_render_stats: bool,
+    /// Whether files whose content starts with an `<svg>` element can be previewed regardless of
+    /// their extension.
+    ///
+    /// Default: false
+    pub detect_by_content: bool,
+    /// How the SVG is rasterized.
+    ///
+    /// Default: "cpu"
+    pub renderer: SvgPreviewRenderer,
+}
+
+impl Settings for SvgPreviewSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let svg_preview = content.svg_preview.clone().unwrap();
+        Self {
+            scroll_behavior: svg_preview.scroll_behavior.unwrap(),
+            background: svg_preview.background.unwrap(),
+            render_debounce_ms: svg_preview.render_debounce_ms.unwrap(),
+            max_file_size: svg_preview
+                .max_file_size_mb
+                .unwrap()
+                .saturating_mul(1024 * 1024),
+            large_file_size: svg_preview
+                .large_file_size_mb
+                .unwrap()
+                .saturating_mul(1024 * 1024),
+            large_element_count: svg_preview.large_element_count.unwrap(),
+            memory_budget: svg_preview
+                .memory_budget_mb
+                .unwrap()
+                .saturating_mul(1024 * 1024),
+            default_zoom: svg_preview.default_zoom.unwrap(),
+            default_mode: svg_preview.default_mode.unwrap(),
+            font_dirs: svg_preview
+                .font_dirs
+                .unwrap()
+                .iter()
+                .map(|dir| match dir.strip_prefix("~/") {
+                    Some(dir) => util::paths::home_dir().join(dir),
+                    None => PathBuf::from(dir),
+                })
+                .collect(),
+            fallback_font_family: svg_preview
+                .fallback_font_family
+                .map(|family| family.0.to_string()),
+            current_color: svg_preview.current_color.unwrap(),
+            export_scales: svg_preview.export_scales.unwrap(),
+            export_presets: svg_preview.export_presets.unwrap(),
+            bounding_box_depth: svg_preview.bounding_box_depth.unwrap(),
+            sandbox: svg_preview.sandbox.unwrap(),
+            follow_scope: svg_preview.follow_scope.unwrap(),
+            clear_when_inactive: svg_preview.clear_when_inactive.unwrap(),
+            close_with_editor: svg_preview.close_with_editor.unwrap(),
+            auto_open: svg_preview.auto_open.unwrap(),
+            show_render_stats: svg_preview.show_render_stats.unwrap(),
+            detect_by_content: svg_preview.detect_by_content.unwrap(),
+            renderer: svg_preview.renderer.unwrap(),
+        }
+    }
+}
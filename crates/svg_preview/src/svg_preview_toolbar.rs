@@ -0,0 +1,66 @@
+use gpui::{Context, Entity, EventEmitter, IntoElement, Render, Subscription, Window};
+use ui::prelude::*;
+use workspace::{ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, item::ItemHandle};
+
+use crate::svg_preview_view::{SvgPreviewEvent, SvgPreviewView};
+
+/// The toolbar of SVG previews, with controls for pinning, zooming, the background and exporting.
+pub struct SvgPreviewToolbar {
+    preview: Option<Entity<SvgPreviewView>>,
+    _subscription: Option<Subscription>,
+}
+
+impl SvgPreviewToolbar {
+    pub fn new() -> Self {
+        Self {
+            preview: None,
+            _subscription: None,
+        }
+    }
+}
+
+impl Default for SvgPreviewToolbar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Render for SvgPreviewToolbar {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex().children(self.preview.clone().map(|preview| {
+            preview.update(cx, |preview, cx| {
+                preview.render_toolbar(cx).into_any_element()
+            })
+        }))
+    }
+}
+
+impl EventEmitter<ToolbarItemEvent> for SvgPreviewToolbar {}
+
+impl ToolbarItemView for SvgPreviewToolbar {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> ToolbarItemLocation {
+        if let Some(preview) = active_pane_item.and_then(|item| item.downcast::<SvgPreviewView>()) {
+            self._subscription =
+                Some(
+                    cx.subscribe_in(&preview, window, |_, _, event: &SvgPreviewEvent, _, cx| {
+                        if matches!(
+                            event,
+                            SvgPreviewEvent::ViewChanged | SvgPreviewEvent::ControlsChanged
+                        ) {
+                            cx.notify();
+                        }
+                    }),
+                );
+            self.preview = Some(preview);
+            return ToolbarItemLocation::PrimaryRight;
+        }
+        self.preview = None;
+        self._subscription = None;
+        ToolbarItemLocation::Hidden
+    }
+}
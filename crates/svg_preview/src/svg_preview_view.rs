@@ -1,26 +1,713 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::iter;
 use std::mem;
+use std::ops::Range;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
+use anyhow::Context as _;
+use collections::{HashMap, HashSet};
+use editor::scroll::Autoscroll;
+use editor::{Editor, EditorEvent, MultiBufferOffset, SelectionEffects, actions::SelectAll};
 use file_icons::FileIcons;
+use fs::Fs;
 use gpui::{
-    App, Context, Entity, EventEmitter, FocusHandle, Focusable, IntoElement, ParentElement, Render,
-    RenderImage, Styled, Subscription, Task, WeakEntity, Window, div, img,
+    App, Axis, Bounds, ClipboardItem, Context, Corner, DevicePixels, DismissEvent, Div, Entity,
+    EntityId, EventEmitter, FocusHandle, Focusable, Global, Hsla, Image, ImageFormat, IntoElement,
+    MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, ObjectFit, ParentElement,
+    PathBuilder, PathPromptOptions, PinchEvent, Pixels, Point, RasterEncoding, Render, RenderImage,
+    Rgba, ScrollWheelEvent, Size, Styled, Subscription, SvgRenderer, Task, WeakEntity, Window,
+    anchored, canvas, deferred, div, fill, img, pattern_slash, point, size,
 };
-use language::{Buffer, BufferEvent};
+use language::{Anchor, Buffer, BufferEvent, BufferId, Capability, DiskState, OffsetRangeExt as _};
 use multi_buffer::MultiBuffer;
-use ui::prelude::*;
-use workspace::item::Item;
-use workspace::{Pane, Workspace};
+use project::image_store::ImageItemEvent;
+use project::{ImageItem, Project, ProjectItem as _, ProjectPath};
+use rpc::proto;
+use settings::{Settings, SettingsStore};
+use ui::{
+    Banner, ButtonLike, Checkbox, ContextMenu, ListItem, ListItemSpacing, PopoverMenu, Tooltip,
+    prelude::*,
+};
+use util::ResultExt as _;
+use util::size::format_file_size;
+use workspace::item::{
+    Dedup, FollowableItem, Item, ItemHandle, SerializableItem, TabContentParams,
+};
+use workspace::notifications::{DetachAndPromptErr, NotificationId};
+use workspace::{
+    CollaboratorId, ItemId, Pane, RevealInProjectPanel, SaveIntent, Toast, ViewId, Workspace,
+    WorkspaceId, delete_unloaded_items,
+};
+
+use crate::accessibility;
+use crate::animation::{self, Animations};
+use crate::bounding_boxes::bounding_boxes;
+use crate::current_color::{hex_color, with_current_color};
+use crate::custom_properties::{self, CustomProperty};
+use crate::data_uri::{SvgDataUri, svg_data_uri_at};
+use crate::element_export;
+use crate::element_finder::ElementFinder;
+use crate::embedded::svg_element_at;
+use crate::export::{
+    DEFAULT_EXPORT_QUALITY, export_extension, parse_export_size, preset_output_path,
+    raster_encoding, render_icns, render_ico, render_preset, scaled_export_size, scaled_file_name,
+    write_export,
+};
+use crate::external_resources::{ExternalResources, OpenFiles};
+use crate::eyedropper::{hex_rgba, paint_with_color};
+use crate::fonts::SvgFonts;
+use crate::gallery::{self, GalleryOptions};
+use crate::gpu_scene::GpuScene;
+use crate::guides::{self, Guide};
+use crate::hidden_elements::HiddenElements;
+use crate::markup::{SVG_SNIFF_LEN, is_svg_markup, starts_with_svg_root};
+use crate::measure::Measurement;
+use crate::media_queries::{ColorScheme, MediaContext, apply_media_queries};
+use crate::memory_budget::{RetainedImages, image_bytes};
+use crate::optimize::{self, Pass, PassSavings};
+use crate::path_points::path_points;
+use crate::pdf;
+use crate::persistence::{SVG_PREVIEW, SerializedSvgPreview};
+use crate::rulers::{self, RULER_SIZE};
+use crate::security::{self, security_risks};
+use crate::simulated_viewport::{apply_simulated_viewport, parse_viewport_size};
+use crate::source_map::SourceMap;
+use crate::sprite;
+use crate::svgz::{SvgzFile, is_svgz_path, open_svgz_buffer};
+use crate::tile_cache::{TileCache, TileSet, TileSetKey};
+use crate::tiles::{self, TileIndex};
+use crate::unsupported_features::unsupported_features;
+use crate::user_space::{UserSpace, format_units};
+use crate::validation::SvgDiagnostics;
+use crate::visual_diff;
+use crate::{
+    AuditAccessibility, CleanSvg, CompareWithHead, CopyImage, ExportElement, ExportIcns, ExportIco,
+    ExportJpeg, ExportPdf, ExportPng, ExportPngSet, ExportWebp, ExportWithPreset, FindElement,
+    FitToWindow, OpenAdditionalPreview, OpenFollowingPreview, OpenPreview, OpenPreviewToTheSide,
+    OpenSource, OptimizeSvg, Pan, PanDown, PanLeft, PanRight, PanUp, PauseAnimation, PlayAnimation,
+    PreviewClipboard, PreviewDataUri, ResetView, RestartAnimation, ShowOptimizationReport,
+    SvgPreviewAutoOpen, SvgPreviewBackground, SvgPreviewCurrentColor, SvgPreviewDefaultMode,
+    SvgPreviewDefaultZoom, SvgPreviewExportFormat, SvgPreviewFollowScope, SvgPreviewRenderer,
+    SvgPreviewScrollBehavior, SvgPreviewSettings, ToggleBackground, ToggleBoundingBoxes,
+    ToggleCanvasOutline, ToggleColorScheme, ToggleCustomProperties, ToggleEyedropper,
+    ToggleGallery, ToggleHiddenElements, ToggleMeasure, ToggleOutline, TogglePathPoints,
+    TogglePinned, TogglePreview, ToggleRenderStats, ToggleRulers, Zoom, ZoomIn, ZoomOut, ZoomReset,
+};
 
-use crate::{OpenFollowingPreview, OpenPreview, OpenPreviewToTheSide};
+const MIN_SCALE_FACTOR: f32 = 0.1;
+const MAX_SCALE_FACTOR: f32 = 20.0;
+/// How much a single pixel of scroll-wheel movement changes the zoom level.
+const SCROLL_ZOOM_SENSITIVITY: f32 = 0.005;
+const SCROLL_LINE_HEIGHT: Pixels = px(20.);
+/// The factor by which a single zoom in or zoom out action changes the zoom level.
+const ZOOM_STEP: f32 = 1.25;
+const PAN_STEP: Pixels = px(40.);
+/// How much of the image is kept within the viewport when panning.
+const MIN_VISIBLE_IMAGE_SIZE: Pixels = px(48.);
+const ZOOM_PRESETS: [f32; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+/// The size of the square that the minimap is fitted into.
+const MINIMAP_SIZE: Pixels = px(160.);
+const MINIMAP_MARGIN: Pixels = px(8.);
+/// The size in device pixels of the longer side of the low resolution overview of the SVG, which
+/// is shown while the tiles for the current zoom level are being rendered.
+const OVERVIEW_SIZE: f32 = 1024.;
+/// The size in device pixels of the longer side of the coarse preview that is shown right after
+/// parsing, so that complex documents don't stay blank until the overview is rendered.
+const PREVIEW_SIZE: f32 = 128.;
+/// The size in device pixels of the longer side of the thumbnail shown in the preview's tab.
+const THUMBNAIL_SIZE: f32 = 32.;
+/// The lowest scale that tiles are rasterized at to stay within the memory budget, so that an
+/// exhausted budget doesn't leave the preview blank.
+const MIN_TILE_SCALE: f32 = 0.05;
+/// How much finer than the display the tiles are rasterized, so that zooming in that far scales
+/// them instead of rasterizing the SVG again.
+const TILE_SUPERSAMPLING: f32 = 1.5;
+/// How much the tiles may be scaled down when zooming out before the SVG is rasterized again.
+const MAX_TILE_DOWNSCALE: f32 = 3.;
+/// How long after the last change of the zoom level it's considered settled, and tiles that
+/// don't cover the new zoom level are replaced.
+const ZOOM_SETTLE_DELAY: Duration = Duration::from_millis(150);
+const ANIMATION_FRAME_INTERVAL: Duration = Duration::from_millis(1000 / 30);
+/// The maximum size in device pixels of the longer side of an animation frame.
+const MAX_ANIMATION_FRAME_SIZE: f32 = 2048.;
+const SCRUBBER_WIDTH: Pixels = px(200.);
+const QUALITY_SLIDER_WIDTH: Pixels = px(100.);
+/// How long the outline of an element found with [`FindElement`] is shown.
+const FLASH_DURATION: Duration = Duration::from_millis(1000);
+/// The fraction of the viewport that an element found with [`FindElement`] is zoomed to fill.
+const REVEAL_FILL: f32 = 0.8;
+/// The opacity of the elements shown with [`ToggleHiddenElements`].
+const HIDDEN_ELEMENT_OPACITY: f32 = 0.3;
+/// How long [`CompareMode::Blink`] shows each version of the SVG for.
+const BLINK_INTERVAL: Duration = Duration::from_millis(500);
+/// How close to a guide, in pixels, the mouse has to be to pick it up.
+const GUIDE_GRAB_DISTANCE: Pixels = px(3.);
+/// How close to the edge of an element, in pixels, a dragged guide has to be to snap to it.
+const GUIDE_SNAP_DISTANCE: f32 = 4.;
+
+enum Dependency {
+    Opening(Task<()>),
+    Open {
+        buffer: Entity<Buffer>,
+        _subscription: Subscription,
+    },
+    /// An image referenced by an SVG of a remote project, which can't be read from disk.
+    Image {
+        image: Entity<ImageItem>,
+        _subscription: Subscription,
+    },
+}
 
 pub struct SvgPreviewView {
     focus_handle: FocusHandle,
     buffer: Option<Entity<Buffer>>,
+    /// The range of an `<svg>` element embedded in another language, such as HTML or JSX, that
+    /// the preview shows instead of the whole buffer.
+    embedded_range: Option<Range<Anchor>>,
+    /// A low resolution rendering of the whole SVG, or the error that occurred while parsing it.
     current_svg: Option<Result<Arc<RenderImage>, SharedString>>,
+    /// A tiny render of the SVG that is shown in the preview's tab.
+    thumbnail: Option<Arc<RenderImage>>,
+    /// The buffer that the last successful render was of.
+    rendered_buffer: Option<EntityId>,
+    /// The error of the last render, if it failed while the last successful one is shown.
+    render_error: Option<RenderError>,
+    tree: Option<Arc<usvg::Tree>>,
+    /// The inputs that [`Self::tree`] was parsed from, once its overview has been rendered.
+    parsed_inputs: Option<ParseInputs>,
+    /// The text that [`Self::tree`] was parsed from, after preprocessing.
+    svg_text: Option<Arc<str>>,
+    /// The resources referenced by the SVG, which are needed to re-parse it for animation frames.
+    external_resources: Arc<ExternalResources>,
+    project: Option<Entity<Project>>,
+    /// The fonts for rendering text, or `None` while they're loaded, in which case the system
+    /// fonts are used.
+    fonts: Option<Arc<SvgFonts>>,
+    /// The font settings that [`Self::fonts`] was or is being loaded with.
+    font_settings: (Vec<PathBuf>, Option<String>),
+    _fonts_task: Task<()>,
+    /// The files referenced by the SVG whose edits re-render it.
+    dependencies: HashMap<PathBuf, Dependency>,
+    /// Tiles of the SVG rasterized at [`Self::tile_scale`], covering the visible part of it.
+    tiles: HashMap<TileIndex, Arc<RenderImage>>,
+    tile_tasks: HashMap<TileIndex, Task<()>>,
+    /// The number of device pixels per SVG pixel that the tiles were rendered at.
+    tile_scale: f32,
+    /// The key that the tiles are stored under when the SVG or the zoom level changes.
+    tiles_key: Option<TileSetKey>,
+    /// The tiles of zoom levels and renderings that were shown recently.
+    tile_cache: TileCache,
+    gpu_scene: GpuSceneState,
+    /// A hash of the inputs of the current rendering of the SVG, see [`content_hash`].
+    content_hash: u64,
+    /// The intrinsic size of the current SVG, in user units.
+    svg_size: Option<Size<f32>>,
+    /// The coordinate system established by the `viewBox` of the current SVG.
+    user_space: Option<UserSpace>,
+    scale_factor: f32,
+    /// When the scale factor was last changed by zooming.
+    zoomed_at: Option<Instant>,
+    /// Updates the tiles once the zoom level settles.
+    _zoom_settle_task: Task<()>,
+    image_offset: Point<Pixels>,
+    /// Whether the scale factor tracks the viewport so that the whole image stays visible.
+    fit_to_window: bool,
+    viewport_bounds: Bounds<Pixels>,
+    drag_start: Option<Point<Pixels>>,
+    dragging_minimap: bool,
+    /// The bounds of the animation timeline scrubber, as of the last frame.
+    scrubber_bounds: Bounds<Pixels>,
+    dragging_scrubber: bool,
+    zoom_input: Option<InlineInput>,
+    /// The viewport size that percentage sizes and media queries of the SVG are evaluated
+    /// against, in place of the SVG's own size.
+    simulated_viewport: Option<Size<f32>>,
+    viewport_input: Option<InlineInput>,
+    export: ExportState,
+    context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
+    show_rulers: bool,
+    /// A background chosen with [`ToggleBackground`], taking precedence over the settings.
+    background_override: Option<SvgPreviewBackground>,
+    current_color_override: Option<SvgPreviewCurrentColor>,
+    /// The hex color that `currentColor` resolved to in the last render.
+    rendered_current_color: String,
+    color_scheme_override: Option<ColorScheme>,
+    /// The color scheme that the last render was evaluated for.
+    rendered_color_scheme: ColorScheme,
+    /// The CSS custom properties referenced by the SVG.
+    custom_properties: Vec<CustomProperty>,
+    custom_property_overrides: HashMap<String, String>,
+    custom_property_editors: HashMap<String, CustomPropertyEditor>,
+    show_custom_properties: bool,
+    show_accessibility_audit: bool,
+    optimization_report: OptimizationReportState,
+    comparison: ComparisonState,
+    show_outline: bool,
+    show_render_stats: bool,
+    /// The stats of the last successful render.
+    render_stats: Option<RenderStats>,
+    show_bounding_boxes: bool,
+    show_canvas_outline: bool,
+    show_path_points: bool,
+    /// The position of the mouse in user units, while it's over the image.
+    pointer_position: Option<Point<f32>>,
+    measure: MeasureState,
+    hidden_elements: HiddenElementsState,
+    /// The ids of the entries of the SVG if it's a sprite, such as its `<symbol>` elements.
+    sprite_ids: Vec<String>,
+    gallery: GalleryState,
+    guides: Vec<Guide>,
+    dragging_guide: Option<usize>,
+    eyedropper: EyedropperState,
+    /// The start offsets of the elements whose children are hidden in the outline.
+    collapsed_outline_elements: HashSet<usize>,
+    /// Incremented for every render so that outdated renders can be abandoned.
+    render_generation: Arc<AtomicUsize>,
+    animation: AnimationState,
     _refresh: Task<()>,
     _buffer_subscription: Option<Subscription>,
+    mode: SvgPreviewMode,
+    workspace: WeakEntity<Workspace>,
+    /// Whether a following preview keeps showing the last SVG it followed while an item other
+    /// than an SVG editor is active.
+    following_inactive: bool,
+    /// The pane that was active when the preview was opened, whose editors a following preview
+    /// sticks to if following is scoped to panes.
+    origin_pane: Option<WeakEntity<Pane>>,
+    /// Follows the active SVG editor, while the preview is in [`SvgPreviewMode::Follow`].
     _workspace_subscription: Option<Subscription>,
+    /// Tracks the active editor, whose cursor is followed if it edits the SVG.
+    _active_editor_subscription: Option<Subscription>,
+    _editor_subscription: Option<Subscription>,
+    /// The map between the SVG's source and its elements, or `None` while it's being built or if
+    /// it isn't needed yet.
+    source_map: Option<Arc<SourceMap>>,
+    source_map_task: Option<Task<()>>,
+    /// The position in the SVG of a click to jump to the source of once the source map is built.
+    pending_source_jump: Option<(f32, f32)>,
+    /// Whether to open the [`ElementFinder`] once the source map is built.
+    pending_find_element: bool,
+    /// The bounds of the elements found with [`FindElement`], outlined until the task finishes.
+    flashed_bounds: Option<(usvg::Rect, Task<()>)>,
+    /// The offset of the cursor of the editor of the SVG, if it's being tracked.
+    cursor_offset: Option<usize>,
+    /// The bounds of the element under [`Self::cursor_offset`], in the coordinates of the SVG's
+    /// size.
+    cursor_highlight: Option<usvg::Rect>,
+    /// The mouse position over the image, and the label of the element under it.
+    hovered_element: Option<(Point<Pixels>, Option<SharedString>)>,
+    /// The features of the SVG that the renderer ignores.
+    unsupported_features: Vec<String>,
+    /// The unsupported features that the warning about was dismissed for, so that it's shown
+    /// again once they change.
+    dismissed_unsupported_features: Vec<String>,
+    /// The parts of the SVG that would run code or make requests in a browser.
+    security_risks: Vec<String>,
+    dismissed_security_risks: Vec<String>,
+    /// Whether the last render was sandboxed with [`security::sandbox`].
+    rendered_sandbox: bool,
+    /// The largest file that was rendered when the preview was last rendered.
+    rendered_max_file_size: u64,
+    /// Why the SVG wasn't rendered, while it waits for rendering it to be confirmed.
+    large_svg: Option<LargeSvg>,
+    /// The buffer that rendering was confirmed for despite being large.
+    confirmed_large_buffer: Option<EntityId>,
+    /// Whether the preview isn't the active item of its pane, in which case rendering is put off
+    /// until it's shown again.
+    hidden: bool,
+    /// The id of the collaborator's preview that this preview follows.
+    remote_id: Option<ViewId>,
+    /// Whether the SVG changed while the preview was hidden, so it has to be rendered once it's
+    /// shown.
+    render_pending: bool,
+    /// Encodes edits to the scratch buffer the preview was opened with back into the data URI it
+    /// was opened from.
+    data_uri_write_back: Option<DataUriWriteBack>,
+    _release_subscription: Subscription,
+    _settings_subscription: Subscription,
+}
+
+/// What rendering the SVG in the background resulted in.
+enum RenderOutcome {
+    Parsed(ParsedSvg),
+    /// The SVG wasn't rendered because it's large and rendering it wasn't confirmed.
+    Large(LargeSvg),
+}
+
+/// Why an SVG is considered too large to render without confirming it.
+#[derive(Clone, Copy, Debug)]
+enum LargeSvg {
+    FileSize(u64),
+    ElementCount(usize),
+}
+
+/// What the SVG is parsed from, so that rendering it again from the same inputs, e.g. because it
+/// was saved or its preview was shown again, keeps the parsed tree instead of parsing it again.
+#[derive(Clone, PartialEq)]
+struct ParseInputs {
+    buffer: EntityId,
+    version: clock::Global,
+    source_range: Option<Range<usize>>,
+    dependency_versions: Vec<(PathBuf, clock::Global)>,
+    sandbox: bool,
+    current_color: String,
+    media_context: MediaContext,
+    custom_property_overrides: HashMap<String, String>,
+}
+
+impl ParseInputs {
+    /// Whether the SVG is parsed from the same inputs as with `other`, except for its text.
+    fn same_besides_text(&self, other: &Self) -> bool {
+        self.buffer == other.buffer
+            && self.dependency_versions == other.dependency_versions
+            && self.sandbox == other.sandbox
+            && self.current_color == other.current_color
+            && self.media_context == other.media_context
+            && self.custom_property_overrides == other.custom_property_overrides
+    }
+}
+
+/// How long the last render took and what it produced, for the render stats overlay.
+#[derive(Clone, Copy, Debug)]
+struct RenderStats {
+    /// The time it took to preprocess the SVG, load its resources and parse it.
+    parse_time: Duration,
+    /// The time it took to rasterize the coarse preview and the overview.
+    rasterize_time: Duration,
+    /// The size of the largest image of the whole SVG that was rasterized.
+    image_size: Size<DevicePixels>,
+    element_count: usize,
+}
+
+/// The result of parsing the SVG and rendering its coarse preview in the background.
+struct ParsedSvg {
+    tree: Arc<usvg::Tree>,
+    text: Arc<str>,
+    preview: Arc<RenderImage>,
+    svg_size: Size<f32>,
+    user_space: UserSpace,
+    animations: Option<Arc<Animations>>,
+    external_resources: Arc<ExternalResources>,
+    custom_properties: Vec<CustomProperty>,
+    unsupported_features: Vec<String>,
+    security_risks: Vec<String>,
+    sprite_ids: Vec<String>,
+    content_hash: u64,
+    stats: RenderStats,
+}
+
+/// The zoom and position that previews of each SVG were last left at, so that reopening a
+/// preview of an SVG restores them.
+#[derive(Default)]
+struct ViewStates(HashMap<ProjectPath, ViewState>);
+
+impl Global for ViewStates {}
+
+#[derive(Clone, Copy)]
+struct ViewState {
+    scale_factor: f32,
+    image_offset: Point<Pixels>,
+    fit_to_window: bool,
+}
+
+/// Another version of the SVG, shown next to the one in the preview.
+enum Comparison {
+    /// An optimized version of the SVG, shown until it's accepted or discarded.
+    Optimization {
+        text: String,
+        image: Arc<RenderImage>,
+        /// The length of the text that was optimized, in bytes.
+        original_len: usize,
+    },
+    /// The version of the SVG in the last commit, which is updated as the SVG is edited.
+    Head {
+        text: String,
+        image: Arc<RenderImage>,
+        /// The regions where the SVG differs from the working copy, as fractions of its size.
+        changes: Vec<Bounds<f32>>,
+        /// How much each pixel of the SVG differs from the working copy, if they're the same size.
+        heatmap: Option<Arc<RenderImage>>,
+    },
+}
+
+/// How the SVG is shown together with its version in the last commit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CompareMode {
+    /// Side by side, with the regions where they differ highlighted.
+    SideBySide,
+    /// On top of each other, with the version in the last commit translucent.
+    OnionSkin,
+    /// On top of each other, with the version in the last commit left of a divider that can be
+    /// dragged across.
+    Swipe,
+    /// Alternating between them.
+    Blink,
+}
+
+impl Comparison {
+    /// Returns the images rendered for the comparison, which are dropped along with it.
+    fn into_images(self) -> Vec<Arc<RenderImage>> {
+        match self {
+            Comparison::Optimization { image, .. } => vec![image],
+            Comparison::Head { image, heatmap, .. } => iter::once(image).chain(heatmap).collect(),
+        }
+    }
+}
+
+/// What an SVG is rendered with besides its text, so that other versions of it can be rendered
+/// the same way as the one in the preview.
+struct RenderInputs {
+    renderer: SvgRenderer,
+    external_resources: Arc<ExternalResources>,
+    fonts: Option<Arc<SvgFonts>>,
+    current_color: String,
+    media_context: MediaContext,
+    custom_property_overrides: HashMap<String, String>,
+}
+
+impl RenderInputs {
+    /// Renders the whole of `text` at [`OVERVIEW_SIZE`], with the same preprocessing as the SVG in
+    /// the preview.
+    fn render(&self, text: &str) -> Option<Arc<RenderImage>> {
+        let document = animation::parse_document(text).ok()?;
+        let rendered_text = with_current_color(text, &document, &self.current_color);
+        let rendered_text = apply_media_queries(
+            rendered_text.as_deref().unwrap_or(text),
+            &self.media_context,
+        )
+        .or(rendered_text);
+        let rendered_text = custom_properties::resolve_custom_properties(
+            rendered_text.as_deref().unwrap_or(text),
+            &self.custom_property_overrides,
+        )
+        .or(rendered_text);
+        let mut options = self
+            .external_resources
+            .usvg_options(&self.renderer, self.fonts.as_deref());
+        apply_simulated_viewport(&mut options, self.media_context.viewport);
+        let tree = usvg::Tree::from_str(rendered_text.as_deref().unwrap_or(text), &options).ok()?;
+        let svg_size = size(tree.size().width(), tree.size().height());
+        render_whole_svg(&self.renderer, &tree, svg_size, OVERVIEW_SIZE).ok()
+    }
+}
+
+/// An error that occurred while rendering an SVG that had been rendered successfully before.
+struct RenderError {
+    message: SharedString,
+    /// The number of the line that the error is on, and its text.
+    line: Option<(u32, SharedString)>,
+    dismissed: bool,
+}
+
+struct CustomPropertyEditor {
+    editor: Entity<Editor>,
+    _subscription: Subscription,
+}
+
+/// An editor shown in place of a button, for entering a value such as the zoom level.
+struct InlineInput {
+    editor: Entity<Editor>,
+    _subscription: Subscription,
+}
+
+/// The inputs for exporting the SVG and what was chosen in them.
+struct ExportState {
+    /// The input for the size of the PNG to export, while it's shown.
+    size_input: Option<InlineInput>,
+    /// The input for the id of the element to export, while it's shown.
+    element_input: Option<InlineInput>,
+    /// The raster format chosen in the export input.
+    format: SvgPreviewExportFormat,
+    /// The JPEG quality chosen in the export input, from 1 to 100.
+    quality: u8,
+    /// The background chosen in the export input, filled in before encoding.
+    background: ExportBackground,
+    /// The bounds of the export quality slider, as of the last frame.
+    quality_slider_bounds: Bounds<Pixels>,
+    dragging_quality_slider: bool,
+    /// The scale of the last export, at which measurements are also given in pixels.
+    scale: f32,
+}
+
+impl Default for ExportState {
+    fn default() -> Self {
+        Self {
+            size_input: None,
+            element_input: None,
+            format: SvgPreviewExportFormat::Png,
+            quality: DEFAULT_EXPORT_QUALITY,
+            background: ExportBackground::Transparent,
+            quality_slider_bounds: Bounds::default(),
+            dragging_quality_slider: false,
+            scale: 1.,
+        }
+    }
+}
+
+/// The animations of the SVG and the point on their timeline that is shown.
+struct AnimationState {
+    animations: Option<Arc<Animations>>,
+    /// The point on the animation timeline that is shown, in seconds, or `None` if the static
+    /// document is shown.
+    time: Option<f32>,
+    /// While the animations are playing, the instant at which the timeline started.
+    started_at: Option<Instant>,
+    frame: Option<Arc<RenderImage>>,
+    _task: Task<()>,
+}
+
+impl Default for AnimationState {
+    fn default() -> Self {
+        Self {
+            animations: None,
+            time: None,
+            started_at: None,
+            frame: None,
+            _task: Task::ready(()),
+        }
+    }
+}
+
+/// The panel reporting what each pass of [`OptimizeSvg`] saves.
+struct OptimizationReportState {
+    shown: bool,
+    /// What each optimization pass saves on the SVG, or `None` while it's being computed.
+    savings: Option<Vec<PassSavings>>,
+    task: Option<Task<()>>,
+    /// The passes that [`OptimizeSvg`] applies.
+    passes: Vec<Pass>,
+    /// Updates the report after edits to the SVG, while it's shown.
+    _edits: Option<Subscription>,
+}
+
+impl Default for OptimizationReportState {
+    fn default() -> Self {
+        Self {
+            shown: false,
+            savings: None,
+            task: None,
+            passes: Pass::ALL.to_vec(),
+            _edits: None,
+        }
+    }
+}
+
+/// The SVG shown together with another version of it.
+struct ComparisonState {
+    current: Option<Comparison>,
+    task: Option<Task<()>>,
+    /// How the SVG is shown together with its version in the last commit.
+    mode: CompareMode,
+    /// Where the divider of [`CompareMode::Swipe`] is, as a fraction of the viewport's width.
+    swipe_position: f32,
+    dragging_swipe_divider: bool,
+    /// Whether the comparison with the last commit is overlaid with how much each pixel changed.
+    show_heatmap: bool,
+    /// Whether [`CompareMode::Blink`] currently shows the version in the last commit.
+    blink_showing_head: bool,
+    blink_task: Option<Task<()>>,
+    /// Updates the comparison with the last commit after edits to the SVG, while it's shown.
+    _edits: Option<Subscription>,
+}
+
+impl Default for ComparisonState {
+    fn default() -> Self {
+        Self {
+            current: None,
+            task: None,
+            mode: CompareMode::SideBySide,
+            swipe_position: 0.5,
+            dragging_swipe_divider: false,
+            show_heatmap: false,
+            blink_showing_head: true,
+            blink_task: None,
+            _edits: None,
+        }
+    }
+}
+
+/// Measuring distances by dragging across the SVG.
+#[derive(Default)]
+struct MeasureState {
+    active: bool,
+    measurement: Option<Measurement>,
+    dragging: bool,
+}
+
+/// The elements hidden with `display="none"` or `opacity="0"`, shown as faint, hatched ghosts.
+#[derive(Default)]
+struct HiddenElementsState {
+    shown: bool,
+    /// A rendering of the hidden elements along with their bounds, in the coordinates of the
+    /// SVG's size.
+    rendering: Option<(Arc<RenderImage>, Vec<usvg::Rect>)>,
+    task: Option<Task<()>>,
+    /// Updates the rendering after edits to the SVG, while they're shown.
+    _edits: Option<Subscription>,
+}
+
+/// The gallery of the entries of an SVG sprite.
+#[derive(Default)]
+struct GalleryState {
+    shown: bool,
+    /// A rendering of each entry of the sprite, shown as tiles in the gallery.
+    tiles: Vec<(SharedString, Arc<RenderImage>)>,
+    task: Option<Task<()>>,
+    options: GalleryOptions,
+    /// The entry of the gallery that's shown on its own, after clicking its tile.
+    isolated_entry: Option<usize>,
+    /// A larger rendering of the isolated entry than its tile's.
+    isolated_image: Option<Arc<RenderImage>>,
+    isolated_task: Option<Task<()>>,
+    /// Updates the tiles after edits to the SVG, while the gallery is shown.
+    _edits: Option<Subscription>,
+}
+
+/// Picking colors by clicking on the SVG.
+#[derive(Default)]
+struct EyedropperState {
+    active: bool,
+    /// The last picked color along with where it was picked, in the coordinates of the SVG's
+    /// size, and a description of it.
+    sample: Option<(Point<f32>, Rgba, SharedString)>,
+}
+
+/// A data URI that the edits to a scratch buffer are written back into.
+struct DataUriWriteBack {
+    scratch_buffer: WeakEntity<Buffer>,
+    source_buffer: WeakEntity<Buffer>,
+    range: Range<Anchor>,
+    data_uri: SvgDataUri,
+    /// The URI as it was last written, so that the write-back stops once the URI is changed in
+    /// the source buffer, rather than overwriting that change.
+    written: String,
+    task: Task<()>,
+    _subscription: Subscription,
+}
+
+/// The SVG tessellated for drawing it on the GPU, with [`SvgPreviewRenderer::Gpu`].
+#[derive(Default)]
+struct GpuSceneState {
+    /// The scene along with the content hash of the rendering it was tessellated from.
+    scene: Option<(u64, Arc<GpuScene>)>,
+    /// The content hash of the last rendering that couldn't be drawn on the GPU, which is
+    /// rasterized in tiles instead.
+    unsupported: Option<u64>,
+    /// Tessellates the rendering with the given content hash for the given scale.
+    task: Option<(u64, f32, Task<()>)>,
+}
+
+/// The background that raster exports are filled with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ExportBackground {
+    Transparent,
+    White,
+    Black,
+    /// The background the preview is currently shown on.
+    Preview,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -37,35 +724,207 @@ impl SvgPreviewView {
         active_buffer: Entity<MultiBuffer>,
         workspace_handle: WeakEntity<Workspace>,
         window: &mut Window,
-        cx: &mut Context<Workspace>,
+        cx: &mut App,
     ) -> Entity<Self> {
-        cx.new(|cx| {
-            let workspace_subscription = if mode == SvgPreviewMode::Follow
-                && let Some(workspace) = workspace_handle.upgrade()
-            {
-                Some(Self::subscribe_to_workspace(workspace, window, cx))
-            } else {
-                None
-            };
+        cx.new(|cx| Self::build(mode, active_buffer, workspace_handle, window, cx))
+    }
+
+    fn build(
+        mode: SvgPreviewMode,
+        active_buffer: Entity<MultiBuffer>,
+        workspace_handle: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let workspace_subscription = if mode == SvgPreviewMode::Follow
+            && let Some(workspace) = workspace_handle.upgrade()
+        {
+            Some(Self::subscribe_to_workspace(workspace, window, cx))
+        } else {
+            None
+        };
+        let active_editor_subscription = workspace_handle.upgrade().map(|workspace| {
+            cx.subscribe_in(
+                &workspace,
+                window,
+                |this: &mut SvgPreviewView, workspace, event: &workspace::Event, window, cx| {
+                    match event {
+                        workspace::Event::ActiveItemChanged => {
+                            if let Some(editor) = workspace
+                                .read(cx)
+                                .active_item(cx)
+                                .and_then(|item| item.downcast::<Editor>())
+                            {
+                                this.track_editor(editor, window, cx);
+                            }
+                        }
+                        workspace::Event::ItemRemoved { .. } => {
+                            this.close_if_orphaned(workspace, window, cx);
+                        }
+                        _ => {}
+                    }
+                },
+            )
+        });
 
-            let buffer = active_buffer.read_with(cx, |buffer, _cx| buffer.as_singleton());
+        let buffer = active_buffer.read_with(cx, |buffer, _cx| buffer.as_singleton());
+        let project = workspace_handle
+            .upgrade()
+            .map(|workspace| workspace.read(cx).project().clone());
+        let origin_pane = workspace_handle
+            .upgrade()
+            .map(|workspace| workspace.read(cx).active_pane().downgrade());
 
-            let subscription = buffer
-                .as_ref()
-                .map(|buffer| Self::create_buffer_subscription(buffer, window, cx));
+        let subscription = buffer
+            .as_ref()
+            .map(|buffer| Self::create_buffer_subscription(buffer, window, cx));
 
-            let mut this = Self {
-                focus_handle: cx.focus_handle(),
-                buffer,
-                current_svg: None,
-                _buffer_subscription: subscription,
-                _workspace_subscription: workspace_subscription,
-                _refresh: Task::ready(()),
-            };
-            this.render_image(window, cx);
+        let entity_id = cx.entity_id();
+        let mut this = Self {
+            focus_handle: cx.focus_handle(),
+            buffer,
+            embedded_range: None,
+            current_svg: None,
+            thumbnail: None,
+            rendered_buffer: None,
+            render_error: None,
+            tree: None,
+            parsed_inputs: None,
+            external_resources: Arc::default(),
+            project,
+            dependencies: HashMap::default(),
+            fonts: None,
+            font_settings: Default::default(),
+            _fonts_task: Task::ready(()),
+            tiles: HashMap::default(),
+            tile_tasks: HashMap::default(),
+            tile_scale: 0.,
+            tiles_key: None,
+            tile_cache: TileCache::default(),
+            gpu_scene: GpuSceneState::default(),
+            content_hash: 0,
+            svg_size: None,
+            user_space: None,
+            scale_factor: 1.0,
+            image_offset: Point::default(),
+            fit_to_window: Self::default_fit_to_window(cx),
+            viewport_bounds: Bounds::default(),
+            drag_start: None,
+            dragging_minimap: false,
+            scrubber_bounds: Bounds::default(),
+            dragging_scrubber: false,
+            zoom_input: None,
+            simulated_viewport: None,
+            viewport_input: None,
+            export: ExportState::default(),
+            context_menu: None,
+            animation: AnimationState::default(),
+            show_rulers: false,
+            background_override: None,
+            current_color_override: None,
+            rendered_current_color: String::new(),
+            color_scheme_override: None,
+            rendered_color_scheme: ColorScheme::Light,
+            custom_properties: Vec::new(),
+            custom_property_overrides: HashMap::default(),
+            custom_property_editors: HashMap::default(),
+            show_custom_properties: false,
+            show_accessibility_audit: false,
+            optimization_report: OptimizationReportState::default(),
+            comparison: ComparisonState::default(),
+            show_outline: false,
+            show_render_stats: SvgPreviewSettings::get_global(cx).show_render_stats,
+            render_stats: None,
+            show_bounding_boxes: false,
+            show_canvas_outline: false,
+            show_path_points: false,
+            pointer_position: None,
+            measure: MeasureState::default(),
+            hidden_elements: HiddenElementsState::default(),
+            sprite_ids: Vec::new(),
+            gallery: GalleryState::default(),
+            guides: Vec::new(),
+            dragging_guide: None,
+            eyedropper: EyedropperState::default(),
+            collapsed_outline_elements: HashSet::default(),
+            _buffer_subscription: subscription,
+            mode,
+            workspace: workspace_handle,
+            origin_pane,
+            following_inactive: false,
+            _workspace_subscription: workspace_subscription,
+            _active_editor_subscription: active_editor_subscription,
+            _editor_subscription: None,
+            source_map: None,
+            source_map_task: None,
+            pending_source_jump: None,
+            pending_find_element: false,
+            flashed_bounds: None,
+            cursor_offset: None,
+            cursor_highlight: None,
+            hovered_element: None,
+            unsupported_features: Vec::new(),
+            dismissed_unsupported_features: Vec::new(),
+            security_risks: Vec::new(),
+            dismissed_security_risks: Vec::new(),
+            rendered_sandbox: false,
+            rendered_max_file_size: 0,
+            large_svg: None,
+            confirmed_large_buffer: None,
+            hidden: false,
+            remote_id: None,
+            render_pending: false,
+            data_uri_write_back: None,
+            _release_subscription: cx.on_release(move |this, cx| {
+                this.save_view_state(cx);
+                RetainedImages::remove(entity_id, cx);
+                SvgDiagnostics::unwatch(entity_id, cx);
+            }),
+            _settings_subscription: cx.observe_global_in::<SettingsStore>(
+                window,
+                |this, window, cx| {
+                    this.load_fonts(window, cx);
+                    let settings = SvgPreviewSettings::get_global(cx);
+                    if settings.sandbox != this.rendered_sandbox
+                        || settings.max_file_size != this.rendered_max_file_size
+                    {
+                        this.render_image(window, cx);
+                    }
+                    this.notify_controls(cx);
+                },
+            ),
+            render_generation: Arc::default(),
+            _refresh: Task::ready(()),
+            zoomed_at: None,
+            _zoom_settle_task: Task::ready(()),
+        };
+        this.restore_view_state(cx);
+        this.watch_diagnostics(cx);
+        this.rendered_current_color = hex_color(this.current_color(cx));
+        this.rendered_color_scheme = this.color_scheme(cx);
+        // Renders the image once the fonts are loaded.
+        this.load_fonts(window, cx);
 
-            this
-        })
+        this
+    }
+
+    /// Switches between showing the SVG the preview is on and following the last active SVG
+    /// editor.
+    fn toggle_pinned(&mut self, _: &TogglePinned, window: &mut Window, cx: &mut Context<Self>) {
+        self.mode = match self.mode {
+            SvgPreviewMode::Default => SvgPreviewMode::Follow,
+            SvgPreviewMode::Follow => SvgPreviewMode::Default,
+        };
+        self.following_inactive = false;
+        self._workspace_subscription = match self.mode {
+            SvgPreviewMode::Default => None,
+            SvgPreviewMode::Follow => self
+                .workspace
+                .upgrade()
+                .map(|workspace| Self::subscribe_to_workspace(workspace, window, cx)),
+        };
+        cx.emit(SvgPreviewEvent::ViewChanged);
+        cx.notify();
     }
 
     fn subscribe_to_workspace(
@@ -77,79 +936,5929 @@ impl SvgPreviewView {
             &workspace,
             window,
             move |this: &mut SvgPreviewView, workspace, event: &workspace::Event, window, cx| {
-                if let workspace::Event::ActiveItemChanged = event {
-                    let workspace = workspace.read(cx);
-                    if let Some(active_item) = workspace.active_item(cx)
-                        && let Some(buffer) = active_item.downcast::<MultiBuffer>()
-                        && Self::is_svg_file(&buffer, cx)
-                    {
-                        let Some(buffer) = buffer.read(cx).as_singleton() else {
+                match event {
+                    workspace::Event::ActiveItemChanged => {
+                        let workspace = workspace.read(cx);
+                        let origin_pane = this.origin_pane.as_ref().and_then(|pane| pane.upgrade());
+                        let active_item =
+                            match (SvgPreviewSettings::get_global(cx).follow_scope, origin_pane) {
+                                (SvgPreviewFollowScope::Pane, Some(pane)) => {
+                                    if workspace.active_pane() != &pane {
+                                        return;
+                                    }
+                                    pane.read(cx).active_item()
+                                }
+                                _ => workspace.active_item(cx),
+                            };
+                        // Switching to the preview itself doesn't change what it follows.
+                        if active_item
+                            .as_ref()
+                            .is_some_and(|item| item.item_id() == cx.entity_id())
+                        {
                             return;
-                        };
-                        if this.buffer.as_ref() != Some(&buffer) {
-                            this._buffer_subscription =
-                                Some(Self::create_buffer_subscription(&buffer, window, cx));
-                            this.buffer = Some(buffer);
-                            this.render_image(window, cx);
+                        }
+                        if let Some(active_item) = active_item
+                            && let Some(buffer) = active_item.act_as::<MultiBuffer>(cx)
+                            && Self::is_svg_file(&buffer, cx)
+                        {
+                            let Some(buffer) = buffer.read(cx).as_singleton() else {
+                                return;
+                            };
+                            this.following_inactive = false;
+                            cx.notify();
+                            this.show_buffer(buffer, window, cx);
+                        } else if SvgPreviewSettings::get_global(cx).clear_when_inactive {
+                            this.forget_buffer(window, cx);
+                        } else if this.buffer.is_some() {
+                            this.following_inactive = true;
                             cx.notify();
                         }
-                    } else {
-                        this.set_current(None, window, cx);
                     }
+                    workspace::Event::ItemRemoved { .. } => {
+                        // The SVG would go stale once no editor edits it anymore.
+                        if let Some(buffer) = this.buffer.as_ref()
+                            && !Self::is_open_in_editor(workspace, buffer, cx)
+                        {
+                            this.forget_buffer(window, cx);
+                        }
+                    }
+                    _ => {}
                 }
             },
         )
     }
 
+    /// Closes a preview that isn't following once no editor edits its SVG anymore, if enabled in
+    /// the settings.
+    fn close_if_orphaned(
+        &mut self,
+        workspace: &Entity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.mode != SvgPreviewMode::Default
+            || !SvgPreviewSettings::get_global(cx).close_with_editor
+        {
+            return;
+        }
+        let Some(buffer) = self.buffer.as_ref() else {
+            return;
+        };
+        if Self::is_open_in_editor(workspace, buffer, cx) {
+            return;
+        }
+        let Some(pane) = workspace.read(cx).pane_for(&cx.entity()) else {
+            return;
+        };
+        let item_id = cx.entity_id();
+        // The pane updates the preview while closing it, so it can't be closed while it's being
+        // updated.
+        window.defer(cx, move |window, cx| {
+            pane.update(cx, |pane, cx| {
+                pane.close_item_by_id(item_id, SaveIntent::Skip, window, cx)
+                    .detach_and_log_err(cx);
+            });
+        });
+    }
+
+    /// Returns whether an editor in `workspace` edits `buffer`.
+    fn is_open_in_editor(workspace: &Entity<Workspace>, buffer: &Entity<Buffer>, cx: &App) -> bool {
+        workspace
+            .read(cx)
+            .items_of_type::<Editor>(cx)
+            .any(|editor| {
+                let editor_buffer = editor.read(cx).buffer().read(cx).as_singleton();
+                editor_buffer.as_ref() == Some(buffer)
+            })
+    }
+
+    /// Shows the SVG of `buffer` instead of the current one, at the zoom and position its last
+    /// preview was left at.
+    fn show_buffer(&mut self, buffer: Entity<Buffer>, window: &mut Window, cx: &mut Context<Self>) {
+        if self.buffer.as_ref() == Some(&buffer) {
+            return;
+        }
+        self.save_view_state(cx);
+        self._buffer_subscription = Some(Self::create_buffer_subscription(&buffer, window, cx));
+        self.buffer = Some(buffer);
+        self.embedded_range = None;
+        self.watch_diagnostics(cx);
+        self.subscribe_panels_to_edits(window, cx);
+        self.restore_view_state(cx);
+        self._editor_subscription = None;
+        self.cursor_offset = None;
+        self.cursor_highlight = None;
+        self.render_image(window, cx);
+        cx.emit(SvgPreviewEvent::ViewChanged);
+        cx.notify();
+    }
+
+    /// Returns what a collaborator following the preview mirrors.
+    fn follow_state(&self, cx: &App) -> proto::view::SvgPreview {
+        proto::view::SvgPreview {
+            buffer_id: self
+                .buffer
+                .as_ref()
+                .map(|buffer| buffer.read(cx).remote_id().to_proto()),
+            scale_factor: self.scale_factor as f64,
+            offset_x: f32::from(self.image_offset.x) as f64,
+            offset_y: f32::from(self.image_offset.y) as f64,
+            fit_to_window: self.fit_to_window,
+        }
+    }
+
+    /// Returns the state of the preview that's restored along with its workspace.
+    fn serialized(&self, cx: &App) -> SerializedSvgPreview {
+        SerializedSvgPreview {
+            svg_path: self.buffer_abs_path(cx),
+            following: self.mode == SvgPreviewMode::Follow,
+            scale_factor: self.scale_factor,
+            offset_x: self.image_offset.x.into(),
+            offset_y: self.image_offset.y.into(),
+            fit_to_window: self.fit_to_window,
+        }
+    }
+
+    /// Mirrors the SVG, zoom and position of a collaborator's preview that is followed.
+    fn apply_follow_state(
+        &mut self,
+        state: &proto::view::SvgPreview,
+        buffer: Option<Entity<Buffer>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match buffer {
+            Some(buffer) => self.show_buffer(buffer, window, cx),
+            None if self.buffer.is_some() => self.forget_buffer(window, cx),
+            None => {}
+        }
+        self.scale_factor = (state.scale_factor as f32).clamp(MIN_SCALE_FACTOR, MAX_SCALE_FACTOR);
+        self.image_offset = point(px(state.offset_x as f32), px(state.offset_y as f32));
+        self.fit_to_window = state.fit_to_window;
+        if self.fit_to_window {
+            self.update_fit_scale();
+        }
+        self.clamp_image_offset();
+        cx.notify();
+    }
+
+    /// Stops showing the SVG, for when a following preview can't follow it anymore.
+    fn forget_buffer(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.save_view_state(cx);
+        self.buffer = None;
+        self.embedded_range = None;
+        self.watch_diagnostics(cx);
+        self._buffer_subscription = None;
+        self.subscribe_panels_to_edits(window, cx);
+        self._editor_subscription = None;
+        self.cursor_offset = None;
+        self.cursor_highlight = None;
+        self.render_error = None;
+        self.following_inactive = false;
+        self.comparison.task = None;
+        self.set_comparison(None, window, cx);
+        self.set_current(None, window, cx);
+        self.set_thumbnail(None, window, cx);
+        cx.emit(SvgPreviewEvent::ViewChanged);
+    }
+
+    /// Handles the SVG's file being deleted: a following preview waits for another SVG editor,
+    /// while a preview of this SVG shows an error instead of the stale image.
+    fn handle_file_deleted(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        match self.mode {
+            SvgPreviewMode::Follow => self.forget_buffer(window, cx),
+            SvgPreviewMode::Default => {
+                let name = self
+                    .buffer
+                    .as_ref()
+                    .and_then(|buffer| buffer.read(cx).file())
+                    .map(|file| file.file_name(cx).to_string())
+                    .unwrap_or_else(|| "The SVG".to_string());
+                self.render_error = None;
+                self.set_current(Some(Err(format!("{name} was deleted").into())), window, cx);
+            }
+        }
+    }
+
     fn render_image(&mut self, window: &Window, cx: &mut Context<Self>) {
+        self.invalidate_source_map(window, cx);
+        if self.hidden {
+            self.render_pending = true;
+            return;
+        }
+        let edited_while_hidden = mem::take(&mut self.render_pending);
         let Some(buffer) = self.buffer.as_ref() else {
             return;
         };
-        const SCALE_FACTOR: f32 = 1.0;
+        let buffer_id = buffer.entity_id();
+        let generation = self.render_generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let latest_generation = self.render_generation.clone();
+        let is_stale = move || latest_generation.load(Ordering::SeqCst) != generation;
+
+        let max_file_size = SvgPreviewSettings::get_global(cx).max_file_size;
+        self.rendered_max_file_size = max_file_size;
+        let file_size = buffer.read(cx).len() as u64;
+        if file_size > max_file_size {
+            let message = SharedString::from(format!(
+                "The SVG is too large to preview ({}). The limit is set by the \
+                 `svg_preview.max_file_size_mb` setting.",
+                format_file_size(file_size, false)
+            ));
+            self._refresh = cx.spawn_in(window, async move |this, cx| {
+                this.update_in(cx, |view, window, cx| {
+                    if view.render_generation.load(Ordering::SeqCst) == generation {
+                        view.forget_render(window, cx);
+                        view.clear_tiles(window);
+                        view.set_current(Some(Err(message)), window, cx);
+                    }
+                })
+                .ok();
+            });
+            return;
+        }
+
+        let settings = SvgPreviewSettings::get_global(cx);
+        let confirmed_large = self.confirmed_large_buffer == Some(buffer_id);
+        if !confirmed_large && settings.large_file_size > 0 && file_size > settings.large_file_size
+        {
+            self._refresh = cx.spawn_in(window, async move |this, cx| {
+                this.update_in(cx, |view, window, cx| {
+                    if view.render_generation.load(Ordering::SeqCst) == generation {
+                        view.show_large_svg(LargeSvg::FileSize(file_size), window, cx);
+                    }
+                })
+                .ok();
+            });
+            return;
+        }
+        let large_element_count = Some(settings.large_element_count as usize)
+            .filter(|count| !confirmed_large && *count > 0);
 
         let renderer = cx.svg_renderer();
         let content = buffer.read(cx).snapshot();
-        let background_task = cx.background_spawn(async move {
-            renderer.render_single_frame(content.text().as_bytes(), SCALE_FACTOR, true)
+        let source_range = self
+            .embedded_range
+            .as_ref()
+            .map(|range| range.to_offset(&content));
+        // Images are referenced relative to the SVG file.
+        let base_dir = self.buffer_dir(cx);
+        let fonts = self.fonts.clone();
+        let current_color = self.rendered_current_color.clone();
+        let media_context = MediaContext {
+            color_scheme: self.rendered_color_scheme,
+            viewport: self.simulated_viewport,
+        };
+        let custom_property_overrides = self.custom_property_overrides.clone();
+        let sandbox = SvgPreviewSettings::get_global(cx).sandbox;
+        self.rendered_sandbox = sandbox;
+        let inputs = ParseInputs {
+            buffer: buffer_id,
+            version: content.version().clone(),
+            source_range: source_range.clone(),
+            dependency_versions: self.dependency_versions(cx),
+            sandbox,
+            current_color: current_color.clone(),
+            media_context,
+            custom_property_overrides: custom_property_overrides.clone(),
+        };
+        // The panels derived from the SVG's text follow its edits while they're shown, so they
+        // only have to be updated here if the SVG is rendered differently otherwise.
+        if edited_while_hidden
+            || self
+                .parsed_inputs
+                .as_ref()
+                .is_none_or(|parsed_inputs| !parsed_inputs.same_besides_text(&inputs))
+        {
+            self.update_panels(Duration::ZERO, window, cx);
+        }
+        // The tree that is shown was parsed from the same inputs, so parsing it again would only
+        // produce the same tree, and its tiles are still valid.
+        if self.parsed_inputs.as_ref() == Some(&inputs)
+            && self.tree.is_some()
+            && self.render_error.is_none()
+        {
+            self._refresh = Task::ready(());
+            return;
+        }
+        // The files of remote projects live on the remote host, so they're only loaded once
+        // they're open.
+        let fs = self
+            .project
+            .as_ref()
+            .filter(|project| project.read(cx).is_local())
+            .map(|project| project.read(cx).fs().clone());
+        let mut open_files = OpenFiles::default();
+        for (path, dependency) in &self.dependencies {
+            match dependency {
+                Dependency::Open { buffer, .. } => {
+                    open_files
+                        .texts
+                        .insert(path.clone(), buffer.read(cx).text());
+                }
+                Dependency::Image { image, .. } => {
+                    let bytes = image.read(cx).image.bytes.clone();
+                    open_files.images.insert(path.clone(), Arc::new(bytes));
+                }
+                Dependency::Opening(_) => {}
+            }
+        }
+        let background_task = cx.background_spawn({
+            let renderer = renderer.clone();
+            let is_stale = is_stale.clone();
+            async move {
+                if is_stale() {
+                    return Ok(None);
+                }
+                let started_at = Instant::now();
+                let mut bytes = match source_range {
+                    Some(range) => content.text_for_range(range).collect(),
+                    None => content.text(),
+                };
+                let security_risks = animation::parse_document(&bytes)
+                    .map(|document| security_risks(&document))
+                    .unwrap_or_default();
+                if sandbox && !security_risks.is_empty() {
+                    bytes = security::sandbox(&bytes).unwrap_or(bytes);
+                }
+                let document =
+                    animation::parse_document(&bytes).map_err(usvg::Error::ParsingFailed)?;
+                if let Some(large_element_count) = large_element_count {
+                    let element_count = document
+                        .descendants()
+                        .filter(|node| node.is_element())
+                        .count();
+                    if element_count > large_element_count {
+                        return Ok(Some(RenderOutcome::Large(LargeSvg::ElementCount(
+                            element_count,
+                        ))));
+                    }
+                }
+                let external_resources = Arc::new(
+                    ExternalResources::load(
+                        &document,
+                        base_dir.as_deref(),
+                        fs.as_ref(),
+                        &open_files,
+                    )
+                    .await,
+                );
+                let inlined_text = external_resources.inline_external_uses(&bytes, &document);
+                let inlined_text = with_current_color(
+                    inlined_text.as_deref().unwrap_or(&bytes),
+                    &document,
+                    &current_color,
+                )
+                .or(inlined_text);
+                let inlined_text =
+                    apply_media_queries(inlined_text.as_deref().unwrap_or(&bytes), &media_context)
+                        .or(inlined_text);
+                let text = inlined_text.as_deref().unwrap_or(&bytes);
+                let custom_properties = custom_properties::custom_properties(text);
+                let inlined_text =
+                    custom_properties::resolve_custom_properties(text, &custom_property_overrides)
+                        .or(inlined_text);
+                let inlined_document = inlined_text
+                    .as_deref()
+                    .map(animation::parse_document)
+                    .transpose()
+                    .map_err(usvg::Error::ParsingFailed)?;
+                let (bytes, document) = match (&inlined_text, &inlined_document) {
+                    (Some(text), Some(document)) => (text.as_str(), document),
+                    _ => (bytes.as_str(), &document),
+                };
+                let mut options = external_resources.usvg_options(&renderer, fonts.as_deref());
+                apply_simulated_viewport(&mut options, media_context.viewport);
+                let tree = usvg::Tree::from_xmltree(document, &options)?;
+                let parse_time = started_at.elapsed();
+                let svg_size = size(tree.size().width(), tree.size().height());
+                let user_space = UserSpace::new(document.root_element(), svg_size);
+                let animations = Animations::new(bytes, document).map(Arc::new);
+                let unsupported_features = unsupported_features(document);
+                let sprite_ids = sprite::sprite_ids(document);
+                let content_hash = content_hash(
+                    bytes,
+                    &external_resources,
+                    fonts.as_ref(),
+                    media_context.viewport,
+                );
+                // Rasterizing is by far the most expensive step, so skip it if a newer render was
+                // started in the meantime.
+                if is_stale() {
+                    return Ok(None);
+                }
+                let rasterize_started_at = Instant::now();
+                let preview = render_whole_svg(&renderer, &tree, svg_size, PREVIEW_SIZE)?;
+                let stats = RenderStats {
+                    parse_time,
+                    rasterize_time: rasterize_started_at.elapsed(),
+                    image_size: preview.size(0),
+                    element_count: document
+                        .descendants()
+                        .filter(|node| node.is_element())
+                        .count(),
+                };
+                Ok::<_, usvg::Error>(Some(RenderOutcome::Parsed(ParsedSvg {
+                    tree: Arc::new(tree),
+                    text: bytes.into(),
+                    preview,
+                    svg_size,
+                    user_space,
+                    animations,
+                    external_resources,
+                    custom_properties,
+                    unsupported_features,
+                    security_risks,
+                    sprite_ids,
+                    content_hash,
+                    stats,
+                })))
+            }
         });
 
         self._refresh = cx.spawn_in(window, async move |this, cx| {
             let result = background_task.await;
 
-            this.update_in(cx, |view, window, cx| {
-                let current = result.map_err(|e| e.to_string().into());
+            let tree = this.update_in(cx, |view, window, cx| {
+                // A newer render has been started since, and its result takes precedence.
+                if view.render_generation.load(Ordering::SeqCst) != generation {
+                    return None;
+                }
+                let (current, tree) = match result {
+                    Ok(None) => return None,
+                    Ok(Some(RenderOutcome::Large(large_svg))) => {
+                        view.show_large_svg(large_svg, window, cx);
+                        return None;
+                    }
+                    Ok(Some(RenderOutcome::Parsed(parsed))) => {
+                        let ParsedSvg {
+                            tree,
+                            text,
+                            preview,
+                            svg_size,
+                            user_space,
+                            animations,
+                            external_resources,
+                            custom_properties,
+                            unsupported_features,
+                            security_risks,
+                            sprite_ids,
+                            content_hash,
+                            stats,
+                        } = parsed;
+                        let size_changed = view.svg_size != Some(svg_size);
+                        let controls_changed =
+                            size_changed || view.sprite_ids.is_empty() != sprite_ids.is_empty();
+                        view.rendered_buffer = Some(buffer_id);
+                        view.render_error = None;
+                        view.svg_size = Some(svg_size);
+                        view.user_space = Some(user_space);
+                        view.tree = Some(tree.clone());
+                        view.svg_text = Some(text);
+                        view.sprite_ids = sprite_ids;
+                        view.content_hash = content_hash;
+                        view.render_stats = Some(stats);
+                        view.watch_dependencies(
+                            external_resources.dependencies(),
+                            external_resources.image_files(),
+                            window,
+                            cx,
+                        );
+                        view.external_resources = external_resources;
+                        view.set_animations(animations, window, cx);
+                        view.set_custom_properties(custom_properties, window, cx);
+                        view.unsupported_features = unsupported_features;
+                        view.security_risks = security_risks;
+                        if size_changed && view.fit_to_window {
+                            view.update_fit_scale();
+                        }
+                        if controls_changed {
+                            cx.emit(SvgPreviewEvent::ControlsChanged);
+                        }
+                        (Ok(preview), Some((tree, svg_size)))
+                    }
+                    Err(error) => {
+                        let message = SharedString::from(error.to_string());
+                        // Errors are usually temporary while editing, so keep showing the last
+                        // successful render of the same SVG, and report the error over it.
+                        if matches!(view.current_svg, Some(Ok(_)))
+                            && view.rendered_buffer == Some(buffer_id)
+                        {
+                            let line = match &error {
+                                usvg::Error::ParsingFailed(error) => {
+                                    view.error_line(error.pos().row, cx)
+                                }
+                                _ => None,
+                            };
+                            let dismissed =
+                                view.render_error.as_ref().is_some_and(|render_error| {
+                                    render_error.dismissed && render_error.message == message
+                                });
+                            view.render_error = Some(RenderError {
+                                message,
+                                line,
+                                dismissed,
+                            });
+                            cx.notify();
+                            return None;
+                        }
+                        view.forget_render(window, cx);
+                        (Err(message), None)
+                    }
+                };
+                view.stash_tiles(window);
                 view.set_current(Some(current), window, cx);
+                tree
+            });
+            let Ok(Some((tree, svg_size))) = tree else {
+                return;
+            };
+
+            // Refine the coarse preview now that it's on screen.
+            let (overview, overview_time, thumbnail) = cx
+                .background_spawn(async move {
+                    if is_stale() {
+                        return (None, Duration::ZERO, None);
+                    }
+                    let started_at = Instant::now();
+                    let overview = render_whole_svg(&renderer, &tree, svg_size, OVERVIEW_SIZE).ok();
+                    let overview_time = started_at.elapsed();
+                    let thumbnail =
+                        render_whole_svg(&renderer, &tree, svg_size, THUMBNAIL_SIZE).ok();
+                    (overview, overview_time, thumbnail)
+                })
+                .await;
+            this.update_in(cx, |view, window, cx| {
+                if view.render_generation.load(Ordering::SeqCst) != generation {
+                    return;
+                }
+                if let Some(overview) = overview {
+                    if let Some(stats) = &mut view.render_stats {
+                        stats.rasterize_time += overview_time;
+                        stats.image_size = overview.size(0);
+                    }
+                    view.set_current(Some(Ok(overview)), window, cx);
+                }
+                view.set_thumbnail(thumbnail, window, cx);
+                view.parsed_inputs = Some(inputs);
             })
             .ok();
         });
     }
 
-    fn set_current(
+    /// Shows a prompt for rendering the SVG in place of the preview, as it's too large to render
+    /// without confirming it.
+    fn show_large_svg(&mut self, large_svg: LargeSvg, window: &mut Window, cx: &mut Context<Self>) {
+        self.forget_render(window, cx);
+        self.clear_tiles(window);
+        self.set_current(None, window, cx);
+        self.large_svg = Some(large_svg);
+    }
+
+    fn render_large_svg(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.confirmed_large_buffer = self.buffer.as_ref().map(|buffer| buffer.entity_id());
+        self.render_image(window, cx);
+    }
+
+    fn render_large_svg_prompt(&self, large_svg: LargeSvg, cx: &mut Context<Self>) -> Div {
+        let size = match large_svg {
+            LargeSvg::FileSize(file_size) => format_file_size(file_size, false),
+            LargeSvg::ElementCount(element_count) => format!("{element_count} elements"),
+        };
+        v_flex()
+            .p_4()
+            .gap_2()
+            .items_start()
+            .child(Label::new(format!(
+                "This SVG is large ({size}) and may take a long time to render."
+            )))
+            .child(
+                Button::new("render-large-svg", "Render Anyway")
+                    .style(ButtonStyle::Filled)
+                    .on_click(cx.listener(|this, _, window, cx| this.render_large_svg(window, cx))),
+            )
+    }
+
+    /// Forgets the last successful render, for when the SVG can't be rendered.
+    fn forget_render(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.rendered_buffer = None;
+        self.render_error = None;
+        self.tree = None;
+        self.parsed_inputs = None;
+        self.render_stats = None;
+        self.svg_text = None;
+        self.unsupported_features.clear();
+        self.security_risks.clear();
+        self.set_animations(None, window, cx);
+        self.set_thumbnail(None, window, cx);
+    }
+
+    fn set_thumbnail(
         &mut self,
-        image: Option<Result<Arc<RenderImage>, SharedString>>,
+        thumbnail: Option<Arc<RenderImage>>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if let Some(Ok(image)) = mem::replace(&mut self.current_svg, image) {
-            window.drop_image(image).ok();
+        if let Some(thumbnail) = mem::replace(&mut self.thumbnail, thumbnail) {
+            window.drop_image(thumbnail).ok();
         }
-        cx.notify();
+        cx.emit(SvgPreviewEvent::ThumbnailChanged);
     }
 
-    fn find_existing_preview_item_idx(
-        pane: &Pane,
-        buffer: &Entity<MultiBuffer>,
-        cx: &App,
-    ) -> Option<usize> {
-        let buffer_id = buffer.entity_id();
-        pane.items_of_type::<SvgPreviewView>()
-            .find(|view| {
-                view.read(cx)
-                    .buffer
-                    .as_ref()
-                    .is_some_and(|buffer| buffer.entity_id() == buffer_id)
+    /// Loads the fonts configured in the settings, if they changed, and re-renders the image with
+    /// them.
+    fn load_fonts(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let settings = SvgPreviewSettings::get_global(cx);
+        let font_settings = (
+            settings.font_dirs.clone(),
+            settings.fallback_font_family.clone(),
+        );
+        if self.fonts.is_some() && font_settings == self.font_settings {
+            return;
+        }
+        self.font_settings = font_settings.clone();
+        let renderer = cx.svg_renderer();
+        let load = cx.background_spawn(async move {
+            let (font_dirs, fallback_family) = font_settings;
+            SvgFonts::load(&renderer, &font_dirs, fallback_family)
+        });
+        self._fonts_task = cx.spawn_in(window, async move |this, cx| {
+            let fonts = load.await;
+            this.update_in(cx, |this, window, cx| {
+                this.fonts = Some(Arc::new(fonts));
+                this.parsed_inputs = None;
+                this.render_image(window, cx);
             })
-            .and_then(|view| pane.index_for_item(&view))
+            .ok();
+        });
+    }
+
+    /// Re-renders the image once the buffer hasn't been edited for the configured debounce
+    /// interval.
+    fn render_image_debounced(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let debounce = SvgPreviewSettings::get_global(cx).render_debounce_ms;
+        if debounce == 0 {
+            self.render_image(window, cx);
+            return;
+        }
+
+        self._refresh = cx.spawn_in(window, async move |this, cx| {
+            cx.background_executor()
+                .timer(Duration::from_millis(debounce))
+                .await;
+            this.update_in(cx, |this, window, cx| this.render_image(window, cx))
+                .ok();
+        });
+    }
+
+    /// Updates the shown panels that are derived from the SVG's text after `debounce`.
+    fn update_panels(&mut self, debounce: Duration, window: &Window, cx: &mut Context<Self>) {
+        self.update_hidden_elements(debounce, window, cx);
+        self.update_gallery(debounce, window, cx);
+        self.update_optimization_report(debounce, window, cx);
+        self.update_head_comparison_after_edit(debounce, window, cx);
+    }
+
+    /// Subscribes the shown panels that are derived from the SVG's text to its edits, and
+    /// unsubscribes the hidden ones, so that editing the SVG only updates what's shown.
+    fn subscribe_panels_to_edits(&mut self, window: &Window, cx: &mut Context<Self>) {
+        self.hidden_elements._edits = self.subscribe_to_edits(
+            self.hidden_elements.shown,
+            Self::update_hidden_elements,
+            window,
+            cx,
+        );
+        self.gallery._edits =
+            self.subscribe_to_edits(self.gallery.shown, Self::update_gallery, window, cx);
+        self.optimization_report._edits = self.subscribe_to_edits(
+            self.optimization_report.shown,
+            Self::update_optimization_report,
+            window,
+            cx,
+        );
+        let comparing_head = matches!(self.comparison.current, Some(Comparison::Head { .. }));
+        self.comparison._edits = self.subscribe_to_edits(
+            comparing_head,
+            Self::update_head_comparison_after_edit,
+            window,
+            cx,
+        );
+    }
+
+    /// Calls `update` with the configured debounce interval after each edit to the SVG's buffer,
+    /// if `shown`. The update is put off while the preview is hidden, until it's rendered again.
+    fn subscribe_to_edits(
+        &self,
+        shown: bool,
+        update: fn(&mut Self, Duration, &Window, &mut Context<Self>),
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Subscription> {
+        let buffer = self.buffer.as_ref().filter(|_| shown)?;
+        Some(cx.subscribe_in(
+            buffer,
+            window,
+            move |this, _, event: &BufferEvent, window, cx| {
+                if let BufferEvent::Edited = event
+                    && !this.hidden
+                {
+                    let debounce = SvgPreviewSettings::get_global(cx).render_debounce_ms;
+                    update(this, Duration::from_millis(debounce), window, cx);
+                }
+            },
+        ))
+    }
+
+    /// Recomputes the scale factor that makes the whole image fit into the viewport, returning
+    /// whether it changed.
+    fn update_fit_scale(&mut self) -> bool {
+        let Some(svg_size) = self.svg_size else {
+            return false;
+        };
+        let viewport_size = self.viewport_bounds.size;
+        if svg_size.width <= 0. || svg_size.height <= 0. || viewport_size.width <= px(0.) {
+            return false;
+        }
+
+        let scale_factor = (f32::from(viewport_size.width) / svg_size.width)
+            .min(f32::from(viewport_size.height) / svg_size.height)
+            .clamp(MIN_SCALE_FACTOR, MAX_SCALE_FACTOR);
+        self.image_offset = Point::default();
+        if scale_factor == self.scale_factor {
+            return false;
+        }
+        self.scale_factor = scale_factor;
+        true
+    }
+
+    fn project_path(&self, cx: &App) -> Option<ProjectPath> {
+        let file = self.buffer.as_ref()?.read(cx).file()?;
+        Some(ProjectPath::from_file(file.as_ref(), cx))
+    }
+
+    /// Remembers the zoom and position of the preview for its SVG, for the next preview of it.
+    fn save_view_state(&self, cx: &mut App) {
+        let Some(project_path) = self.project_path(cx) else {
+            return;
+        };
+        let view_state = ViewState {
+            scale_factor: self.scale_factor,
+            image_offset: self.image_offset,
+            fit_to_window: self.fit_to_window,
+        };
+        cx.default_global::<ViewStates>()
+            .0
+            .insert(project_path, view_state);
+    }
+
+    /// Restores the zoom and position that the last preview of the SVG was left at.
+    fn restore_view_state(&mut self, cx: &App) {
+        let Some(view_state) = self
+            .project_path(cx)
+            .and_then(|project_path| cx.try_global::<ViewStates>()?.0.get(&project_path).copied())
+        else {
+            return;
+        };
+        self.scale_factor = view_state.scale_factor;
+        self.image_offset = view_state.image_offset;
+        self.fit_to_window = view_state.fit_to_window;
+    }
+
+    fn fit_to_window(&mut self, _: &FitToWindow, _window: &mut Window, cx: &mut Context<Self>) {
+        self.fit_to_window = true;
+        self.update_fit_scale();
+        cx.emit(SvgPreviewEvent::ViewChanged);
+        cx.notify();
+    }
+
+    /// Whether new previews fit the image into the pane rather than showing it at 100%.
+    fn default_fit_to_window(cx: &App) -> bool {
+        SvgPreviewSettings::get_global(cx).default_zoom != SvgPreviewDefaultZoom::ActualSize
+    }
+
+    fn reset_view(&mut self, _: &ResetView, _window: &mut Window, cx: &mut Context<Self>) {
+        self.fit_to_window = Self::default_fit_to_window(cx);
+        self.image_offset = Point::default();
+        if self.fit_to_window {
+            self.update_fit_scale();
+        } else {
+            self.scale_factor = 1.0;
+            self.clamp_image_offset();
+        }
+        cx.emit(SvgPreviewEvent::ViewChanged);
+        cx.notify();
+    }
+
+    fn set_viewport_bounds(&mut self, bounds: Bounds<Pixels>, cx: &mut Context<Self>) {
+        if self.viewport_bounds == bounds {
+            return;
+        }
+        self.viewport_bounds = bounds;
+        self.clamp_image_offset();
+        if self.fit_to_window && self.update_fit_scale() {
+            cx.emit(SvgPreviewEvent::ControlsChanged);
+        }
+        cx.notify();
+    }
+
+    /// Changes the zoom level while keeping the image point under `anchor` (in window
+    /// coordinates) at the same position on screen.
+    fn set_scale_factor(
+        &mut self,
+        scale_factor: f32,
+        anchor: Point<Pixels>,
+        cx: &mut Context<Self>,
+    ) {
+        let scale_factor = scale_factor.clamp(MIN_SCALE_FACTOR, MAX_SCALE_FACTOR);
+        self.fit_to_window = false;
+        if scale_factor != self.scale_factor {
+            let ratio = scale_factor / self.scale_factor;
+            let center = self.viewport_bounds.center();
+            self.image_offset = point(
+                anchor.x - center.x - (anchor.x - center.x - self.image_offset.x) * ratio,
+                anchor.y - center.y - (anchor.y - center.y - self.image_offset.y) * ratio,
+            );
+            self.scale_factor = scale_factor;
+            self.zoomed_at = Some(Instant::now());
+            self.clamp_image_offset();
+        }
+        cx.emit(SvgPreviewEvent::ViewChanged);
+        cx.notify();
+    }
+
+    fn zoom_in(&mut self, _: &ZoomIn, _window: &mut Window, cx: &mut Context<Self>) {
+        let center = self.viewport_bounds.center();
+        self.set_scale_factor(self.scale_factor * ZOOM_STEP, center, cx);
+    }
+
+    fn zoom_out(&mut self, _: &ZoomOut, _window: &mut Window, cx: &mut Context<Self>) {
+        let center = self.viewport_bounds.center();
+        self.set_scale_factor(self.scale_factor / ZOOM_STEP, center, cx);
+    }
+
+    fn zoom_reset(&mut self, _: &ZoomReset, _window: &mut Window, cx: &mut Context<Self>) {
+        let center = self.viewport_bounds.center();
+        self.set_scale_factor(1.0, center, cx);
+    }
+
+    fn zoom(&mut self, action: &Zoom, _window: &mut Window, cx: &mut Context<Self>) {
+        let scale_factor = match *action {
+            Zoom::By { by } => self.scale_factor * by,
+            Zoom::To { to } => to,
+        };
+        if !scale_factor.is_finite() || scale_factor <= 0. {
+            return;
+        }
+        let center = self.viewport_bounds.center();
+        self.set_scale_factor(scale_factor, center, cx);
+    }
+
+    fn toggle_rulers(&mut self, _: &ToggleRulers, _window: &mut Window, cx: &mut Context<Self>) {
+        self.show_rulers = !self.show_rulers;
+        cx.notify();
+    }
+
+    fn toggle_render_stats(
+        &mut self,
+        _: &ToggleRenderStats,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.show_render_stats = !self.show_render_stats;
+        cx.notify();
+    }
+
+    fn toggle_background(
+        &mut self,
+        _: &ToggleBackground,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let current = self
+            .background_override
+            .as_ref()
+            .unwrap_or(&SvgPreviewSettings::get_global(cx).background);
+        self.background_override = Some(match current {
+            SvgPreviewBackground::Theme => SvgPreviewBackground::White,
+            SvgPreviewBackground::White => SvgPreviewBackground::Black,
+            SvgPreviewBackground::Black | SvgPreviewBackground::Custom(_) => {
+                SvgPreviewBackground::Theme
+            }
+        });
+        self.notify_controls(cx);
+    }
+
+    /// Re-renders the preview along with its toolbar and status bar item, after a change to a
+    /// control they show.
+    fn notify_controls(&mut self, cx: &mut Context<Self>) {
+        cx.emit(SvgPreviewEvent::ControlsChanged);
+        cx.notify();
+    }
+
+    fn pan_by(&mut self, delta: Point<Pixels>, cx: &mut Context<Self>) {
+        self.image_offset.x += delta.x;
+        self.image_offset.y += delta.y;
+        self.clamp_image_offset();
+        cx.emit(SvgPreviewEvent::ViewChanged);
+        cx.notify();
+    }
+
+    /// Constrains the image offset so that the image can't be panned entirely out of view.
+    fn clamp_image_offset(&mut self) {
+        let Some(image_bounds) = self.image_bounds() else {
+            return;
+        };
+        self.image_offset = clamp_image_offset(
+            self.image_offset,
+            image_bounds.size,
+            self.viewport_bounds.size,
+        );
+    }
+
+    fn pan_left(&mut self, _: &PanLeft, _window: &mut Window, cx: &mut Context<Self>) {
+        self.pan_by(point(PAN_STEP, px(0.)), cx);
+    }
+
+    fn pan_right(&mut self, _: &PanRight, _window: &mut Window, cx: &mut Context<Self>) {
+        self.pan_by(point(-PAN_STEP, px(0.)), cx);
+    }
+
+    fn pan_up(&mut self, _: &PanUp, _window: &mut Window, cx: &mut Context<Self>) {
+        self.pan_by(point(px(0.), PAN_STEP), cx);
+    }
+
+    fn pan_down(&mut self, _: &PanDown, _window: &mut Window, cx: &mut Context<Self>) {
+        self.pan_by(point(px(0.), -PAN_STEP), cx);
+    }
+
+    fn pan(&mut self, action: &Pan, _window: &mut Window, cx: &mut Context<Self>) {
+        match *action {
+            Pan::By { by: [x, y] } => self.pan_by(point(px(-x), px(-y)), cx),
+            Pan::To { to: [x, y] } => {
+                let target = self
+                    .user_space
+                    .map_or(point(x, y), |user_space| user_space.from_user(point(x, y)));
+                self.center_on(target, cx);
+            }
+        }
+    }
+
+    fn handle_scroll_wheel(
+        &mut self,
+        event: &ScrollWheelEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let delta = event.delta.pixel_delta(SCROLL_LINE_HEIGHT);
+        let zoom = match SvgPreviewSettings::get_global(cx).scroll_behavior {
+            SvgPreviewScrollBehavior::Zoom => !event.modifiers.secondary(),
+            SvgPreviewScrollBehavior::Pan => event.modifiers.secondary(),
+        };
+        if !zoom {
+            self.pan_by(delta, cx);
+        } else if delta.y != px(0.) {
+            let zoom = (f32::from(delta.y) * SCROLL_ZOOM_SENSITIVITY).exp();
+            self.set_scale_factor(self.scale_factor * zoom, event.position, cx);
+        }
+        self.update_pointer_position(event.position, cx);
+    }
+
+    fn update_pointer_position(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        let pointer_position = self
+            .svg_position(position)
+            .zip(self.user_space)
+            .map(|((x, y), user_space)| user_space.to_user(point(x, y)));
+        if self.pointer_position != pointer_position {
+            self.pointer_position = pointer_position;
+            cx.notify();
+        }
+    }
+
+    fn show_zoom_input(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let percentage = zoom_percentage(self.scale_factor).to_string();
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_text(percentage, window, cx);
+            editor.select_all(&SelectAll, window, cx);
+            editor
+        });
+        let subscription = cx.subscribe_in(
+            &editor,
+            window,
+            |this, _, event: &EditorEvent, _window, cx| {
+                if let EditorEvent::Blurred = event {
+                    this.zoom_input = None;
+                    this.notify_controls(cx);
+                }
+            },
+        );
+        editor.focus_handle(cx).focus(window);
+        self.zoom_input = Some(InlineInput {
+            editor,
+            _subscription: subscription,
+        });
+        self.notify_controls(cx);
+    }
+
+    fn confirm_zoom_input(
+        &mut self,
+        _: &menu::Confirm,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(zoom_input) = self.zoom_input.take() else {
+            return;
+        };
+        let text = zoom_input.editor.read(cx).text(cx);
+        if let Some(scale_factor) = parse_zoom_percentage(&text) {
+            self.set_scale_factor(scale_factor, self.viewport_bounds.center(), cx);
+        }
+        self.focus_handle.focus(window);
+        self.notify_controls(cx);
+    }
+
+    fn show_viewport_input(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self
+            .simulated_viewport
+            .map(|viewport| format!("{}x{}", viewport.width, viewport.height))
+            .unwrap_or_default();
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Width x Height", window, cx);
+            editor.set_text(text, window, cx);
+            editor.select_all(&SelectAll, window, cx);
+            editor
+        });
+        let subscription = cx.subscribe_in(
+            &editor,
+            window,
+            |this, _, event: &EditorEvent, _window, cx| {
+                if let EditorEvent::Blurred = event {
+                    this.viewport_input = None;
+                    this.notify_controls(cx);
+                }
+            },
+        );
+        editor.focus_handle(cx).focus(window);
+        self.viewport_input = Some(InlineInput {
+            editor,
+            _subscription: subscription,
+        });
+        self.notify_controls(cx);
+    }
+
+    fn confirm_viewport_input(
+        &mut self,
+        _: &menu::Confirm,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(viewport_input) = self.viewport_input.take() else {
+            return;
+        };
+        let text = viewport_input.editor.read(cx).text(cx);
+        if let Some(viewport) = parse_viewport_size(&text)
+            && viewport != self.simulated_viewport
+        {
+            self.simulated_viewport = viewport;
+            self.render_image(window, cx);
+        }
+        self.focus_handle.focus(window);
+        self.notify_controls(cx);
+    }
+
+    fn cancel_viewport_input(
+        &mut self,
+        _: &menu::Cancel,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.viewport_input.take().is_some() {
+            self.focus_handle.focus(window);
+            self.notify_controls(cx);
+        }
+    }
+
+    fn export_png(&mut self, _: &ExportPng, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_export_input(SvgPreviewExportFormat::Png, window, cx);
+    }
+
+    fn export_jpeg(&mut self, _: &ExportJpeg, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_export_input(SvgPreviewExportFormat::Jpeg, window, cx);
+    }
+
+    fn export_webp(&mut self, _: &ExportWebp, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_export_input(SvgPreviewExportFormat::Webp, window, cx);
+    }
+
+    fn show_export_input(
+        &mut self,
+        format: SvgPreviewExportFormat,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(svg_size) = self.svg_size else {
+            return;
+        };
+        self.export.format = format;
+        let text = format!("{}x{}", svg_size.width.ceil(), svg_size.height.ceil());
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Width x Height, Width or Scale (2x)", window, cx);
+            editor.set_text(text, window, cx);
+            editor.select_all(&SelectAll, window, cx);
+            editor
+        });
+        let subscription = cx.subscribe_in(
+            &editor,
+            window,
+            |this, _, event: &EditorEvent, _window, cx| {
+                if let EditorEvent::Blurred = event {
+                    this.export.size_input = None;
+                    cx.notify();
+                }
+            },
+        );
+        editor.focus_handle(cx).focus(window);
+        self.export.size_input = Some(InlineInput {
+            editor,
+            _subscription: subscription,
+        });
+        cx.notify();
+    }
+
+    fn confirm_export_input(
+        &mut self,
+        _: &menu::Confirm,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(export_input) = self.export.size_input.take() else {
+            return;
+        };
+        self.focus_handle.focus(window);
+        cx.notify();
+        let text = export_input.editor.read(cx).text(cx);
+        let (Some(tree), Some(svg_size)) = (self.tree.clone(), self.svg_size) else {
+            return;
+        };
+        let Some(export_size) = parse_export_size(&text, svg_size) else {
+            return;
+        };
+        if svg_size.width > 0. {
+            self.export.scale = export_size.width.0 as f32 / svg_size.width;
+        }
+        let Some(encoding) = raster_encoding(self.export.format, self.export.quality) else {
+            return;
+        };
+        let background = match self.export.background {
+            ExportBackground::Transparent => None,
+            ExportBackground::White => Some(Rgba::from(gpui::white())),
+            ExportBackground::Black => Some(Rgba::from(gpui::black())),
+            ExportBackground::Preview => Some(Rgba::from(self.background_color(cx))),
+        };
+
+        self.export_file(
+            export_extension(self.export.format),
+            window,
+            cx,
+            move |renderer| renderer.render_raster(&tree, export_size, background, encoding),
+        );
+    }
+
+    fn export_element(
+        &mut self,
+        action: &ExportElement,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.tree.is_none() {
+            return;
+        }
+        let text = action.id.clone().unwrap_or_default();
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text(
+                "Element id, optionally with a scale (arrow@2x)",
+                window,
+                cx,
+            );
+            editor.set_text(text, window, cx);
+            editor.select_all(&SelectAll, window, cx);
+            editor
+        });
+        let subscription = cx.subscribe_in(
+            &editor,
+            window,
+            |this, _, event: &EditorEvent, _window, cx| {
+                if let EditorEvent::Blurred = event {
+                    this.export.element_input = None;
+                    cx.notify();
+                }
+            },
+        );
+        editor.focus_handle(cx).focus(window);
+        self.export.element_input = Some(InlineInput {
+            editor,
+            _subscription: subscription,
+        });
+        cx.notify();
+    }
+
+    fn confirm_export_element_input(
+        &mut self,
+        _: &menu::Confirm,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(input) = self.export.element_input.take() else {
+            return;
+        };
+        self.focus_handle.focus(window);
+        cx.notify();
+        let input = input.editor.read(cx).text(cx);
+        let (Some(tree), Some(text)) = (self.tree.clone(), self.svg_text.clone()) else {
+            return;
+        };
+        let Some((id, scale)) = element_export::parse_element_export(&input) else {
+            return;
+        };
+        let id = id.to_string();
+        let external_resources = self.external_resources.clone();
+        let fonts = self.fonts.clone();
+        let simulated_viewport = self.simulated_viewport;
+        let (directory, stem, fs) = self.export_target(cx);
+        let path = cx.prompt_for_new_path(&directory, Some(&format!("{stem}-{id}.png")));
+        let renderer = cx.svg_renderer();
+        cx.spawn_in(window, async move |_, cx| {
+            let Some(path) = path.await?? else {
+                return Ok(());
+            };
+            let png = cx
+                .background_spawn(async move {
+                    let bounds = tree
+                        .node_by_id(&id)
+                        .with_context(|| format!("There is no rendered element with id {id:?}"))?
+                        .abs_stroke_bounding_box();
+                    let isolated_text = element_export::isolate_element(&text, &id)
+                        .with_context(|| format!("There is no element with id {id:?}"))?;
+                    let mut options = external_resources.usvg_options(&renderer, fonts.as_deref());
+                    apply_simulated_viewport(&mut options, simulated_viewport);
+                    let isolated_tree = usvg::Tree::from_str(&isolated_text, &options)?;
+                    let device_pixels =
+                        |length: f32| DevicePixels((length * scale).ceil().max(1.) as i32);
+                    renderer.render_raster_region(
+                        &isolated_tree,
+                        point(bounds.x(), bounds.y()),
+                        size(
+                            device_pixels(bounds.width()),
+                            device_pixels(bounds.height()),
+                        ),
+                        scale,
+                        None,
+                        RasterEncoding::Png,
+                    )
+                })
+                .await?;
+            write_export(fs, path, png, cx.background_executor()).await
+        })
+        .detach_and_prompt_err("Failed to export element", window, cx, |_, _, _| None);
+    }
+
+    fn cancel_export_element_input(
+        &mut self,
+        _: &menu::Cancel,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.export.element_input.take().is_some() {
+            self.focus_handle.focus(window);
+            cx.notify();
+        }
+    }
+
+    fn render_export_element_input(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let input = self.export.element_input.as_ref()?;
+        let colors = cx.theme().colors();
+        Some(
+            h_flex()
+                .absolute()
+                .top_2()
+                .left_0()
+                .right_0()
+                .justify_center()
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .p_1()
+                        .rounded_sm()
+                        .border_1()
+                        .border_color(colors.border)
+                        .bg(colors.elevated_surface_background)
+                        .shadow_md()
+                        .child(Label::new("Export Element").size(LabelSize::Small))
+                        .child(
+                            h_flex()
+                                .w(px(200.))
+                                .px_1()
+                                .rounded_sm()
+                                .border_1()
+                                .border_color(colors.border_focused)
+                                .bg(colors.editor_background)
+                                .on_action(cx.listener(Self::confirm_export_element_input))
+                                .on_action(cx.listener(Self::cancel_export_element_input))
+                                .child(input.editor.clone()),
+                        ),
+                ),
+        )
+    }
+
+    /// Returns the id of the topmost element with an id at `position` in the window.
+    fn element_id_at(&self, position: Point<Pixels>) -> Option<String> {
+        element_export::element_id_at(self.tree.as_ref()?, self.svg_position(position)?)
+    }
+
+    /// Maps `position` in the window to the coordinates of the SVG's size, if it's on the image.
+    fn svg_position(&self, position: Point<Pixels>) -> Option<(f32, f32)> {
+        let svg_size = self.svg_size?;
+        let image_bounds = self.image_bounds()?;
+        if !image_bounds.contains(&position) {
+            return None;
+        }
+        let relative = position - image_bounds.origin;
+        Some((
+            relative.x / image_bounds.size.width * svg_size.width,
+            relative.y / image_bounds.size.height * svg_size.height,
+        ))
+    }
+
+    /// Returns the map between the SVG's source and the rendered elements, building it in the
+    /// background if it's out of date, after which [`Self::source_map_built`] is called.
+    fn source_map(&mut self, window: &Window, cx: &mut Context<Self>) -> Option<Arc<SourceMap>> {
+        if self.source_map.is_some() || self.source_map_task.is_some() {
+            return self.source_map.clone();
+        }
+        let text = self.source_text(cx)?;
+        let external_resources = self.external_resources.clone();
+        let fonts = self.fonts.clone();
+        let simulated_viewport = self.simulated_viewport;
+        let renderer = cx.svg_renderer();
+        self.source_map_task = Some(cx.spawn_in(window, async move |this, cx| {
+            let source_map = cx
+                .background_spawn(async move {
+                    let mut options = external_resources.usvg_options(&renderer, fonts.as_deref());
+                    apply_simulated_viewport(&mut options, simulated_viewport);
+                    SourceMap::new(&text, &options).map(Arc::new)
+                })
+                .await;
+            this.update_in(cx, |this, window, cx| {
+                this.source_map = source_map;
+                this.source_map_task = None;
+                this.source_map_built(window, cx);
+            })
+            .ok();
+        }));
+        None
+    }
+
+    fn source_map_built(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        cx.notify();
+        self.update_cursor_highlight(window, cx);
+        if let Some((position, _)) = self.hovered_element {
+            self.update_hovered_element(position, window, cx);
+        }
+        if let Some(svg_position) = self.pending_source_jump.take()
+            && self.source_map.is_some()
+        {
+            self.jump_to_source(svg_position, window, cx);
+        }
+        if mem::take(&mut self.pending_find_element)
+            && let Some(source_map) = self.source_map.clone()
+        {
+            self.open_element_finder(&source_map, window, cx);
+        }
+    }
+
+    /// Discards the source map, since the SVG changed, and rebuilds it if it's in use.
+    fn invalidate_source_map(&mut self, window: &Window, cx: &mut Context<Self>) {
+        self.source_map = None;
+        self.source_map_task = None;
+        if self.cursor_offset.is_some()
+            || self.show_outline
+            || self.show_path_points
+            || self.eyedropper.active
+            || self.show_accessibility_audit
+        {
+            self.source_map(window, cx);
+        }
+    }
+
+    /// Moves the cursor of the SVG's editor to the start tag of the topmost element at
+    /// `svg_position`, opening the editor if needed.
+    fn jump_to_source(
+        &mut self,
+        svg_position: (f32, f32),
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(source_map) = self.source_map(window, cx) else {
+            self.pending_source_jump = Some(svg_position);
+            return;
+        };
+        if let Some(offset) = source_map.offset_at(svg_position) {
+            self.select_in_editor(offset, true, window, cx);
+        }
+    }
+
+    fn open_source(&mut self, _: &OpenSource, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffer.clone() else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                Self::editor_for_buffer(workspace, buffer, true, window, cx);
+            })
+            .ok();
+    }
+
+    /// Returns the editor of `buffer` in `workspace`. If `activate` is true, the editor is
+    /// focused, and opened if needed; otherwise only an open editor is returned.
+    fn editor_for_buffer(
+        workspace: &mut Workspace,
+        buffer: Entity<Buffer>,
+        activate: bool,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Option<Entity<Editor>> {
+        let existing_editor = workspace.items_of_type::<Editor>(cx).find(|editor| {
+            editor.read(cx).buffer().read(cx).as_singleton().as_ref() == Some(&buffer)
+        });
+        match existing_editor {
+            Some(editor) => {
+                if activate {
+                    workspace.activate_item(&editor, true, true, window, cx);
+                }
+                Some(editor)
+            }
+            None if activate => Some(workspace.open_project_item::<Editor>(
+                workspace.active_pane().clone(),
+                buffer,
+                true,
+                true,
+                true,
+                true,
+                window,
+                cx,
+            )),
+            None => None,
+        }
+    }
+
+    /// Moves the cursor of the SVG's editor to `offset`. If `activate` is true, the editor is
+    /// focused, and opened if needed; otherwise only an open editor is scrolled.
+    fn select_in_editor(
+        &mut self,
+        offset: usize,
+        activate: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (Some(buffer), Some(source_range)) = (self.buffer.clone(), self.source_range(cx))
+        else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let Some(editor) = Self::editor_for_buffer(workspace, buffer, activate, window, cx)
+                else {
+                    return;
+                };
+                editor.update(cx, |editor, cx| {
+                    let offset = MultiBufferOffset(source_range.start + offset);
+                    editor.change_selections(
+                        SelectionEffects::scroll(Autoscroll::center()),
+                        window,
+                        cx,
+                        |selections| selections.select_ranges([offset..offset]),
+                    );
+                });
+            })
+            .ok();
+    }
+
+    /// Follows the cursor of `editor` if it edits the previewed SVG.
+    fn track_editor(
+        &mut self,
+        editor: Entity<Editor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let buffer = editor.read(cx).buffer().read(cx).as_singleton();
+        if buffer.is_none() || buffer != self.buffer {
+            return;
+        }
+        self._editor_subscription = Some(cx.subscribe_in(
+            &editor,
+            window,
+            |this, editor, event: &EditorEvent, window, cx| {
+                if let EditorEvent::SelectionsChanged { .. } = event {
+                    this.update_cursor_offset(editor, window, cx);
+                }
+            },
+        ));
+        self.update_cursor_offset(&editor, window, cx);
+    }
+
+    fn update_cursor_offset(
+        &mut self,
+        editor: &Entity<Editor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let offset = editor.update(cx, |editor, cx| {
+            editor
+                .selections
+                .newest::<MultiBufferOffset>(&editor.display_snapshot(cx))
+                .head()
+        });
+        // Offsets are relative to the start of embedded SVGs.
+        self.cursor_offset = self.source_range(cx).and_then(|range| {
+            (range.start <= offset.0 && offset.0 <= range.end).then(|| offset.0 - range.start)
+        });
+        self.update_cursor_highlight(window, cx);
+    }
+
+    fn update_cursor_highlight(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let highlight = match self.cursor_offset {
+            Some(offset) => match self.source_map(window, cx) {
+                Some(source_map) => source_map.bounds_at(offset),
+                None => return,
+            },
+            None => None,
+        };
+        if self.cursor_highlight != highlight {
+            self.cursor_highlight = highlight;
+            cx.notify();
+        }
+    }
+
+    fn update_hovered_element(
+        &mut self,
+        position: Point<Pixels>,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) {
+        let hovered_element = self.svg_position(position).map(|svg_position| {
+            let label = self.source_map(window, cx).and_then(|source_map| {
+                source_map
+                    .label_at(svg_position)
+                    .map(|label| SharedString::from(label.to_string()))
+            });
+            (position, label)
+        });
+        if self.hovered_element != hovered_element {
+            self.hovered_element = hovered_element;
+            cx.notify();
+        }
+    }
+
+    /// Shows the tag name, id and classes of the element under the mouse next to it.
+    fn render_hovered_element(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let (position, Some(label)) = self.hovered_element.clone()? else {
+            return None;
+        };
+        let viewport_origin = self.viewport_bounds.origin;
+        let colors = cx.theme().colors();
+        Some(
+            div()
+                .absolute()
+                .left(position.x - viewport_origin.x + px(12.))
+                .top(position.y - viewport_origin.y + px(12.))
+                .px_1()
+                .rounded_sm()
+                .border_1()
+                .border_color(colors.border)
+                .bg(colors.elevated_surface_background)
+                .shadow_md()
+                .child(Label::new(label).size(LabelSize::Small).buffer_font(cx)),
+        )
+    }
+
+    fn find_element(&mut self, _: &FindElement, window: &mut Window, cx: &mut Context<Self>) {
+        match self.source_map(window, cx) {
+            Some(source_map) => self.open_element_finder(&source_map, window, cx),
+            None => self.pending_find_element = true,
+        }
+    }
+
+    fn open_element_finder(
+        &mut self,
+        source_map: &SourceMap,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let selectors = source_map
+            .selectors()
+            .into_iter()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        let preview = cx.entity().downgrade();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.toggle_modal(window, cx, |window, cx| {
+                    ElementFinder::new(selectors, preview, window, cx)
+                })
+            })
+            .ok();
+    }
+
+    /// Zooms to the elements matched by `selector`, an id or class such as `#arrow` or
+    /// `.stroke-2`, and briefly outlines them.
+    pub(crate) fn reveal_selector(
+        &mut self,
+        selector: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(bounds) = self
+            .source_map
+            .as_ref()
+            .and_then(|source_map| source_map.selector_bounds(selector))
+        else {
+            return;
+        };
+        let Some(svg_size) = self.svg_size else {
+            return;
+        };
+        let viewport_size = self.viewport_bounds.size;
+        let scale_factor = (viewport_size.width / px(bounds.width().max(1.)))
+            .min(viewport_size.height / px(bounds.height().max(1.)))
+            * REVEAL_FILL;
+        let scale_factor = scale_factor.clamp(MIN_SCALE_FACTOR, MAX_SCALE_FACTOR);
+        self.fit_to_window = false;
+        self.scale_factor = scale_factor;
+        // Puts the center of the bounds at the center of the viewport.
+        self.image_offset = point(
+            px((svg_size.width / 2. - (bounds.x() + bounds.width() / 2.)) * scale_factor),
+            px((svg_size.height / 2. - (bounds.y() + bounds.height() / 2.)) * scale_factor),
+        );
+        self.clamp_image_offset();
+        cx.emit(SvgPreviewEvent::ViewChanged);
+
+        let clear = cx.spawn_in(window, async move |this, cx| {
+            cx.background_executor().timer(FLASH_DURATION).await;
+            this.update(cx, |this, cx| {
+                this.flashed_bounds = None;
+                cx.notify();
+            })
+            .ok();
+        });
+        self.flashed_bounds = Some((bounds, clear));
+        cx.notify();
+    }
+
+    fn render_flashed_bounds(
+        &self,
+        image_bounds: Bounds<Pixels>,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let (rect, _) = self.flashed_bounds.as_ref()?;
+        let bounds = self.svg_rect_bounds(*rect, image_bounds)?;
+        Some(
+            div()
+                .absolute()
+                .left(bounds.origin.x)
+                .top(bounds.origin.y)
+                .w(bounds.size.width)
+                .h(bounds.size.height)
+                .border_2()
+                .border_color(cx.theme().colors().text_accent),
+        )
+    }
+
+    /// Outlines the element under the editor's cursor.
+    fn render_cursor_highlight(
+        &self,
+        image_bounds: Bounds<Pixels>,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let bounds = self.svg_rect_bounds(self.cursor_highlight?, image_bounds)?;
+        let color = cx.theme().colors().border_focused;
+        Some(
+            div()
+                .absolute()
+                .left(bounds.origin.x)
+                .top(bounds.origin.y)
+                .w(bounds.size.width)
+                .h(bounds.size.height)
+                .border_1()
+                .border_color(color)
+                .bg(color.opacity(0.1)),
+        )
+    }
+
+    /// Outlines the bounding boxes of the elements down to the configured depth.
+    fn render_bounding_boxes(
+        &self,
+        image_bounds: Bounds<Pixels>,
+        cx: &mut Context<Self>,
+    ) -> Vec<impl IntoElement> {
+        let Some(tree) = self.tree.as_ref() else {
+            return Vec::new();
+        };
+        let depth = SvgPreviewSettings::get_global(cx).bounding_box_depth;
+        let color = cx.theme().colors().text_accent;
+        bounding_boxes(tree, depth)
+            .into_iter()
+            .filter_map(|rect| self.svg_rect_bounds(rect, image_bounds))
+            .map(|bounds| {
+                div()
+                    .absolute()
+                    .left(bounds.origin.x)
+                    .top(bounds.origin.y)
+                    .w(bounds.size.width)
+                    .h(bounds.size.height)
+                    .border_1()
+                    .border_dashed()
+                    .border_color(color.opacity(0.7))
+            })
+            .collect()
+    }
+
+    /// Maps `rect`, in the coordinates of the SVG's size, to bounds relative to the viewport.
+    fn svg_rect_bounds(
+        &self,
+        rect: usvg::Rect,
+        image_bounds: Bounds<Pixels>,
+    ) -> Option<Bounds<Pixels>> {
+        let svg_size = self.svg_size?;
+        let scale_x = image_bounds.size.width / svg_size.width;
+        let scale_y = image_bounds.size.height / svg_size.height;
+        let viewport_origin = self.viewport_bounds.origin;
+        Some(Bounds::new(
+            point(
+                image_bounds.origin.x - viewport_origin.x + scale_x * rect.x(),
+                image_bounds.origin.y - viewport_origin.y + scale_y * rect.y(),
+            ),
+            size(scale_x * rect.width(), scale_y * rect.height()),
+        ))
+    }
+
+    fn toggle_path_points(
+        &mut self,
+        _: &TogglePathPoints,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.show_path_points = !self.show_path_points;
+        if self.show_path_points {
+            self.source_map(window, cx);
+        }
+        cx.notify();
+    }
+
+    /// Draws the anchors and control points of the path under the editor's cursor, along with
+    /// the handles connecting them.
+    fn render_path_points(
+        &self,
+        image_bounds: Bounds<Pixels>,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let path = self.source_map.as_ref()?.path_at(self.cursor_offset?)?;
+        let svg_size = self.svg_size?;
+        let points = path_points(path);
+        let to_window = move |(x, y): (f32, f32)| {
+            point(
+                image_bounds.origin.x + image_bounds.size.width * (x / svg_size.width),
+                image_bounds.origin.y + image_bounds.size.height * (y / svg_size.height),
+            )
+        };
+        let colors = cx.theme().colors();
+        let (anchor_color, control_color, handle_color) = (
+            colors.text_accent,
+            colors.editor_background,
+            colors.text_muted,
+        );
+        Some(
+            canvas(
+                |_, _, _| {},
+                move |_, _, window, _| {
+                    for (start, end) in &points.handles {
+                        let mut builder = PathBuilder::stroke(px(1.));
+                        builder.move_to(to_window(*start));
+                        builder.line_to(to_window(*end));
+                        if let Ok(path) = builder.build() {
+                            window.paint_path(path, handle_color);
+                        }
+                    }
+                    let point_size = size(px(6.), px(6.));
+                    for control in &points.controls {
+                        window.paint_quad(
+                            fill(
+                                Bounds::centered_at(to_window(*control), point_size),
+                                control_color,
+                            )
+                            .corner_radii(px(3.))
+                            .border_widths(px(1.))
+                            .border_color(anchor_color),
+                        );
+                    }
+                    for anchor in &points.anchors {
+                        window.paint_quad(fill(
+                            Bounds::centered_at(to_window(*anchor), point_size),
+                            anchor_color,
+                        ));
+                    }
+                },
+            )
+            .absolute()
+            .size_full(),
+        )
+    }
+
+    /// Reports the problems in the SVG as diagnostics of its buffer, instead of the problems in
+    /// the buffer it showed before.
+    fn watch_diagnostics(&self, cx: &mut Context<Self>) {
+        let preview = cx.entity_id();
+        match (&self.project, &self.buffer) {
+            (Some(project), Some(buffer)) => {
+                SvgDiagnostics::watch(preview, project, buffer, self.embedded_range.clone(), cx)
+            }
+            _ => SvgDiagnostics::unwatch(preview, cx),
+        }
+    }
+
+    fn toggle_hidden_elements(
+        &mut self,
+        _: &ToggleHiddenElements,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.hidden_elements.shown = !self.hidden_elements.shown;
+        if self.hidden_elements.shown {
+            self.update_hidden_elements(Duration::ZERO, window, cx);
+        } else {
+            self.hidden_elements.task = None;
+            self.set_hidden_elements(None, window, cx);
+        }
+        self.subscribe_panels_to_edits(window, cx);
+    }
+
+    /// Renders the hidden elements in the background after `debounce`, if they're shown. The
+    /// previous rendering stays on screen until then.
+    fn update_hidden_elements(
+        &mut self,
+        debounce: Duration,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.hidden_elements.shown {
+            return;
+        }
+        let Some(text) = self.source_text(cx) else {
+            return;
+        };
+        let external_resources = self.external_resources.clone();
+        let fonts = self.fonts.clone();
+        let simulated_viewport = self.simulated_viewport;
+        let renderer = cx.svg_renderer();
+        self.hidden_elements.task = Some(cx.spawn_in(window, async move |this, cx| {
+            cx.background_executor().timer(debounce).await;
+            let hidden_elements = cx
+                .background_spawn(async move {
+                    let mut options = external_resources.usvg_options(&renderer, fonts.as_deref());
+                    apply_simulated_viewport(&mut options, simulated_viewport);
+                    let hidden_elements = HiddenElements::new(&text, &options)?;
+                    let tree = &hidden_elements.tree;
+                    let svg_size = size(tree.size().width(), tree.size().height());
+                    let image = render_whole_svg(&renderer, tree, svg_size, OVERVIEW_SIZE).ok()?;
+                    Some((image, hidden_elements.bounds))
+                })
+                .await;
+            this.update_in(cx, |this, window, cx| {
+                this.hidden_elements.task = None;
+                this.set_hidden_elements(hidden_elements, window, cx);
+            })
+            .ok();
+        }));
+    }
+
+    fn set_hidden_elements(
+        &mut self,
+        hidden_elements: Option<(Arc<RenderImage>, Vec<usvg::Rect>)>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some((image, _)) = mem::replace(&mut self.hidden_elements.rendering, hidden_elements)
+        {
+            window.drop_image(image).ok();
+        }
+        cx.notify();
+    }
+
+    fn toggle_gallery(&mut self, _: &ToggleGallery, window: &mut Window, cx: &mut Context<Self>) {
+        self.gallery.shown = !self.gallery.shown;
+        if self.gallery.shown {
+            self.update_gallery(Duration::ZERO, window, cx);
+        } else {
+            self.gallery.task = None;
+            self.set_gallery(Vec::new(), window, cx);
+            self.isolate_gallery_entry(None, window, cx);
+        }
+        self.subscribe_panels_to_edits(window, cx);
+        self.notify_controls(cx);
+    }
+
+    fn set_gallery_options(
+        &mut self,
+        options: GalleryOptions,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let size_changed = options.tile_size != self.gallery.options.tile_size;
+        self.gallery.options = options;
+        if size_changed {
+            self.update_gallery(Duration::ZERO, window, cx);
+        }
+        cx.notify();
+    }
+
+    /// Renders each entry of the sprite at the size of the gallery's tiles in the background after
+    /// `debounce`, if the gallery is shown. The previous renderings stay on screen until then.
+    fn update_gallery(&mut self, debounce: Duration, window: &Window, cx: &mut Context<Self>) {
+        if !self.gallery.shown {
+            return;
+        }
+        let Some(text) = self.source_text(cx) else {
+            return;
+        };
+        let external_resources = self.external_resources.clone();
+        let fonts = self.fonts.clone();
+        let renderer = cx.svg_renderer();
+        let length = self.gallery.options.render_size(window);
+        self.gallery.task = Some(cx.spawn_in(window, async move |this, cx| {
+            cx.background_executor().timer(debounce).await;
+            let gallery = cx
+                .background_spawn(async move {
+                    let Ok(document) = animation::parse_document(&text) else {
+                        return Vec::new();
+                    };
+                    let options = external_resources.usvg_options(&renderer, fonts.as_deref());
+                    sprite::sprite_ids(&document)
+                        .into_iter()
+                        .filter_map(|id| {
+                            let image =
+                                render_sprite_entry(&text, &id, &options, &renderer, length)?;
+                            Some((SharedString::from(id), image))
+                        })
+                        .collect()
+                })
+                .await;
+            this.update_in(cx, |this, window, cx| {
+                this.gallery.task = None;
+                this.set_gallery(gallery, window, cx);
+                this.update_isolated_gallery_entry(window, cx);
+            })
+            .ok();
+        }));
+    }
+
+    fn set_gallery(
+        &mut self,
+        gallery: Vec<(SharedString, Arc<RenderImage>)>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for (_, image) in mem::replace(&mut self.gallery.tiles, gallery) {
+            window.drop_image(image).ok();
+        }
+        if self
+            .gallery
+            .isolated_entry
+            .is_some_and(|index| index >= self.gallery.tiles.len())
+        {
+            self.isolate_gallery_entry(None, window, cx);
+        }
+        cx.notify();
+    }
+
+    fn isolate_gallery_entry(
+        &mut self,
+        index: Option<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.gallery.isolated_entry = index;
+        self.set_isolated_gallery_image(None, window, cx);
+        self.update_isolated_gallery_entry(window, cx);
+    }
+
+    /// Renders the isolated entry of the gallery at the size of the overview in the background.
+    /// Its tile's rendering is shown until then.
+    fn update_isolated_gallery_entry(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let Some((id, _)) = self
+            .gallery
+            .isolated_entry
+            .and_then(|index| self.gallery.tiles.get(index))
+        else {
+            self.gallery.isolated_task = None;
+            return;
+        };
+        let Some(text) = self.source_text(cx) else {
+            return;
+        };
+        let id = id.clone();
+        let external_resources = self.external_resources.clone();
+        let fonts = self.fonts.clone();
+        let renderer = cx.svg_renderer();
+        self.gallery.isolated_task = Some(cx.spawn_in(window, async move |this, cx| {
+            let image = cx
+                .background_spawn(async move {
+                    let options = external_resources.usvg_options(&renderer, fonts.as_deref());
+                    render_sprite_entry(&text, &id, &options, &renderer, OVERVIEW_SIZE)
+                })
+                .await;
+            this.update_in(cx, |this, window, cx| {
+                this.gallery.isolated_task = None;
+                this.set_isolated_gallery_image(image, window, cx);
+            })
+            .ok();
+        }));
+    }
+
+    fn set_isolated_gallery_image(
+        &mut self,
+        image: Option<Arc<RenderImage>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(image) = mem::replace(&mut self.gallery.isolated_image, image) {
+            window.drop_image(image).ok();
+        }
+        cx.notify();
+    }
+
+    /// Shows the entries of the sprite as a grid of tiles over the preview. Clicking a tile shows
+    /// its entry on its own, until it's clicked again.
+    fn render_gallery(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let options = &self.gallery.options;
+        let isolated_entry = self
+            .gallery
+            .isolated_entry
+            .and_then(|index| self.gallery.tiles.get(index));
+
+        v_flex()
+            .id("sprite-gallery")
+            .absolute()
+            .inset_0()
+            .p_4()
+            .gap_2()
+            .overflow_y_scroll()
+            .bg(options.background_color(cx))
+            // Keep clicks in the gallery from panning the preview.
+            .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        Label::new(match isolated_entry {
+                            Some((id, _)) => format!("#{id}"),
+                            None => format!("{} symbols", self.gallery.tiles.len()),
+                        })
+                        .size(LabelSize::Small),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(gallery::render_controls(
+                                options,
+                                cx.listener(|this, options: &GalleryOptions, window, cx| {
+                                    this.set_gallery_options(options.clone(), window, cx)
+                                }),
+                                cx,
+                            ))
+                            .child(
+                                IconButton::new("close-gallery", IconName::Close)
+                                    .icon_size(IconSize::Small)
+                                    .tooltip(Tooltip::for_action_title_in(
+                                        "Close Gallery",
+                                        &ToggleGallery,
+                                        &self.focus_handle,
+                                    ))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.toggle_gallery(&ToggleGallery, window, cx)
+                                    })),
+                            ),
+                    ),
+            )
+            .map(|this| match isolated_entry {
+                Some((_, tile_image)) => {
+                    let image = self
+                        .gallery
+                        .isolated_image
+                        .clone()
+                        .unwrap_or_else(|| tile_image.clone());
+                    this.child(
+                        div()
+                            .id("isolated-gallery-entry")
+                            .flex_1()
+                            .cursor_pointer()
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.isolate_gallery_entry(None, window, cx)
+                            }))
+                            .child(img(image).size_full().object_fit(ObjectFit::Contain)),
+                    )
+                }
+                None => this.child(
+                    h_flex().flex_wrap().gap_2().children(
+                        self.gallery
+                            .tiles
+                            .iter()
+                            .enumerate()
+                            .map(|(index, (id, image))| {
+                                gallery::render_tile(
+                                    ("gallery-entry", index),
+                                    img(image.clone())
+                                        .size_full()
+                                        .object_fit(ObjectFit::Contain),
+                                    id.clone(),
+                                    Color::Muted,
+                                    options,
+                                    cx,
+                                )
+                                .on_click(cx.listener(
+                                    move |this, _, window, cx| {
+                                        this.isolate_gallery_entry(Some(index), window, cx)
+                                    },
+                                ))
+                            }),
+                    ),
+                ),
+            })
+    }
+
+    /// Draws the hidden elements faintly over the image, with their bounds hatched.
+    fn render_hidden_elements(
+        &self,
+        image_bounds: Bounds<Pixels>,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        let Some((image, bounds)) = self.hidden_elements.rendering.clone() else {
+            return Vec::new();
+        };
+        let viewport_origin = self.viewport_bounds.origin;
+        let color = cx.theme().colors().text_muted;
+        let ghost = img(image)
+            .object_fit(ObjectFit::Fill)
+            .absolute()
+            .left(image_bounds.origin.x - viewport_origin.x)
+            .top(image_bounds.origin.y - viewport_origin.y)
+            .w(image_bounds.size.width)
+            .h(image_bounds.size.height)
+            .opacity(HIDDEN_ELEMENT_OPACITY)
+            .into_any_element();
+        let hatches = bounds
+            .into_iter()
+            .filter_map(|rect| self.svg_rect_bounds(rect, image_bounds))
+            .map(|bounds| {
+                div()
+                    .absolute()
+                    .left(bounds.origin.x)
+                    .top(bounds.origin.y)
+                    .w(bounds.size.width)
+                    .h(bounds.size.height)
+                    .border_1()
+                    .border_dashed()
+                    .border_color(color.opacity(0.6))
+                    .bg(pattern_slash(color.opacity(0.3), 1., 6.))
+                    .into_any_element()
+            });
+        std::iter::once(ghost).chain(hatches).collect()
+    }
+
+    fn toggle_eyedropper(
+        &mut self,
+        _: &ToggleEyedropper,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.eyedropper.active = !self.eyedropper.active;
+        self.eyedropper.sample = None;
+        if self.eyedropper.active {
+            self.source_map(window, cx);
+        }
+        self.notify_controls(cx);
+    }
+
+    /// Copies the hex value of the rendered color at `position`, in the coordinates of the SVG's
+    /// size, and describes which element's paint it comes from.
+    fn pick_color(&mut self, position: Point<f32>, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(tree) = self.tree.clone() else {
+            return;
+        };
+        let renderer = cx.svg_renderer();
+        let scale_factor = self.scale_factor * window.scale_factor();
+        let source_map = self.source_map(window, cx);
+        cx.spawn_in(window, async move |this, cx| {
+            let color = cx
+                .background_spawn(
+                    async move { renderer.render_pixel(&tree, position, scale_factor) },
+                )
+                .await?;
+            let hex = hex_rgba(color);
+            let source = source_map.as_ref().and_then(|source_map| {
+                let (label, path) = source_map.label_and_path_at((position.x, position.y))?;
+                Some(match path.and_then(|path| paint_with_color(path, color)) {
+                    Some(paint) => format!("{paint} of {label}"),
+                    None => label.to_string(),
+                })
+            });
+            let description = match source {
+                Some(source) => format!("Copied {hex} · {source}"),
+                None => format!("Copied {hex}"),
+            };
+            this.update(cx, |this, cx| {
+                cx.write_to_clipboard(ClipboardItem::new_string(hex));
+                this.eyedropper.sample = Some((position, color, description.into()));
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Shows the last picked color next to where it was picked.
+    fn render_color_sample(
+        &self,
+        image_bounds: Bounds<Pixels>,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let (position, color, description) = self.eyedropper.sample.clone()?;
+        let svg_size = self.svg_size?;
+        let viewport_origin = self.viewport_bounds.origin;
+        let colors = cx.theme().colors();
+        Some(
+            h_flex()
+                .absolute()
+                .left(
+                    image_bounds.origin.x - viewport_origin.x
+                        + image_bounds.size.width * (position.x / svg_size.width)
+                        + px(12.),
+                )
+                .top(
+                    image_bounds.origin.y - viewport_origin.y
+                        + image_bounds.size.height * (position.y / svg_size.height)
+                        + px(12.),
+                )
+                .gap_1()
+                .px_1()
+                .rounded_sm()
+                .border_1()
+                .border_color(colors.border)
+                .bg(colors.elevated_surface_background)
+                .shadow_md()
+                .child(
+                    div()
+                        .size_3()
+                        .rounded_xs()
+                        .border_1()
+                        .border_color(colors.border)
+                        .bg(color),
+                )
+                .child(
+                    Label::new(description)
+                        .size(LabelSize::Small)
+                        .buffer_font(cx),
+                ),
+        )
+    }
+
+    fn toggle_measure(&mut self, _: &ToggleMeasure, _window: &mut Window, cx: &mut Context<Self>) {
+        self.measure.active = !self.measure.active;
+        self.measure.measurement = None;
+        self.measure.dragging = false;
+        self.notify_controls(cx);
+    }
+
+    /// Moves the end of the measurement to `position` in the window, keeping it on the image.
+    fn update_measurement(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        let Some(image_bounds) = self.image_bounds() else {
+            return;
+        };
+        let position = position.clamp(&image_bounds.origin, &image_bounds.bottom_right());
+        if let Some(measurement) = self.measure.measurement.as_mut()
+            && let Some((x, y)) = self.svg_position(position)
+        {
+            measurement.end = point(x, y);
+            cx.notify();
+        }
+    }
+
+    /// Draws the measured line along with its length next to its end.
+    fn render_measurement(
+        &self,
+        image_bounds: Bounds<Pixels>,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let measurement = self.measure.measurement?;
+        let svg_size = self.svg_size?;
+        let label = measurement.label(&self.user_space?, self.export.scale);
+        let to_window = move |position: Point<f32>| {
+            point(
+                image_bounds.origin.x + image_bounds.size.width * (position.x / svg_size.width),
+                image_bounds.origin.y + image_bounds.size.height * (position.y / svg_size.height),
+            )
+        };
+        let (start, end) = (to_window(measurement.start), to_window(measurement.end));
+        let viewport_origin = self.viewport_bounds.origin;
+        let colors = cx.theme().colors();
+        let line_color = colors.text_accent;
+        Some(
+            div()
+                .absolute()
+                .size_full()
+                .child(
+                    canvas(
+                        |_, _, _| {},
+                        move |_, _, window, _| {
+                            let mut builder = PathBuilder::stroke(px(1.));
+                            builder.move_to(start);
+                            builder.line_to(end);
+                            if let Ok(path) = builder.build() {
+                                window.paint_path(path, line_color);
+                            }
+                            for position in [start, end] {
+                                window.paint_quad(fill(
+                                    Bounds::centered_at(position, size(px(5.), px(5.))),
+                                    line_color,
+                                ));
+                            }
+                        },
+                    )
+                    .absolute()
+                    .size_full(),
+                )
+                .child(
+                    div()
+                        .absolute()
+                        .left(end.x - viewport_origin.x + px(8.))
+                        .top(end.y - viewport_origin.y + px(8.))
+                        .px_1()
+                        .rounded_sm()
+                        .border_1()
+                        .border_color(colors.border)
+                        .bg(colors.elevated_surface_background)
+                        .shadow_md()
+                        .child(Label::new(label).size(LabelSize::Small).buffer_font(cx)),
+                ),
+        )
+    }
+
+    fn toggle_canvas_outline(
+        &mut self,
+        _: &ToggleCanvasOutline,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.show_canvas_outline = !self.show_canvas_outline;
+        cx.notify();
+    }
+
+    /// Casts a shadow around the canvas, drawn beneath the image.
+    fn render_canvas_shadow(&self, image_bounds: Bounds<Pixels>, cx: &App) -> impl IntoElement {
+        let viewport_origin = self.viewport_bounds.origin;
+        div()
+            .absolute()
+            .left(image_bounds.origin.x - viewport_origin.x)
+            .top(image_bounds.origin.y - viewport_origin.y)
+            .w(image_bounds.size.width)
+            .h(image_bounds.size.height)
+            // The background keeps the shadow from showing through transparent areas.
+            .bg(self.background_color(cx))
+            .shadow_lg()
+    }
+
+    /// Outlines the edges of the canvas, and those of the `viewBox` where they differ.
+    fn render_canvas_outline(
+        &self,
+        image_bounds: Bounds<Pixels>,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        let viewport_origin = self.viewport_bounds.origin;
+        let colors = cx.theme().colors();
+        let mut outlines = vec![
+            div()
+                .absolute()
+                .left(image_bounds.origin.x - viewport_origin.x)
+                .top(image_bounds.origin.y - viewport_origin.y)
+                .w(image_bounds.size.width)
+                .h(image_bounds.size.height)
+                .border_1()
+                .border_color(colors.border)
+                .into_any_element(),
+        ];
+        let view_box = self
+            .user_space
+            .and_then(|user_space| user_space.view_box_bounds())
+            .filter(|view_box| {
+                self.svg_size
+                    .is_some_and(|svg_size| *view_box != Bounds::new(point(0., 0.), svg_size))
+            })
+            .and_then(|view_box| {
+                usvg::Rect::from_xywh(
+                    view_box.origin.x,
+                    view_box.origin.y,
+                    view_box.size.width,
+                    view_box.size.height,
+                )
+            })
+            .and_then(|view_box| self.svg_rect_bounds(view_box, image_bounds));
+        if let Some(view_box) = view_box {
+            outlines.push(
+                div()
+                    .absolute()
+                    .left(view_box.origin.x)
+                    .top(view_box.origin.y)
+                    .w(view_box.size.width)
+                    .h(view_box.size.height)
+                    .border_1()
+                    .border_dashed()
+                    .border_color(colors.border_focused)
+                    .into_any_element(),
+            );
+        }
+        outlines
+    }
+
+    fn toggle_bounding_boxes(
+        &mut self,
+        _: &ToggleBoundingBoxes,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.show_bounding_boxes = !self.show_bounding_boxes;
+        self.notify_controls(cx);
+    }
+
+    fn export_ico(&mut self, _: &ExportIco, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(tree) = self.tree.clone() else {
+            return;
+        };
+        self.export_file("ico", window, cx, move |renderer| {
+            render_ico(renderer, &tree)
+        });
+    }
+
+    fn export_icns(&mut self, _: &ExportIcns, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(tree) = self.tree.clone() else {
+            return;
+        };
+        self.export_file("icns", window, cx, move |renderer| {
+            render_icns(renderer, &tree)
+        });
+    }
+
+    /// Copies the shown frame to the clipboard as a PNG, at the SVG's size in device pixels.
+    fn copy_image(&mut self, _: &CopyImage, window: &mut Window, cx: &mut Context<Self>) {
+        let (Some(tree), Some(svg_size)) = (self.tree.clone(), self.svg_size) else {
+            return;
+        };
+        self.update_animation_time();
+        let snapshot = self.animation.animations.clone().zip(self.animation.time);
+        let renderer = cx.svg_renderer();
+        let external_resources = self.external_resources.clone();
+        let fonts = self.fonts.clone();
+        let simulated_viewport = self.simulated_viewport;
+        let device_pixels =
+            |length: f32| DevicePixels((length * window.scale_factor()).ceil() as i32);
+        let image_size = size(
+            device_pixels(svg_size.width),
+            device_pixels(svg_size.height),
+        );
+        cx.spawn_in(window, async move |_, cx| {
+            let png = cx
+                .background_spawn(async move {
+                    let tree = match snapshot {
+                        Some((animations, time)) => Arc::new(animation_snapshot_tree(
+                            &animations,
+                            time,
+                            &renderer,
+                            &external_resources,
+                            fonts.as_deref(),
+                            simulated_viewport,
+                        )?),
+                        None => tree,
+                    };
+                    renderer.render_png(&tree, image_size, None)
+                })
+                .await?;
+            cx.update(|_, cx| {
+                cx.write_to_clipboard(ClipboardItem::new_image(&Image::from_bytes(
+                    ImageFormat::Png,
+                    png,
+                )))
+            })
+        })
+        .detach_and_prompt_err("Failed to copy image", window, cx, |_, _, _| None);
+    }
+
+    fn deploy_context_menu(
+        &mut self,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let has_svg = self.tree.is_some();
+        let focus_handle = self.focus_handle.clone();
+        let element_id = self.element_id_at(event.position);
+        let preset_names = self.export_preset_names(cx);
+        let view = cx.entity();
+        let has_source = self.buffer.is_some();
+        let is_embedded = self.embedded_range.is_some();
+        let entry_id = self
+            .buffer
+            .as_ref()
+            .and_then(|buffer| buffer.read(cx).entry_id(cx));
+        let context_menu = ContextMenu::build(window, cx, |menu, window, _| {
+            menu.context(focus_handle)
+                .when(has_svg, |menu| {
+                    let menu = menu.action("Copy Image", Box::new(CopyImage)).separator();
+                    export_menu_entries(menu, element_id, preset_names)
+                        .separator()
+                        .action("Clean SVG", Box::new(CleanSvg))
+                        .action("Optimize SVG", Box::new(OptimizeSvg))
+                        .action("Optimization Report", Box::new(ShowOptimizationReport))
+                        .action_disabled_when(
+                            is_embedded,
+                            "Compare with HEAD",
+                            Box::new(CompareWithHead),
+                        )
+                        .separator()
+                })
+                .action("Toggle Background", Box::new(ToggleBackground))
+                .action("Fit to Window", Box::new(FitToWindow))
+                .action("Reset View", Box::new(ResetView))
+                .when(has_source, |menu| {
+                    menu.separator().action("Open Source", Box::new(OpenSource))
+                })
+                .when_some(entry_id, |menu, entry_id| {
+                    menu.entry(
+                        "Reveal in Project Panel",
+                        Some(Box::new(RevealInProjectPanel::default())),
+                        window.handler_for(&view, move |this, _, cx| {
+                            if let Some(project) = &this.project {
+                                project.update(cx, |_, cx| {
+                                    cx.emit(project::Event::RevealInProjectPanel(entry_id))
+                                });
+                            }
+                        }),
+                    )
+                })
+        });
+        window.focus(&context_menu.focus_handle(cx));
+        let subscription = cx.subscribe(&context_menu, |this, _, _: &DismissEvent, cx| {
+            this.context_menu.take();
+            cx.notify();
+        });
+        self.context_menu = Some((context_menu, event.position, subscription));
+        cx.notify();
+    }
+
+    fn export_pdf(&mut self, _: &ExportPdf, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(tree) = self.tree.clone() else {
+            return;
+        };
+        self.export_file("pdf", window, cx, move |_| Ok(pdf::pdf(&tree)));
+    }
+
+    fn export_with_preset(
+        &mut self,
+        action: &ExportWithPreset,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (Some(tree), Some(svg_size)) = (self.tree.clone(), self.svg_size) else {
+            return;
+        };
+        let presets = SvgPreviewSettings::get_global(cx)
+            .export_presets
+            .iter()
+            .filter(|preset| {
+                action
+                    .preset
+                    .as_ref()
+                    .is_none_or(|name| *name == preset.name)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        let missing_preset = action.preset.clone().filter(|_| presets.is_empty());
+        let (directory, stem, fs) = self.export_target(cx);
+        let renderer = cx.svg_renderer();
+        cx.spawn_in(window, async move |_, cx| {
+            if let Some(name) = missing_preset {
+                anyhow::bail!("There is no export preset named {name:?}");
+            }
+            for preset in presets {
+                let (contents, export_size) = cx
+                    .background_spawn({
+                        let renderer = renderer.clone();
+                        let tree = tree.clone();
+                        let preset = preset.clone();
+                        async move { render_preset(&renderer, &tree, svg_size, &preset) }
+                    })
+                    .await?;
+                let path = preset_output_path(&preset, &directory, &stem, export_size);
+                write_export(fs.clone(), path, contents, cx.background_executor()).await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to export", window, cx, |_, _, _| None);
+    }
+
+    /// Prompts for the path of a file with `extension`, renders its contents in the background
+    /// with `render`, and writes them.
+    fn export_file(
+        &self,
+        extension: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        render: impl FnOnce(&SvgRenderer) -> anyhow::Result<Vec<u8>> + Send + 'static,
+    ) {
+        let (directory, stem, fs) = self.export_target(cx);
+        let path = cx.prompt_for_new_path(&directory, Some(&format!("{stem}.{extension}")));
+        let renderer = cx.svg_renderer();
+        let error_message = format!("Failed to export {}", extension.to_ascii_uppercase());
+        cx.spawn_in(window, async move |_, cx| {
+            let Some(path) = path.await?? else {
+                return Ok(());
+            };
+            let contents = cx
+                .background_spawn(async move { render(&renderer) })
+                .await?;
+            write_export(fs, path, contents, cx.background_executor()).await
+        })
+        .detach_and_prompt_err(&error_message, window, cx, |_, _, _| None);
+    }
+
+    fn export_png_set(&mut self, _: &ExportPngSet, window: &mut Window, cx: &mut Context<Self>) {
+        let (Some(tree), Some(svg_size)) = (self.tree.clone(), self.svg_size) else {
+            return;
+        };
+        let scales = SvgPreviewSettings::get_global(cx).export_scales.clone();
+        if scales.is_empty() {
+            return;
+        }
+        let (_, stem, fs) = self.export_target(cx);
+        let directory = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some("Export".into()),
+        });
+        let renderer = cx.svg_renderer();
+        cx.spawn_in(window, async move |_, cx| {
+            let Some(directory) = directory.await??.and_then(|paths| paths.into_iter().next())
+            else {
+                return Ok(());
+            };
+            for scale in scales.into_iter().filter(|scale| *scale > 0.) {
+                let export_size = scaled_export_size(svg_size, scale);
+                let png = cx
+                    .background_spawn({
+                        let renderer = renderer.clone();
+                        let tree = tree.clone();
+                        async move { renderer.render_png(&tree, export_size, None) }
+                    })
+                    .await?;
+                let path = directory.join(scaled_file_name(&stem, scale));
+                write_export(fs.clone(), path, png, cx.background_executor()).await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to export PNGs", window, cx, |_, _, _| None);
+    }
+
+    fn cancel_export_input(
+        &mut self,
+        _: &menu::Cancel,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.export.size_input.take().is_some() {
+            self.focus_handle.focus(window);
+            cx.notify();
+        }
+    }
+
+    fn render_export_input(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let export_input = self.export.size_input.as_ref()?;
+        let colors = cx.theme().colors();
+        Some(
+            h_flex()
+                .absolute()
+                .top_2()
+                .left_0()
+                .right_0()
+                .justify_center()
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .p_1()
+                        .rounded_sm()
+                        .border_1()
+                        .border_color(colors.border)
+                        .bg(colors.elevated_surface_background)
+                        .shadow_md()
+                        .on_mouse_down(MouseButton::Left, |_, window, _| {
+                            // Keeps the size input focused, since the input closes when blurred.
+                            window.prevent_default()
+                        })
+                        .child(Label::new("Export").size(LabelSize::Small))
+                        .child(
+                            h_flex().gap_0p5().children(
+                                [
+                                    (SvgPreviewExportFormat::Png, "PNG"),
+                                    (SvgPreviewExportFormat::Jpeg, "JPEG"),
+                                    (SvgPreviewExportFormat::Webp, "WebP"),
+                                ]
+                                .into_iter()
+                                .map(|(format, label)| {
+                                    Button::new(label, label)
+                                        .label_size(LabelSize::Small)
+                                        .toggle_state(self.export.format == format)
+                                        .on_click(cx.listener(move |this, _, _, cx| {
+                                            this.export.format = format;
+                                            cx.notify();
+                                        }))
+                                }),
+                            ),
+                        )
+                        .child(
+                            h_flex().gap_0p5().children(
+                                [
+                                    (ExportBackground::Transparent, "None"),
+                                    (ExportBackground::White, "White"),
+                                    (ExportBackground::Black, "Black"),
+                                    (ExportBackground::Preview, "Preview"),
+                                ]
+                                .into_iter()
+                                .map(|(background, label)| {
+                                    Button::new(label, label)
+                                        .label_size(LabelSize::Small)
+                                        .toggle_state(self.export.background == background)
+                                        .tooltip(Tooltip::text("Export Background"))
+                                        .on_click(cx.listener(move |this, _, _, cx| {
+                                            this.export.background = background;
+                                            cx.notify();
+                                        }))
+                                }),
+                            ),
+                        )
+                        .child(
+                            h_flex()
+                                .w(px(200.))
+                                .px_1()
+                                .rounded_sm()
+                                .border_1()
+                                .border_color(colors.border_focused)
+                                .bg(colors.editor_background)
+                                .on_action(cx.listener(Self::confirm_export_input))
+                                .on_action(cx.listener(Self::cancel_export_input))
+                                .child(export_input.editor.clone()),
+                        )
+                        .when(self.export.format == SvgPreviewExportFormat::Jpeg, |this| {
+                            this.child(self.render_quality_slider(cx))
+                        }),
+                ),
+        )
+    }
+
+    fn render_quality_slider(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let progress = (self.export.quality - 1) as f32 / 99.;
+        let colors = cx.theme().colors();
+        h_flex()
+            .gap_1()
+            .child(
+                Label::new("Quality")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                div()
+                    .id("export-quality-slider")
+                    .relative()
+                    .w(QUALITY_SLIDER_WIDTH)
+                    .h_4()
+                    .cursor_pointer()
+                    .child({
+                        let this = cx.entity();
+                        canvas(
+                            move |bounds, _window, cx| {
+                                this.update(cx, |this, _| {
+                                    this.export.quality_slider_bounds = bounds
+                                })
+                            },
+                            |_, _, _, _| {},
+                        )
+                        .absolute()
+                        .size_full()
+                    })
+                    .child(
+                        div()
+                            .absolute()
+                            .top(px(6.))
+                            .h_1()
+                            .w_full()
+                            .rounded_sm()
+                            .bg(colors.border),
+                    )
+                    .child(
+                        div()
+                            .absolute()
+                            .top(px(6.))
+                            .h_1()
+                            .w(QUALITY_SLIDER_WIDTH * progress)
+                            .rounded_sm()
+                            .bg(colors.border_focused),
+                    )
+                    .child(
+                        div()
+                            .absolute()
+                            .top_0()
+                            .left(QUALITY_SLIDER_WIDTH * progress - px(2.))
+                            .w_1()
+                            .h_4()
+                            .rounded_sm()
+                            .bg(colors.text),
+                    )
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, event: &MouseDownEvent, _, cx| {
+                            this.export.dragging_quality_slider = true;
+                            this.set_export_quality(event.position.x, cx);
+                        }),
+                    ),
+            )
+            .child(
+                Label::new(self.export.quality.to_string())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+    }
+
+    fn set_export_quality(&mut self, position_x: Pixels, cx: &mut Context<Self>) {
+        let bounds = self.export.quality_slider_bounds;
+        if bounds.size.width <= px(0.) {
+            return;
+        }
+        let progress = ((position_x - bounds.origin.x) / bounds.size.width).clamp(0., 1.);
+        self.export.quality = (1. + progress * 99.).round() as u8;
+        cx.notify();
+    }
+
+    /// Returns the number and the text of the given line of the SVG, numbered from 1.
+    fn error_line(&self, row: u32, cx: &App) -> Option<(u32, SharedString)> {
+        let text = self.source_text(cx)?;
+        let line = text.lines().nth(row.checked_sub(1)? as usize)?;
+        Some((row, line.trim().to_string().into()))
+    }
+
+    /// Returns the range of the buffer that the preview shows, which is the whole buffer unless
+    /// the SVG is embedded in another language.
+    fn source_range(&self, cx: &App) -> Option<Range<usize>> {
+        let buffer = self.buffer.as_ref()?.read(cx);
+        Some(match &self.embedded_range {
+            Some(range) => range.to_offset(buffer),
+            None => 0..buffer.len(),
+        })
+    }
+
+    /// Returns the text of the SVG the preview shows.
+    fn source_text(&self, cx: &App) -> Option<String> {
+        let buffer = self.buffer.as_ref()?.read(cx);
+        Some(match &self.embedded_range {
+            Some(range) => buffer.text_for_range(range.clone()).collect(),
+            None => buffer.text(),
+        })
+    }
+
+    /// Shows the `<svg>` element at `range` instead of the whole buffer, for SVGs embedded in
+    /// other languages.
+    fn set_embedded_range(
+        &mut self,
+        range: Range<Anchor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.embedded_range = Some(range);
+        self.watch_diagnostics(cx);
+        self.cursor_offset = None;
+        self.cursor_highlight = None;
+        self.render_image(window, cx);
+        cx.notify();
+    }
+
+    /// Returns the directory containing the SVG's file, which is on the remote host for remote
+    /// projects.
+    fn buffer_dir(&self, cx: &App) -> Option<PathBuf> {
+        let project_path = self.project_path(cx)?;
+        let abs_path = self
+            .project
+            .as_ref()?
+            .read(cx)
+            .absolute_path(&project_path, cx)?;
+        Some(abs_path.parent()?.to_path_buf())
+    }
+
+    fn buffer_abs_path(&self, cx: &App) -> Option<PathBuf> {
+        let file = self.buffer.as_ref()?.read(cx).file()?;
+        Some(file.as_local()?.abs_path(cx))
+    }
+
+    /// Returns the directory that exports of the SVG go to by default, the stem of their file
+    /// names, and the file system of the project to write them with. Exports of SVGs that aren't
+    /// saved go to the home directory and are named after "image".
+    fn export_target(&self, cx: &App) -> (PathBuf, String, Option<Arc<dyn Fs>>) {
+        let svg_path = self.buffer_abs_path(cx);
+        let directory = svg_path
+            .as_ref()
+            .and_then(|path| path.parent())
+            .map(|dir| dir.to_path_buf())
+            .unwrap_or_else(|| util::paths::home_dir().clone());
+        let stem = svg_path
+            .as_ref()
+            .and_then(|path| path.file_stem())
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "image".to_string());
+        let fs = self
+            .project
+            .as_ref()
+            .map(|project| project.read(cx).fs().clone());
+        (directory, stem, fs)
+    }
+
+    fn cancel_zoom_input(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.zoom_input.take().is_some() {
+            self.focus_handle.focus(window);
+            self.notify_controls(cx);
+        }
+    }
+
+    /// Renders the position of the pointer over the SVG, in its user units.
+    fn render_pointer_position(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let position = self.pointer_position?;
+        let colors = cx.theme().colors();
+        Some(
+            div()
+                .absolute()
+                .bottom_2()
+                .right_2()
+                .px_1()
+                .rounded_sm()
+                .border_1()
+                .border_color(colors.border)
+                .bg(colors.elevated_surface_background)
+                .child(
+                    Label::new(format!(
+                        "{}, {}",
+                        format_units(position.x),
+                        format_units(position.y)
+                    ))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .buffer_font(cx),
+                ),
+        )
+    }
+
+    /// Renders the toolbar's toggles of the preview's tools and overlays, and its controls for
+    /// the media features and `currentColor` the SVG is rendered with.
+    fn render_tool_toggles(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        h_flex()
+            .gap_0p5()
+            .child(
+                IconButton::new("toggle-eyedropper", IconName::SwatchBook)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(self.eyedropper.active)
+                    .tooltip(Tooltip::for_action_title_in(
+                        "Pick Color",
+                        &ToggleEyedropper,
+                        &self.focus_handle,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.toggle_eyedropper(&ToggleEyedropper, window, cx)
+                    })),
+            )
+            .child(
+                IconButton::new("toggle-measure", IconName::Crosshair)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(self.measure.active)
+                    .tooltip(Tooltip::for_action_title_in(
+                        "Measure",
+                        &ToggleMeasure,
+                        &self.focus_handle,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.toggle_measure(&ToggleMeasure, window, cx)
+                    })),
+            )
+            .child(
+                IconButton::new("toggle-bounding-boxes", IconName::SquareDot)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(self.show_bounding_boxes)
+                    .tooltip(Tooltip::for_action_title_in(
+                        "Bounding Boxes",
+                        &ToggleBoundingBoxes,
+                        &self.focus_handle,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.toggle_bounding_boxes(&ToggleBoundingBoxes, window, cx)
+                    })),
+            )
+            .child(
+                IconButton::new("toggle-outline", IconName::ListTree)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(self.show_outline)
+                    .tooltip(Tooltip::for_action_title_in(
+                        "Outline",
+                        &ToggleOutline,
+                        &self.focus_handle,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.toggle_outline(&ToggleOutline, window, cx)
+                    })),
+            )
+            .child(
+                IconButton::new("toggle-custom-properties", IconName::Sliders)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(self.show_custom_properties)
+                    .tooltip(Tooltip::for_action_title_in(
+                        "Custom Properties",
+                        &ToggleCustomProperties,
+                        &self.focus_handle,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.toggle_custom_properties(&ToggleCustomProperties, window, cx)
+                    })),
+            )
+            .when(!self.sprite_ids.is_empty(), |this| {
+                this.child(
+                    IconButton::new("toggle-gallery", IconName::Blocks)
+                        .icon_size(IconSize::Small)
+                        .toggle_state(self.gallery.shown)
+                        .tooltip(Tooltip::for_action_title_in(
+                            "Symbol Gallery",
+                            &ToggleGallery,
+                            &self.focus_handle,
+                        ))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.toggle_gallery(&ToggleGallery, window, cx)
+                        })),
+                )
+            })
+            .child(
+                Button::new(
+                    "toggle-color-scheme",
+                    match self.color_scheme(cx) {
+                        ColorScheme::Light => "Light",
+                        ColorScheme::Dark => "Dark",
+                    },
+                )
+                .label_size(LabelSize::Small)
+                .tooltip(Tooltip::for_action_title_in(
+                    "Toggle prefers-color-scheme",
+                    &ToggleColorScheme,
+                    &self.focus_handle,
+                ))
+                .on_click(cx.listener(|this, _, window, cx| {
+                    this.toggle_color_scheme(&ToggleColorScheme, window, cx)
+                })),
+            )
+            .child(self.render_current_color_menu(cx))
+            .map(|this| match &self.viewport_input {
+                Some(viewport_input) => this.child(
+                    h_flex()
+                        .w(px(96.))
+                        .px_1()
+                        .rounded_sm()
+                        .border_1()
+                        .border_color(colors.border_focused)
+                        .bg(colors.editor_background)
+                        .on_action(cx.listener(Self::confirm_viewport_input))
+                        .on_action(cx.listener(Self::cancel_viewport_input))
+                        .child(viewport_input.editor.clone()),
+                ),
+                None => this.child(
+                    Button::new(
+                        "simulated-viewport",
+                        match self.simulated_viewport {
+                            Some(viewport) => format!("{}×{}", viewport.width, viewport.height),
+                            None => "Viewport".to_string(),
+                        },
+                    )
+                    .label_size(LabelSize::Small)
+                    .toggle_state(self.simulated_viewport.is_some())
+                    .tooltip(Tooltip::text("Simulate Viewport Size"))
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.show_viewport_input(window, cx)),
+                    ),
+                ),
+            })
+    }
+
+    /// Renders the controls of the preview's toolbar.
+    pub(crate) fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let pinned = self.mode == SvgPreviewMode::Default;
+        h_flex()
+            .gap_1()
+            .child(
+                IconButton::new("toggle-pinned", IconName::Pin)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(pinned)
+                    .tooltip(Tooltip::for_action_title_in(
+                        if pinned {
+                            "Follow Active SVG Editor"
+                        } else {
+                            "Pin to This SVG"
+                        },
+                        &TogglePinned,
+                        &self.focus_handle,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.toggle_pinned(&TogglePinned, window, cx)
+                    })),
+            )
+            .when(self.svg_size.is_some(), |this| {
+                this.child(
+                    h_flex()
+                        .gap_0p5()
+                        .child(
+                            IconButton::new("zoom-out", IconName::Dash)
+                                .icon_size(IconSize::Small)
+                                .tooltip(Tooltip::for_action_title_in(
+                                    "Zoom Out",
+                                    &ZoomOut,
+                                    &self.focus_handle,
+                                ))
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.zoom_out(&ZoomOut, window, cx)
+                                })),
+                        )
+                        .map(|this| match &self.zoom_input {
+                            Some(zoom_input) => this.child(
+                                h_flex()
+                                    .w(px(64.))
+                                    .px_1()
+                                    .rounded_sm()
+                                    .border_1()
+                                    .border_color(colors.border_focused)
+                                    .bg(colors.editor_background)
+                                    .on_action(cx.listener(Self::confirm_zoom_input))
+                                    .on_action(cx.listener(Self::cancel_zoom_input))
+                                    .child(zoom_input.editor.clone()),
+                            ),
+                            None => this.child(
+                                Button::new(
+                                    "zoom-level",
+                                    format!("{}%", zoom_percentage(self.scale_factor)),
+                                )
+                                .label_size(LabelSize::Small)
+                                .tooltip(Tooltip::text("Set Zoom Level"))
+                                .on_click(cx.listener(
+                                    |this, _, window, cx| this.show_zoom_input(window, cx),
+                                )),
+                            ),
+                        })
+                        .child(self.render_zoom_presets_menu(cx))
+                        .child(
+                            IconButton::new("zoom-in", IconName::Plus)
+                                .icon_size(IconSize::Small)
+                                .tooltip(Tooltip::for_action_title_in(
+                                    "Zoom In",
+                                    &ZoomIn,
+                                    &self.focus_handle,
+                                ))
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.zoom_in(&ZoomIn, window, cx)
+                                })),
+                        ),
+                )
+                .child(self.render_tool_toggles(cx))
+                .child(self.render_background_menu(cx))
+                .child(self.render_export_menu(cx))
+            })
+    }
+
+    fn render_background_menu(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let view = cx.entity().downgrade();
+        let focus_handle = self.focus_handle.clone();
+        let selected = self
+            .background_override
+            .clone()
+            .unwrap_or_else(|| SvgPreviewSettings::get_global(cx).background.clone());
+        let colors = cx.theme().colors();
+
+        PopoverMenu::new("background")
+            .trigger_with_tooltip(
+                ButtonLike::new("background-trigger").child(
+                    div()
+                        .size_3()
+                        .rounded_sm()
+                        .border_1()
+                        .border_color(colors.border)
+                        .bg(self.background_color(cx)),
+                ),
+                Tooltip::for_action_title_in("Background", &ToggleBackground, &self.focus_handle),
+            )
+            .anchor(Corner::TopRight)
+            .menu(move |window, cx| {
+                let view = view.clone();
+                let focus_handle = focus_handle.clone();
+                let selected = selected.clone();
+                let custom = match SvgPreviewSettings::get_global(cx).background.clone() {
+                    SvgPreviewBackground::Custom(color) => {
+                        Some(SvgPreviewBackground::Custom(color))
+                    }
+                    _ => None,
+                };
+                Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                    menu = menu.context(focus_handle).header("Background");
+                    let options = [
+                        ("Theme Background", SvgPreviewBackground::Theme),
+                        ("White", SvgPreviewBackground::White),
+                        ("Black", SvgPreviewBackground::Black),
+                    ]
+                    .into_iter()
+                    .chain(custom.map(|custom| ("Custom", custom)));
+                    for (label, background) in options {
+                        let view = view.clone();
+                        menu = menu.toggleable_entry(
+                            label,
+                            selected == background,
+                            IconPosition::Start,
+                            None,
+                            move |_window, cx| {
+                                view.update(cx, |view, cx| {
+                                    view.background_override = Some(background.clone());
+                                    view.notify_controls(cx);
+                                })
+                                .ok();
+                            },
+                        );
+                    }
+                    menu
+                }))
+            })
+    }
+
+    fn render_export_menu(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle.clone();
+        let preset_names = self.export_preset_names(cx);
+
+        PopoverMenu::new("export")
+            .trigger_with_tooltip(
+                IconButton::new("export-trigger", IconName::Download).icon_size(IconSize::Small),
+                Tooltip::text("Export"),
+            )
+            .anchor(Corner::TopRight)
+            .menu(move |window, cx| {
+                let focus_handle = focus_handle.clone();
+                let preset_names = preset_names.clone();
+                Some(ContextMenu::build(window, cx, move |menu, _, _| {
+                    export_menu_entries(menu.context(focus_handle), None, preset_names)
+                }))
+            })
+    }
+
+    fn export_preset_names(&self, cx: &App) -> Vec<String> {
+        SvgPreviewSettings::get_global(cx)
+            .export_presets
+            .iter()
+            .map(|preset| preset.name.clone())
+            .collect()
+    }
+
+    fn render_zoom_presets_menu(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        PopoverMenu::new("zoom-presets")
+            .trigger_with_tooltip(
+                IconButton::new("zoom-presets-trigger", IconName::ChevronDown)
+                    .icon_size(IconSize::Small),
+                Tooltip::text("Zoom Presets"),
+            )
+            .anchor(Corner::TopRight)
+            .menu(self.zoom_presets_menu(cx))
+    }
+
+    /// Renders the zoom level and the intrinsic size of the SVG for the status bar, opening the
+    /// zoom presets when clicked.
+    pub(crate) fn render_zoom_status(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let svg_size = self.svg_size?;
+        let label = format!(
+            "{}% • {}×{}",
+            zoom_percentage(self.scale_factor),
+            format_units(svg_size.width),
+            format_units(svg_size.height)
+        );
+        Some(
+            PopoverMenu::new("zoom-status")
+                .trigger_with_tooltip(
+                    Button::new("zoom-status-trigger", label).label_size(LabelSize::Small),
+                    Tooltip::text("Zoom Presets"),
+                )
+                .anchor(Corner::BottomRight)
+                .menu(self.zoom_presets_menu(cx)),
+        )
+    }
+
+    fn zoom_presets_menu(
+        &self,
+        cx: &mut Context<Self>,
+    ) -> impl Fn(&mut Window, &mut App) -> Option<Entity<ContextMenu>> + 'static {
+        let view = cx.entity().downgrade();
+        let focus_handle = self.focus_handle.clone();
+        let scale_factor = self.scale_factor;
+        let fit_to_window = self.fit_to_window;
+
+        move |window, cx| {
+            let view = view.clone();
+            let focus_handle = focus_handle.clone();
+            Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                menu = menu.context(focus_handle);
+                for preset in ZOOM_PRESETS {
+                    let view = view.clone();
+                    menu = menu.toggleable_entry(
+                        format!("{}%", zoom_percentage(preset)),
+                        !fit_to_window && scale_factor == preset,
+                        IconPosition::Start,
+                        None,
+                        move |_window, cx| {
+                            view.update(cx, |view, cx| {
+                                let center = view.viewport_bounds.center();
+                                view.set_scale_factor(preset, center, cx);
+                            })
+                            .ok();
+                        },
+                    );
+                }
+                menu.separator().toggleable_entry(
+                    "Fit to Window",
+                    fit_to_window,
+                    IconPosition::Start,
+                    Some(Box::new(FitToWindow)),
+                    move |window, cx| {
+                        view.update(cx, |view, cx| view.fit_to_window(&FitToWindow, window, cx))
+                            .ok();
+                    },
+                )
+            }))
+        }
+    }
+
+    fn render_rulers(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let user_space = self.user_space?;
+        let image_origin = self.image_bounds()?.origin;
+        let viewport = self.viewport_bounds;
+        let colors = cx.theme().colors();
+
+        // The user unit coordinates at the top left corner of the viewport.
+        let start = user_space.to_user(point(
+            f32::from(viewport.origin.x - image_origin.x) / self.scale_factor,
+            f32::from(viewport.origin.y - image_origin.y) / self.scale_factor,
+        ));
+        let pixels_per_unit = user_space.scale().map(|scale| scale * self.scale_factor);
+
+        let (horizontal_ticks, horizontal_step) =
+            rulers::ticks(start.x, pixels_per_unit.x, viewport.size.width);
+        let (vertical_ticks, vertical_step) =
+            rulers::ticks(start.y, pixels_per_unit.y, viewport.size.height);
+
+        let tick_label = |value: f32, step: f32| {
+            Label::new(rulers::format_tick_label(value, step))
+                .size(LabelSize::XSmall)
+                .color(Color::Muted)
+        };
+        let ruler = || {
+            div()
+                .absolute()
+                .bg(colors.panel_background)
+                .border_color(colors.border)
+        };
+
+        let horizontal = ruler()
+            .top_0()
+            .left_0()
+            .w_full()
+            .h(RULER_SIZE)
+            .border_b_1()
+            .children(horizontal_ticks.into_iter().map(|tick| {
+                div()
+                    .absolute()
+                    .left(tick.offset)
+                    .bottom_0()
+                    .w(px(1.))
+                    .h(RULER_SIZE / if tick.label.is_some() { 2. } else { 4. })
+                    .bg(colors.border)
+                    .when_some(tick.label, |this, value| {
+                        this.child(
+                            div()
+                                .absolute()
+                                .left(px(2.))
+                                .bottom(RULER_SIZE / 2. - px(4.))
+                                .child(tick_label(value, horizontal_step)),
+                        )
+                    })
+            }));
+
+        let vertical = ruler()
+            .top_0()
+            .left_0()
+            .h_full()
+            .w(RULER_SIZE)
+            .border_r_1()
+            .children(vertical_ticks.into_iter().map(|tick| {
+                div()
+                    .absolute()
+                    .top(tick.offset)
+                    .right_0()
+                    .h(px(1.))
+                    .w(RULER_SIZE / if tick.label.is_some() { 2. } else { 4. })
+                    .bg(colors.border)
+                    .when_some(tick.label, |this, value| {
+                        this.child(
+                            div()
+                                .absolute()
+                                .top(px(1.))
+                                .right(px(0.))
+                                .child(tick_label(value, vertical_step)),
+                        )
+                    })
+            }));
+
+        let corner = ruler()
+            .top_0()
+            .left_0()
+            .size(RULER_SIZE)
+            .border_b_1()
+            .border_r_1();
+
+        Some(
+            div()
+                .absolute()
+                .top_0()
+                .left_0()
+                .size_full()
+                .child(horizontal)
+                .child(vertical)
+                .child(corner),
+        )
+    }
+
+    /// Maps `position` in the window to user units, whether or not it's on the image.
+    fn user_position(&self, position: Point<Pixels>) -> Option<Point<f32>> {
+        let user_space = self.user_space?;
+        let image_origin = self.image_bounds()?.origin;
+        Some(user_space.to_user(point(
+            f32::from(position.x - image_origin.x) / self.scale_factor,
+            f32::from(position.y - image_origin.y) / self.scale_factor,
+        )))
+    }
+
+    /// The distance of `guide` from the left or top edge of the viewport.
+    fn guide_offset(&self, guide: &Guide) -> Option<Pixels> {
+        let user_space = self.user_space?;
+        let image_origin = self.image_bounds()?.origin - self.viewport_bounds.origin;
+        let position = user_space.from_user(point(guide.position, guide.position));
+        Some(match guide.axis {
+            Axis::Horizontal => image_origin.x + px(position.x * self.scale_factor),
+            Axis::Vertical => image_origin.y + px(position.y * self.scale_factor),
+        })
+    }
+
+    /// The user unit coordinates of the edges of the elements along `axis`.
+    fn guide_edges(&self, axis: Axis) -> Vec<f32> {
+        match (self.tree.as_ref(), self.user_space) {
+            (Some(tree), Some(user_space)) => {
+                guides::edges(&bounding_boxes(tree, u32::MAX), axis, &user_space)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Starts dragging a new guide out of the ruler at `position` in the window, or the guide
+    /// at `position`. Returns whether there was a guide to drag.
+    fn start_dragging_guide(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) -> bool {
+        let relative = position - self.viewport_bounds.origin;
+        let new_guide_axis = if relative.y < RULER_SIZE && relative.x >= RULER_SIZE {
+            Some(Axis::Vertical)
+        } else if relative.x < RULER_SIZE && relative.y >= RULER_SIZE {
+            Some(Axis::Horizontal)
+        } else {
+            None
+        };
+        if let Some(axis) = new_guide_axis {
+            let Some(user_position) = self.user_position(position) else {
+                return false;
+            };
+            self.guides.push(Guide {
+                axis,
+                position: match axis {
+                    Axis::Horizontal => user_position.x,
+                    Axis::Vertical => user_position.y,
+                },
+            });
+            self.dragging_guide = Some(self.guides.len() - 1);
+            cx.notify();
+            return true;
+        }
+
+        self.dragging_guide = self.guides.iter().position(|guide| {
+            self.guide_offset(guide).is_some_and(|offset| {
+                let coordinate = match guide.axis {
+                    Axis::Horizontal => relative.x,
+                    Axis::Vertical => relative.y,
+                };
+                (coordinate - offset).abs() <= GUIDE_GRAB_DISTANCE
+            })
+        });
+        self.dragging_guide.is_some()
+    }
+
+    /// Moves the dragged guide to `position` in the window, snapping it to the edges of the
+    /// elements or to whole user units.
+    fn drag_guide(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        let (Some(index), Some(user_space), Some(user_position)) = (
+            self.dragging_guide,
+            self.user_space,
+            self.user_position(position),
+        ) else {
+            return;
+        };
+        let Some(axis) = self.guides.get(index).map(|guide| guide.axis) else {
+            return;
+        };
+        let (position, units_per_pixel) = match axis {
+            Axis::Horizontal => (
+                user_position.x,
+                1. / (user_space.scale().x * self.scale_factor),
+            ),
+            Axis::Vertical => (
+                user_position.y,
+                1. / (user_space.scale().y * self.scale_factor),
+            ),
+        };
+        let edges = self.guide_edges(axis);
+        self.guides[index].position =
+            guides::snap(position, &edges, GUIDE_SNAP_DISTANCE * units_per_pixel);
+        cx.notify();
+    }
+
+    /// Stops dragging the dragged guide, removing it if it was dropped back onto its ruler.
+    fn drop_guide(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        let Some(index) = self.dragging_guide.take() else {
+            return;
+        };
+        let relative = position - self.viewport_bounds.origin;
+        let on_ruler = match self.guides.get(index).map(|guide| guide.axis) {
+            Some(Axis::Horizontal) => relative.x < RULER_SIZE,
+            Some(Axis::Vertical) => relative.y < RULER_SIZE,
+            None => false,
+        };
+        if on_ruler {
+            self.guides.remove(index);
+            cx.notify();
+        }
+    }
+
+    /// Draws the guides, labeled with their positions and distances to the nearest edges of the
+    /// elements.
+    fn render_guides(&self, cx: &mut Context<Self>) -> Vec<impl IntoElement> {
+        if self.guides.is_empty() {
+            return Vec::new();
+        }
+        let edges = [Axis::Horizontal, Axis::Vertical].map(|axis| self.guide_edges(axis));
+        let colors = cx.theme().colors();
+        self.guides
+            .iter()
+            .filter_map(|guide| {
+                let offset = self.guide_offset(guide)?;
+                let edges = match guide.axis {
+                    Axis::Horizontal => &edges[0],
+                    Axis::Vertical => &edges[1],
+                };
+                let label = div()
+                    .absolute()
+                    .px_1()
+                    .rounded_sm()
+                    .bg(colors.elevated_surface_background)
+                    .whitespace_nowrap()
+                    .child(
+                        Label::new(guide.label(edges))
+                            .size(LabelSize::XSmall)
+                            .color(Color::Accent)
+                            .buffer_font(cx),
+                    );
+                let line = div().absolute().bg(colors.text_accent);
+                Some(match guide.axis {
+                    Axis::Horizontal => line
+                        .top_0()
+                        .left(offset)
+                        .h_full()
+                        .w(px(1.))
+                        .child(label.top(RULER_SIZE + px(4.)).left(px(4.))),
+                    Axis::Vertical => line
+                        .left_0()
+                        .top(offset)
+                        .w_full()
+                        .h(px(1.))
+                        .child(label.left(RULER_SIZE + px(4.)).bottom(px(4.))),
+                })
+            })
+            .collect()
+    }
+
+    fn handle_pinch(&mut self, event: &PinchEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        if event.delta == 0. {
+            return;
+        }
+        self.set_scale_factor(self.scale_factor * (1. + event.delta), event.position, cx);
+    }
+
+    fn handle_mouse_down(
+        &mut self,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .minimap_bounds()
+            .is_some_and(|(bounds, _)| bounds.contains(&event.position))
+        {
+            self.dragging_minimap = true;
+            self.center_on_minimap_position(event.position, cx);
+            return;
+        }
+        if self.show_rulers && self.start_dragging_guide(event.position, cx) {
+            return;
+        }
+        if self.eyedropper.active
+            && let Some((x, y)) = self.svg_position(event.position)
+        {
+            self.pick_color(point(x, y), window, cx);
+            return;
+        }
+        if self.measure.active
+            && let Some((x, y)) = self.svg_position(event.position)
+        {
+            let start = point(x, y);
+            self.measure.measurement = Some(Measurement { start, end: start });
+            self.measure.dragging = true;
+            cx.notify();
+            return;
+        }
+        if event.modifiers.secondary()
+            && let Some(svg_position) = self.svg_position(event.position)
+        {
+            self.jump_to_source(svg_position, window, cx);
+            return;
+        }
+        if event.click_count == 2
+            && !self
+                .image_bounds()
+                .is_some_and(|bounds| bounds.contains(&event.position))
+        {
+            self.reset_view(&ResetView, window, cx);
+            return;
+        }
+        self.drag_start = Some(event.position);
+    }
+
+    fn handle_mouse_move(
+        &mut self,
+        event: &MouseMoveEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_pointer_position(event.position, cx);
+        if self.export.dragging_quality_slider {
+            if event.dragging() {
+                self.set_export_quality(event.position.x, cx);
+            } else {
+                self.export.dragging_quality_slider = false;
+            }
+            return;
+        }
+        if self.comparison.dragging_swipe_divider {
+            if event.dragging() {
+                self.set_swipe_position(event.position.x, cx);
+            } else {
+                self.comparison.dragging_swipe_divider = false;
+            }
+            return;
+        }
+        if self.dragging_scrubber {
+            if event.dragging() {
+                self.seek_animation(event.position.x, window, cx);
+            } else {
+                self.dragging_scrubber = false;
+            }
+            return;
+        }
+        if self.dragging_minimap {
+            if event.dragging() {
+                self.center_on_minimap_position(event.position, cx);
+            } else {
+                self.dragging_minimap = false;
+            }
+            return;
+        }
+        if self.dragging_guide.is_some() {
+            if event.dragging() {
+                self.drag_guide(event.position, cx);
+            } else {
+                self.dragging_guide = None;
+            }
+            return;
+        }
+        if self.measure.dragging {
+            if event.dragging() {
+                self.update_measurement(event.position, cx);
+            } else {
+                self.measure.dragging = false;
+            }
+            return;
+        }
+        let Some(drag_start) = self.drag_start else {
+            self.update_hovered_element(event.position, window, cx);
+            return;
+        };
+        if !event.dragging() {
+            self.drag_start = None;
+            return;
+        }
+        self.hovered_element = None;
+        self.drag_start = Some(event.position);
+        self.pan_by(
+            point(
+                event.position.x - drag_start.x,
+                event.position.y - drag_start.y,
+            ),
+            cx,
+        );
+    }
+
+    /// The bounds of the minimap in window coordinates along with its scale relative to the
+    /// intrinsic size of the SVG, if the image doesn't fit into the viewport.
+    fn minimap_bounds(&self) -> Option<(Bounds<Pixels>, f32)> {
+        let svg_size = self.svg_size?;
+        let image_bounds = self.image_bounds()?;
+        let viewport = self.viewport_bounds;
+        if svg_size.width <= 0.
+            || svg_size.height <= 0.
+            // Allow for rounding errors when the image was fitted into the viewport.
+            || image_bounds.is_contained_within(&viewport.dilate(px(1.)))
+        {
+            return None;
+        }
+
+        let scale = (f32::from(MINIMAP_SIZE) / svg_size.width)
+            .min(f32::from(MINIMAP_SIZE) / svg_size.height);
+        let minimap_size = size(px(svg_size.width * scale), px(svg_size.height * scale));
+        let top = if self.show_rulers {
+            RULER_SIZE + MINIMAP_MARGIN
+        } else {
+            MINIMAP_MARGIN
+        };
+        let origin = point(
+            viewport.right() - MINIMAP_MARGIN - minimap_size.width,
+            viewport.top() + top,
+        );
+        Some((Bounds::new(origin, minimap_size), scale))
+    }
+
+    /// Pans the image so that the point under `position` in the minimap is centered in the
+    /// viewport.
+    fn center_on_minimap_position(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        let Some((minimap_bounds, minimap_scale)) = self.minimap_bounds() else {
+            return;
+        };
+        let target = point(
+            f32::from(position.x - minimap_bounds.origin.x) / minimap_scale,
+            f32::from(position.y - minimap_bounds.origin.y) / minimap_scale,
+        );
+        self.center_on(target, cx);
+    }
+
+    /// Pans the image so that `target`, in SVG pixels, is centered in the viewport.
+    fn center_on(&mut self, target: Point<f32>, cx: &mut Context<Self>) {
+        let Some(svg_size) = self.svg_size else {
+            return;
+        };
+        self.image_offset = point(
+            px((svg_size.width / 2. - target.x) * self.scale_factor),
+            px((svg_size.height / 2. - target.y) * self.scale_factor),
+        );
+        self.clamp_image_offset();
+        cx.emit(SvgPreviewEvent::ViewChanged);
+        cx.notify();
+    }
+
+    fn render_minimap(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let Some(Ok(image)) = self.current_svg.clone() else {
+            return None;
+        };
+        let (minimap_bounds, minimap_scale) = self.minimap_bounds()?;
+        let image_origin = self.image_bounds()?.origin;
+        let viewport = self.viewport_bounds;
+        let colors = cx.theme().colors();
+
+        // The viewport expressed in minimap coordinates.
+        let scale = minimap_scale / self.scale_factor;
+        let visible_origin = point(
+            (viewport.origin.x - image_origin.x) * scale,
+            (viewport.origin.y - image_origin.y) * scale,
+        );
+        let visible_size = viewport.size.map(|length| length * scale);
+
+        Some(
+            div()
+                .absolute()
+                .left(minimap_bounds.origin.x - viewport.origin.x)
+                .top(minimap_bounds.origin.y - viewport.origin.y)
+                .w(minimap_bounds.size.width)
+                .h(minimap_bounds.size.height)
+                .overflow_hidden()
+                .border_1()
+                .border_color(colors.border)
+                .bg(colors.editor_background)
+                .shadow_md()
+                .cursor_pointer()
+                .child(img(image).object_fit(ObjectFit::Fill).size_full())
+                .child(
+                    div()
+                        .absolute()
+                        .left(visible_origin.x)
+                        .top(visible_origin.y)
+                        .w(visible_size.width)
+                        .h(visible_size.height)
+                        .border_1()
+                        .border_color(colors.border_focused)
+                        .bg(colors.border_focused.opacity(0.1)),
+                ),
+        )
+    }
+
+    /// The bounds of the rendered image, in window coordinates.
+    fn image_bounds(&self) -> Option<Bounds<Pixels>> {
+        let svg_size = self.svg_size?;
+        let image_size = size(
+            px(svg_size.width * self.scale_factor),
+            px(svg_size.height * self.scale_factor),
+        );
+        let center = self.viewport_bounds.center();
+        Some(Bounds::new(
+            point(
+                center.x - image_size.width / 2. + self.image_offset.x,
+                center.y - image_size.height / 2. + self.image_offset.y,
+            ),
+            image_size,
+        ))
+    }
+
+    fn set_animations(
+        &mut self,
+        animations: Option<Arc<Animations>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.animation.animations = animations;
+        if self.animation.animations.is_none() {
+            self.animation.time = None;
+            self.animation.started_at = None;
+            self.animation._task = Task::ready(());
+            self.set_animation_frame(None, window, cx);
+        } else if self.animation.time.is_some() && self.animation.started_at.is_none() {
+            // Show the paused frame of the updated document.
+            self.render_animation_frame(window, cx);
+        }
+    }
+
+    fn play_animation(&mut self, _: &PlayAnimation, window: &mut Window, cx: &mut Context<Self>) {
+        if self.animation.animations.is_none() || self.animation.started_at.is_some() {
+            return;
+        }
+        let time = self.animation.time.unwrap_or(0.);
+        self.animation.started_at = Instant::now().checked_sub(Duration::from_secs_f32(time));
+        self.animation._task = cx.spawn_in(window, async move |this, cx| {
+            loop {
+                let frame_started_at = Instant::now();
+                let Ok(Some(frame)) =
+                    this.update_in(cx, |this, window, cx| this.animation_frame_task(window, cx))
+                else {
+                    break;
+                };
+                let frame = frame.await;
+                if this
+                    .update_in(cx, |this, window, cx| {
+                        this.set_animation_frame(frame, window, cx)
+                    })
+                    .is_err()
+                {
+                    break;
+                }
+                cx.background_executor()
+                    .timer(ANIMATION_FRAME_INTERVAL.saturating_sub(frame_started_at.elapsed()))
+                    .await;
+            }
+        });
+        cx.notify();
+    }
+
+    fn pause_animation(&mut self, _: &PauseAnimation, window: &mut Window, cx: &mut Context<Self>) {
+        if self.animation.started_at.is_none() {
+            return;
+        }
+        self.update_animation_time();
+        self.animation.started_at = None;
+        self.render_animation_frame(window, cx);
+        cx.notify();
+    }
+
+    fn restart_animation(
+        &mut self,
+        _: &RestartAnimation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.animation.animations.is_none() {
+            return;
+        }
+        self.animation.time = Some(0.);
+        if self.animation.started_at.is_some() {
+            self.animation.started_at = Some(Instant::now());
+        } else {
+            self.render_animation_frame(window, cx);
+        }
+        cx.notify();
+    }
+
+    /// Pauses the animations and shows the frame at the time under `position_x` on the scrubber.
+    fn seek_animation(&mut self, position_x: Pixels, window: &Window, cx: &mut Context<Self>) {
+        let Some(animations) = self.animation.animations.as_ref() else {
+            return;
+        };
+        let bounds = self.scrubber_bounds;
+        if bounds.size.width <= px(0.) {
+            return;
+        }
+        let progress = ((position_x - bounds.origin.x) / bounds.size.width).clamp(0., 1.);
+        self.animation.time = Some(progress * animations.duration());
+        self.animation.started_at = None;
+        self.render_animation_frame(window, cx);
+        cx.notify();
+    }
+
+    /// Advances the animation time according to the playback clock.
+    fn update_animation_time(&mut self) {
+        if let Some((animations, started_at)) = self
+            .animation
+            .animations
+            .as_ref()
+            .zip(self.animation.started_at)
+        {
+            self.animation.time = Some(started_at.elapsed().as_secs_f32() % animations.duration());
+        }
+    }
+
+    /// Returns a task rendering the animation frame at the current animation time.
+    fn animation_frame_task(
+        &mut self,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<Option<Arc<RenderImage>>>> {
+        self.update_animation_time();
+        let animations = self.animation.animations.clone()?;
+        let time = self.animation.time?;
+        let svg_size = self.svg_size?;
+        let length =
+            (svg_size.width.max(svg_size.height) * self.scale_factor * window.scale_factor())
+                .min(MAX_ANIMATION_FRAME_SIZE);
+        let renderer = cx.svg_renderer();
+        let external_resources = self.external_resources.clone();
+        let fonts = self.fonts.clone();
+        let simulated_viewport = self.simulated_viewport;
+        Some(cx.background_spawn(async move {
+            let tree = animation_snapshot_tree(
+                &animations,
+                time,
+                &renderer,
+                &external_resources,
+                fonts.as_deref(),
+                simulated_viewport,
+            )
+            .ok()?;
+            render_whole_svg(&renderer, &tree, svg_size, length).ok()
+        }))
+    }
+
+    fn render_animation_frame(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let Some(frame) = self.animation_frame_task(window, cx) else {
+            return;
+        };
+        self.animation._task = cx.spawn_in(window, async move |this, cx| {
+            let frame = frame.await;
+            this.update_in(cx, |this, window, cx| {
+                this.set_animation_frame(frame, window, cx)
+            })
+            .ok();
+        });
+    }
+
+    fn set_animation_frame(
+        &mut self,
+        frame: Option<Arc<RenderImage>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(previous_frame) = mem::replace(&mut self.animation.frame, frame) {
+            window.drop_image(previous_frame).ok();
+        }
+        cx.notify();
+    }
+
+    fn render_animation_controls(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_playing = self.animation.started_at.is_some();
+        let left = if self.show_rulers {
+            RULER_SIZE + px(8.)
+        } else {
+            px(8.)
+        };
+        h_flex()
+            .absolute()
+            .bottom_2()
+            .left(left)
+            .gap_0p5()
+            .child(if is_playing {
+                IconButton::new("pause-animation", IconName::DebugPause)
+                    .style(ButtonStyle::Filled)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::for_action_title_in(
+                        "Pause Animation",
+                        &PauseAnimation,
+                        &self.focus_handle,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.pause_animation(&PauseAnimation, window, cx)
+                    }))
+            } else {
+                IconButton::new("play-animation", IconName::PlayFilled)
+                    .style(ButtonStyle::Filled)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::for_action_title_in(
+                        "Play Animation",
+                        &PlayAnimation,
+                        &self.focus_handle,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.play_animation(&PlayAnimation, window, cx)
+                    }))
+            })
+            .child(
+                IconButton::new("restart-animation", IconName::RotateCcw)
+                    .style(ButtonStyle::Filled)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::for_action_title_in(
+                        "Restart Animation",
+                        &RestartAnimation,
+                        &self.focus_handle,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.restart_animation(&RestartAnimation, window, cx)
+                    })),
+            )
+            .child(self.render_scrubber(cx))
+    }
+
+    fn render_scrubber(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let duration = self
+            .animation
+            .animations
+            .as_ref()
+            .map_or(0., |animations| animations.duration());
+        let time = self.animation.time.unwrap_or(0.);
+        let progress = if duration > 0. {
+            (time / duration).clamp(0., 1.)
+        } else {
+            0.
+        };
+        let colors = cx.theme().colors();
+
+        h_flex()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .rounded_sm()
+            .bg(colors.elevated_surface_background)
+            .border_1()
+            .border_color(colors.border)
+            .child(
+                div()
+                    .id("animation-scrubber")
+                    .relative()
+                    .w(SCRUBBER_WIDTH)
+                    .h_4()
+                    .cursor_pointer()
+                    .child({
+                        let this = cx.entity();
+                        canvas(
+                            move |bounds, _window, cx| {
+                                this.update(cx, |this, _| this.scrubber_bounds = bounds)
+                            },
+                            |_, _, _, _| {},
+                        )
+                        .absolute()
+                        .size_full()
+                    })
+                    .child(
+                        div()
+                            .absolute()
+                            .top(px(6.))
+                            .h_1()
+                            .w_full()
+                            .rounded_sm()
+                            .bg(colors.border),
+                    )
+                    .child(
+                        div()
+                            .absolute()
+                            .top(px(6.))
+                            .h_1()
+                            .w(SCRUBBER_WIDTH * progress)
+                            .rounded_sm()
+                            .bg(colors.border_focused),
+                    )
+                    .child(
+                        div()
+                            .absolute()
+                            .top_0()
+                            .left(SCRUBBER_WIDTH * progress - px(2.))
+                            .w_1()
+                            .h_4()
+                            .rounded_sm()
+                            .bg(colors.text),
+                    )
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, event: &MouseDownEvent, window, cx| {
+                            cx.stop_propagation();
+                            this.dragging_scrubber = true;
+                            this.seek_animation(event.position.x, window, cx);
+                        }),
+                    ),
+            )
+            .child(
+                Label::new(format!("{time:.2}s / {duration:.2}s"))
+                    .size(LabelSize::XSmall)
+                    .color(Color::Muted),
+            )
+    }
+
+    /// Drops the rendered tiles, e.g. because the SVG can't be rendered.
+    fn clear_tiles(&mut self, window: &mut Window) {
+        for (_, tile) in self.tiles.drain() {
+            window.drop_image(tile).ok();
+        }
+        self.tile_tasks.clear();
+        self.tiles_key = None;
+    }
+
+    /// Moves the rendered tiles into the cache because the SVG or the zoom level changed, so that
+    /// they're reused when returning to it.
+    fn stash_tiles(&mut self, window: &mut Window) {
+        self.tile_tasks.clear();
+        let Some(key) = self.tiles_key.take() else {
+            self.clear_tiles(window);
+            return;
+        };
+        let tile_set = TileSet {
+            scale: self.tile_scale,
+            tiles: mem::take(&mut self.tiles),
+        };
+        for tile in self.tile_cache.insert(key, tile_set) {
+            window.drop_image(tile).ok();
+        }
+    }
+
+    /// Starts rendering the tiles that are visible at the current zoom level and drops the ones
+    /// that went out of view.
+    fn update_tiles(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let (Some(tree), Some(svg_size), Some(image_bounds)) =
+            (self.tree.clone(), self.svg_size, self.image_bounds())
+        else {
+            return;
+        };
+        let visible = self.viewport_bounds.intersect(&image_bounds);
+        let visible = Bounds::new(
+            point(
+                f32::from(visible.origin.x - image_bounds.origin.x) / self.scale_factor,
+                f32::from(visible.origin.y - image_bounds.origin.y) / self.scale_factor,
+            ),
+            visible
+                .size
+                .map(|length| f32::from(length) / self.scale_factor),
+        );
+        // Rasterize in device pixels so that the preview stays crisp on high-DPI displays, unless
+        // that would exceed the memory budget, in which case the tiles are stretched.
+        let display_scale = self.scale_factor * window.scale_factor();
+        let max_scale = self.max_tile_scale(visible, cx).max(MIN_TILE_SCALE);
+        let target_scale = display_scale.min(max_scale);
+        // The current tiles are scaled on the GPU for zoom levels that they're fine enough for
+        // and not too much finer than.
+        let covers_target = self
+            .tiles_key
+            .is_some_and(|key| key.content == self.content_hash)
+            && self.tile_scale >= target_scale
+            && self.tile_scale <= target_scale * MAX_TILE_DOWNSCALE
+            && self.tile_scale <= max_scale;
+        let mut tiles_changed = false;
+        if !covers_target {
+            // While zooming continuously, keep scaling the current tiles rather than rasterizing
+            // the SVG at every step.
+            if self.tiles_key.is_some() && self.is_zoom_unsettled(window, cx) {
+                return;
+            }
+            let scale = (display_scale * TILE_SUPERSAMPLING).min(max_scale);
+            let key = TileSetKey::new(self.content_hash, scale);
+            if self.tiles_key != Some(key) {
+                self.stash_tiles(window);
+                let tile_set = self.tile_cache.take(key).unwrap_or_else(|| TileSet {
+                    scale,
+                    tiles: HashMap::default(),
+                });
+                self.tile_scale = tile_set.scale;
+                self.tiles = tile_set.tiles;
+                self.tiles_key = Some(key);
+                tiles_changed = true;
+            }
+        }
+        // The missing tiles have to match the scale of the current ones, which may have been
+        // rendered for another zoom level or at a scale within the same bucket.
+        let scale = self.tile_scale;
+        let visible_tiles = tiles::visible_tiles(svg_size, visible, scale);
+
+        self.tiles.retain(|index, tile| {
+            let is_visible = visible_tiles.contains(index);
+            if !is_visible {
+                window.drop_image(tile.clone()).ok();
+                tiles_changed = true;
+            }
+            is_visible
+        });
+        self.tile_tasks
+            .retain(|index, _| visible_tiles.contains(index));
+        if tiles_changed {
+            self.report_retained_images(window, cx);
+        }
+
+        for index in visible_tiles {
+            if self.tiles.contains_key(&index) || self.tile_tasks.contains_key(&index) {
+                continue;
+            }
+            let renderer = cx.svg_renderer();
+            let tree = tree.clone();
+            let (origin, tile_size) = tiles::tile_region(index, svg_size, scale);
+            let render_task = cx.background_spawn(async move {
+                renderer.render_region(&tree, origin, tile_size, scale, true)
+            });
+            let task = cx.spawn_in(window, async move |this, cx| {
+                let tile = render_task.await;
+                this.update_in(cx, |this, window, cx| {
+                    this.tile_tasks.remove(&index);
+                    if let Ok(tile) = tile {
+                        this.tiles.insert(index, tile);
+                        this.report_retained_images(window, cx);
+                        cx.notify();
+                    }
+                })
+                .ok();
+            });
+            self.tile_tasks.insert(index, task);
+        }
+    }
+
+    /// Returns whether the zoom level changed too recently to be considered settled, in which case
+    /// the tiles are updated again once it settles.
+    fn is_zoom_unsettled(&mut self, window: &Window, cx: &mut Context<Self>) -> bool {
+        let Some(remaining) = self
+            .zoomed_at
+            .and_then(|zoomed_at| ZOOM_SETTLE_DELAY.checked_sub(zoomed_at.elapsed()))
+        else {
+            return false;
+        };
+        self._zoom_settle_task = cx.spawn_in(window, async move |this, cx| {
+            cx.background_executor().timer(remaining).await;
+            this.update(cx, |_, cx| cx.notify()).ok();
+        });
+        true
+    }
+
+    /// Returns the largest scale that the visible part of the SVG, given in SVG pixels, can be
+    /// rasterized at without the preview's images exceeding its share of the memory budget.
+    fn max_tile_scale(&self, visible: Bounds<f32>, cx: &Context<Self>) -> f32 {
+        let budget = SvgPreviewSettings::get_global(cx).memory_budget;
+        let other_images = self
+            .current_svg
+            .iter()
+            .flatten()
+            .chain(&self.animation.frame)
+            .map(|image| image_bytes(image))
+            .sum::<usize>();
+        let available_pixels = RetainedImages::available(cx.entity_id(), budget, cx)
+            .saturating_sub(other_images) as f32
+            / 4.;
+        // Tiles are aligned to a grid, so up to a tile more than the visible part is rasterized
+        // along each axis: solve (width * scale + tile) * (height * scale + tile) = pixels.
+        let tile = tiles::TILE_SIZE as f32;
+        let (width, height) = (visible.size.width.max(1.), visible.size.height.max(1.));
+        let a = width * height;
+        let b = tile * (width + height);
+        let c = tile * tile - available_pixels;
+        ((b * b - 4. * a * c).max(0.).sqrt() - b) / (2. * a)
+    }
+
+    /// Tessellates the SVG for drawing it on the GPU in the background, if that's the configured
+    /// renderer, when the SVG changes or its curves would look coarse at the current zoom level.
+    /// Returns whether the SVG is drawn on the GPU, in which case its tiles aren't rasterized,
+    /// unless it uses features that only the CPU can draw.
+    fn update_gpu_scene(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        if SvgPreviewSettings::get_global(cx).renderer != SvgPreviewRenderer::Gpu {
+            self.gpu_scene = GpuSceneState::default();
+            return false;
+        }
+        let Some(tree) = self.tree.clone() else {
+            return false;
+        };
+        let content_hash = self.content_hash;
+        if self.gpu_scene.unsupported == Some(content_hash) {
+            return false;
+        }
+        if self.tiles_key.is_some() {
+            self.stash_tiles(window);
+            self.report_retained_images(window, cx);
+        }
+
+        // Like tiles, scenes are tessellated more finely than the display needs, so that zooming
+        // in a little doesn't tessellate the SVG again.
+        let display_scale = self.scale_factor * window.scale_factor();
+        let covers = |hash: u64, scale: f32| {
+            hash == content_hash
+                && scale >= display_scale
+                && scale <= display_scale * MAX_TILE_DOWNSCALE
+        };
+        let shown_scene = self
+            .gpu_scene
+            .scene
+            .as_ref()
+            .filter(|(hash, _)| *hash == content_hash);
+        if shown_scene.is_some_and(|(_, scene)| covers(content_hash, scene.scale))
+            || self
+                .gpu_scene
+                .task
+                .as_ref()
+                .is_some_and(|(hash, scale, _)| covers(*hash, *scale))
+        {
+            return true;
+        }
+        // While zooming continuously, keep drawing the current scene rather than tessellating
+        // the SVG at every step.
+        if shown_scene.is_some() && self.is_zoom_unsettled(window, cx) {
+            return true;
+        }
+        let scale = display_scale * TILE_SUPERSAMPLING;
+        let tessellate = cx.background_spawn(async move { GpuScene::new(&tree, scale) });
+        let task = cx.spawn_in(window, async move |this, cx| {
+            let scene = tessellate.await;
+            this.update(cx, |this, cx| {
+                this.gpu_scene.task = None;
+                match scene {
+                    Some(scene) => this.gpu_scene.scene = Some((content_hash, Arc::new(scene))),
+                    None => this.gpu_scene.unsupported = Some(content_hash),
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+        self.gpu_scene.task = Some((content_hash, scale, task));
+        true
+    }
+
+    /// Returns an element that draws the SVG on the GPU, once it's tessellated.
+    fn render_gpu_scene(&self, image_bounds: Bounds<Pixels>) -> Option<AnyElement> {
+        let (_, scene) = self
+            .gpu_scene
+            .scene
+            .as_ref()
+            .filter(|(hash, _)| *hash == self.content_hash)?;
+        let scene = scene.clone();
+        let scale = self.scale_factor;
+        Some(
+            canvas(
+                |_, _, _| {},
+                move |bounds, _, window, _| scene.paint(image_bounds.origin, scale, bounds, window),
+            )
+            .absolute()
+            .size_full()
+            .into_any_element(),
+        )
+    }
+
+    /// Reports the images the preview retains to the memory budget, which evicts cached tiles of
+    /// the previews retaining the most when the budget is exceeded.
+    fn report_retained_images(&self, window: &Window, cx: &mut Context<Self>) {
+        let on_screen = self
+            .tiles
+            .values()
+            .chain(self.current_svg.iter().flatten())
+            .chain(&self.animation.frame)
+            .map(|image| image_bytes(image))
+            .sum::<usize>();
+        let cached = self.tile_cache.bytes();
+        let budget = SvgPreviewSettings::get_global(cx).memory_budget;
+        let view = cx.entity().downgrade();
+        let window_handle = window.window_handle();
+        RetainedImages::update(
+            cx.entity_id(),
+            on_screen + cached,
+            cached,
+            move |cx| {
+                window_handle
+                    .update(cx, |_, window, cx| {
+                        view.update(cx, |view, _| {
+                            for tile in view.tile_cache.clear() {
+                                window.drop_image(tile).ok();
+                            }
+                        })
+                    })
+                    .ok();
+            },
+            budget,
+            cx,
+        );
+    }
+
+    fn render_tiles(&self, image_bounds: Bounds<Pixels>) -> Vec<AnyElement> {
+        let Some(svg_size) = self.svg_size else {
+            return Vec::new();
+        };
+        let viewport_origin = self.viewport_bounds.origin;
+        // The tiles are stretched when they were rasterized at a lower scale than the display's,
+        // to stay within the memory budget.
+        let stretch = self.scale_factor / self.tile_scale;
+        self.tiles
+            .iter()
+            .map(|(index, tile)| {
+                let (origin, tile_size) = tiles::tile_region(*index, svg_size, self.tile_scale);
+                img(tile.clone())
+                    .object_fit(ObjectFit::Fill)
+                    .absolute()
+                    .left(
+                        image_bounds.origin.x - viewport_origin.x
+                            + px(origin.x * self.scale_factor),
+                    )
+                    .top(
+                        image_bounds.origin.y - viewport_origin.y
+                            + px(origin.y * self.scale_factor),
+                    )
+                    .w(px(tile_size.width.0 as f32 * stretch))
+                    .h(px(tile_size.height.0 as f32 * stretch))
+                    .into_any_element()
+            })
+            .collect()
+    }
+
+    fn current_color(&self, cx: &App) -> Hsla {
+        let theme_foreground = cx.theme().colors().text;
+        let current_color = self
+            .current_color_override
+            .as_ref()
+            .unwrap_or(&SvgPreviewSettings::get_global(cx).current_color);
+        match current_color {
+            SvgPreviewCurrentColor::Foreground => theme_foreground,
+            SvgPreviewCurrentColor::White => gpui::white(),
+            SvgPreviewCurrentColor::Black => gpui::black(),
+            SvgPreviewCurrentColor::Custom(color) => Rgba::try_from(color.as_str())
+                .map(Hsla::from)
+                .unwrap_or(theme_foreground),
+        }
+    }
+
+    /// The color scheme of the theme, unless a color scheme is being simulated.
+    fn color_scheme(&self, cx: &App) -> ColorScheme {
+        self.color_scheme_override
+            .unwrap_or(if cx.theme().appearance.is_light() {
+                ColorScheme::Light
+            } else {
+                ColorScheme::Dark
+            })
+    }
+
+    fn toggle_color_scheme(
+        &mut self,
+        _: &ToggleColorScheme,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.color_scheme_override = Some(self.color_scheme(cx).toggle());
+        self.notify_controls(cx);
+    }
+
+    /// Re-renders the image if the color that `currentColor` resolves to or the color scheme
+    /// changed, e.g. because the theme changed.
+    fn update_theme_inputs(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let current_color = hex_color(self.current_color(cx));
+        let color_scheme = self.color_scheme(cx);
+        if current_color != self.rendered_current_color
+            || color_scheme != self.rendered_color_scheme
+        {
+            self.rendered_current_color = current_color;
+            self.rendered_color_scheme = color_scheme;
+            self.render_image(window, cx);
+        }
+    }
+
+    fn render_current_color_menu(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let view = cx.entity().downgrade();
+        let focus_handle = self.focus_handle.clone();
+        let selected = self
+            .current_color_override
+            .clone()
+            .unwrap_or_else(|| SvgPreviewSettings::get_global(cx).current_color.clone());
+        let colors = cx.theme().colors();
+
+        PopoverMenu::new("current-color")
+            .trigger_with_tooltip(
+                ButtonLike::new("current-color-trigger").child(
+                    div()
+                        .size_3()
+                        .rounded_sm()
+                        .border_1()
+                        .border_color(colors.border)
+                        .bg(self.current_color(cx)),
+                ),
+                Tooltip::text("Current Color"),
+            )
+            .anchor(Corner::TopRight)
+            .menu(move |window, cx| {
+                let view = view.clone();
+                let focus_handle = focus_handle.clone();
+                let selected = selected.clone();
+                let custom = match SvgPreviewSettings::get_global(cx).current_color.clone() {
+                    SvgPreviewCurrentColor::Custom(color) => {
+                        Some(SvgPreviewCurrentColor::Custom(color))
+                    }
+                    _ => None,
+                };
+                Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                    menu = menu.context(focus_handle).header("currentColor");
+                    let options = [
+                        ("Theme Foreground", SvgPreviewCurrentColor::Foreground),
+                        ("White", SvgPreviewCurrentColor::White),
+                        ("Black", SvgPreviewCurrentColor::Black),
+                    ]
+                    .into_iter()
+                    .chain(custom.map(|custom| ("Custom", custom)));
+                    for (label, current_color) in options {
+                        let view = view.clone();
+                        menu = menu.toggleable_entry(
+                            label,
+                            selected == current_color,
+                            IconPosition::Start,
+                            None,
+                            move |_window, cx| {
+                                view.update(cx, |view, cx| {
+                                    view.current_color_override = Some(current_color.clone());
+                                    view.notify_controls(cx);
+                                })
+                                .ok();
+                            },
+                        );
+                    }
+                    menu
+                }))
+            })
+    }
+
+    fn toggle_outline(&mut self, _: &ToggleOutline, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_outline = !self.show_outline;
+        if self.show_outline {
+            self.source_map(window, cx);
+        }
+        self.notify_controls(cx);
+    }
+
+    /// Highlights the element starting at `offset` and moves the editor's cursor to it.
+    fn select_outline_element(
+        &mut self,
+        offset: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.cursor_offset = Some(offset);
+        self.update_cursor_highlight(window, cx);
+        self.select_in_editor(offset, false, window, cx);
+        cx.notify();
+    }
+
+    fn render_outline_panel(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        // The panel sits in the top left corner, inside the rulers.
+        let inset = if self.show_rulers {
+            RULER_SIZE + px(8.)
+        } else {
+            px(8.)
+        };
+        let selected_offset = self
+            .source_map
+            .as_ref()
+            .zip(self.cursor_offset)
+            .and_then(|(source_map, offset)| source_map.element_containing(offset))
+            .map(|element| element.range.start);
+
+        let mut rows = Vec::new();
+        if let Some(source_map) = &self.source_map {
+            let elements = source_map.elements();
+            let mut collapsed_depth = None;
+            for (index, element) in elements.iter().enumerate() {
+                if collapsed_depth.is_some_and(|depth| element.depth > depth) {
+                    continue;
+                }
+                let start = element.range.start;
+                let has_children = elements
+                    .get(index + 1)
+                    .is_some_and(|next| next.depth > element.depth);
+                let is_collapsed = self.collapsed_outline_elements.contains(&start);
+                collapsed_depth = (has_children && is_collapsed).then_some(element.depth);
+                rows.push(
+                    ListItem::new(("svg-outline-element", index))
+                        .spacing(ListItemSpacing::Dense)
+                        .indent_level(element.depth)
+                        .indent_step_size(px(12.))
+                        .toggle(has_children.then_some(!is_collapsed))
+                        .on_toggle(cx.listener(move |this, _, _, cx| {
+                            if !this.collapsed_outline_elements.remove(&start) {
+                                this.collapsed_outline_elements.insert(start);
+                            }
+                            cx.notify();
+                        }))
+                        .toggle_state(selected_offset == Some(start))
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.select_outline_element(start, window, cx)
+                        }))
+                        .child(
+                            Label::new(element.label.clone())
+                                .size(LabelSize::Small)
+                                .buffer_font(cx),
+                        ),
+                );
+            }
+        }
+
+        v_flex()
+            .id("svg-outline")
+            .absolute()
+            .top(inset)
+            .left(inset)
+            .w(px(240.))
+            .max_h((self.viewport_bounds.size.height - inset - px(48.)).max(px(80.)))
+            .overflow_y_scroll()
+            .p_2()
+            .gap_1()
+            .rounded_sm()
+            .border_1()
+            .border_color(colors.border)
+            .bg(colors.elevated_surface_background)
+            .shadow_md()
+            // Keep clicks in the panel from panning the preview.
+            .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(Label::new("Outline").size(LabelSize::Small))
+                    .child(
+                        IconButton::new("close-outline", IconName::Close)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Close",
+                                &ToggleOutline,
+                                &self.focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.toggle_outline(&ToggleOutline, window, cx)
+                            })),
+                    ),
+            )
+            .when(self.source_map.is_none(), |this| {
+                this.child(
+                    Label::new("Loading…")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .children(rows)
+    }
+
+    fn toggle_custom_properties(
+        &mut self,
+        _: &ToggleCustomProperties,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.show_custom_properties = !self.show_custom_properties;
+        // The panels take the same place.
+        self.show_accessibility_audit = false;
+        self.optimization_report.shown = false;
+        self.update_custom_property_editors(window, cx);
+        self.subscribe_panels_to_edits(window, cx);
+        self.notify_controls(cx);
+    }
+
+    fn audit_accessibility(
+        &mut self,
+        _: &AuditAccessibility,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.show_accessibility_audit = !self.show_accessibility_audit;
+        if self.show_accessibility_audit {
+            self.show_custom_properties = false;
+            self.optimization_report.shown = false;
+            self.update_custom_property_editors(window, cx);
+            self.subscribe_panels_to_edits(window, cx);
+            self.source_map(window, cx);
+        }
+        self.notify_controls(cx);
+    }
+
+    /// Optimizes the SVG in the background, and shows the result next to the original until
+    /// it's accepted or discarded.
+    fn optimize_svg(&mut self, _: &OptimizeSvg, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(text) = self.source_text(cx) else {
+            return;
+        };
+        let passes = self.optimization_report.passes.clone();
+        let inputs = self.render_inputs(cx);
+        self.comparison.task = Some(cx.spawn_in(window, async move |this, cx| {
+            let comparison = cx
+                .background_spawn(async move {
+                    let optimized = optimize::optimize(&text, &passes)?;
+                    let image = inputs.render(&optimized)?;
+                    Some(Comparison::Optimization {
+                        original_len: text.len(),
+                        text: optimized,
+                        image,
+                    })
+                })
+                .await;
+            this.update_in(cx, |this, window, cx| {
+                this.comparison.task = None;
+                this.set_comparison(comparison, window, cx);
+            })
+            .ok();
+        }));
+    }
+
+    fn compare_with_head(
+        &mut self,
+        _: &CompareWithHead,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if matches!(self.comparison.current, Some(Comparison::Head { .. })) {
+            self.comparison.task = None;
+            self.set_comparison(None, window, cx);
+            return;
+        }
+        let (Some(buffer), Some(project)) = (self.buffer.clone(), self.project.clone()) else {
+            return;
+        };
+        if self.embedded_range.is_some() {
+            struct CompareWithHeadToast;
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<CompareWithHeadToast>(),
+                            "Comparing with HEAD isn't supported for SVGs embedded in other files",
+                        ),
+                        cx,
+                    )
+                })
+                .ok();
+            return;
+        }
+        let diff = project.update(cx, |project, cx| project.open_uncommitted_diff(buffer, cx));
+        cx.spawn_in(window, async move |this, cx| {
+            let diff = diff.await?;
+            let head_text = diff
+                .read_with(cx, |diff, _| diff.base_text_string())?
+                .context("The SVG isn't committed")?;
+            this.update_in(cx, |this, window, cx| {
+                this.update_head_comparison(head_text, Duration::ZERO, window, cx)
+            })
+        })
+        .detach_and_prompt_err("Failed to compare with HEAD", window, cx, |_, _, _| None);
+    }
+
+    /// Renders the working copy again for the comparison with the last commit, if it's shown.
+    fn update_head_comparison_after_edit(
+        &mut self,
+        debounce: Duration,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(Comparison::Head { text, .. }) = &self.comparison.current {
+            self.update_head_comparison(text.clone(), debounce, window, cx);
+        }
+    }
+
+    /// Renders `head_text` and the working copy in the background after `debounce`, and shows
+    /// them side by side with the regions where they differ highlighted.
+    fn update_head_comparison(
+        &mut self,
+        head_text: String,
+        debounce: Duration,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(text) = self.source_text(cx) else {
+            return;
+        };
+        let inputs = self.render_inputs(cx);
+        self.comparison.task = Some(cx.spawn_in(window, async move |this, cx| {
+            cx.background_executor().timer(debounce).await;
+            let comparison = cx
+                .background_spawn(async move {
+                    let image = inputs.render(&head_text)?;
+                    let (changes, heatmap) = match inputs.render(&text) {
+                        Some(working_copy) => (
+                            visual_diff::changed_regions(&image, &working_copy),
+                            visual_diff::heatmap(&image, &working_copy).map(Arc::new),
+                        ),
+                        None => (Vec::new(), None),
+                    };
+                    Some(Comparison::Head {
+                        text: head_text,
+                        image,
+                        changes,
+                        heatmap,
+                    })
+                })
+                .await;
+            this.update_in(cx, |this, window, cx| {
+                this.comparison.task = None;
+                this.set_comparison(comparison, window, cx);
+            })
+            .ok();
+        }));
+    }
+
+    /// Returns what the SVG is currently rendered with besides its text.
+    fn render_inputs(&self, cx: &App) -> RenderInputs {
+        RenderInputs {
+            renderer: cx.svg_renderer(),
+            external_resources: self.external_resources.clone(),
+            fonts: self.fonts.clone(),
+            current_color: self.rendered_current_color.clone(),
+            media_context: MediaContext {
+                color_scheme: self.rendered_color_scheme,
+                viewport: self.simulated_viewport,
+            },
+            custom_property_overrides: self.custom_property_overrides.clone(),
+        }
+    }
+
+    fn set_comparison(
+        &mut self,
+        comparison: Option<Comparison>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let comparing_head = matches!(comparison, Some(Comparison::Head { .. }));
+        if let Some(comparison) = mem::replace(&mut self.comparison.current, comparison) {
+            for image in comparison.into_images() {
+                window.drop_image(image).ok();
+            }
+        }
+        if comparing_head != self.comparison._edits.is_some() {
+            self.subscribe_panels_to_edits(window, cx);
+        }
+        self.update_blink_task(cx);
+        cx.notify();
+    }
+
+    fn accept_optimization(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(Comparison::Optimization { text, image, .. }) = self.comparison.current.take() {
+            window.drop_image(image).ok();
+            self.rewrite_buffer(text, window, cx);
+        }
+    }
+
+    fn clean_svg(&mut self, _: &CleanSvg, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(text) = self.source_text(cx) else {
+            return;
+        };
+        cx.spawn_in(window, async move |this, cx| {
+            let cleaned = cx
+                .background_spawn(async move { optimize::optimize(&text, &[Pass::StripMetadata]) })
+                .await;
+            if let Some(cleaned) = cleaned {
+                this.update_in(cx, |this, window, cx| {
+                    this.rewrite_buffer(cleaned, window, cx)
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Replaces the text of the SVG's buffer with `text` as a single edit that can be undone, and
+    /// renders the result right away so that any visual changes show up.
+    fn rewrite_buffer(&mut self, text: String, window: &mut Window, cx: &mut Context<Self>) {
+        let (Some(buffer), Some(source_range)) = (self.buffer.clone(), self.source_range(cx))
+        else {
+            return;
+        };
+        // Embedded SVGs are rewritten in place, keeping the rest of the buffer.
+        let mut new_text = buffer.read(cx).text();
+        new_text.replace_range(source_range, &text);
+        let diff = buffer.read(cx).diff(new_text, cx);
+        cx.spawn_in(window, async move |this, cx| {
+            let diff = diff.await;
+            buffer.update(cx, |buffer, cx| buffer.apply_diff(diff, cx))?;
+            this.update_in(cx, |this, window, cx| this.render_image(window, cx))
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Renders the SVG together with the version it's compared with. Both are placed where
+    /// they'd be in a viewport of the size they're shown at, so that panning and zooming moves
+    /// them together.
+    fn render_comparison(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let comparison = self.comparison.current.as_ref()?;
+        let Some(Ok(current)) = self.current_svg.clone() else {
+            return None;
+        };
+        let image_bounds = self.image_bounds()?;
+        let colors = cx.theme().colors();
+        let background = self.background_color(cx);
+        let left = image_bounds.origin.x - self.viewport_bounds.origin.x;
+        let top = image_bounds.origin.y - self.viewport_bounds.origin.y;
+        let layer = |image: Arc<RenderImage>, left: Pixels| {
+            img(image)
+                .object_fit(ObjectFit::Fill)
+                .absolute()
+                .left(left)
+                .top(top)
+                .w(image_bounds.size.width)
+                .h(image_bounds.size.height)
+        };
+        let label = |label: String| {
+            div()
+                .absolute()
+                .bottom_2()
+                .child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+        };
+        // Each side is half as wide as the viewport, so the SVG moves left by a quarter of it.
+        let side_left = left - self.viewport_bounds.size.width / 4.;
+        let side = |image: Arc<RenderImage>,
+                    text: String,
+                    changes: &[Bounds<f32>],
+                    heatmap: Option<Arc<RenderImage>>| {
+            div()
+                .relative()
+                .flex_1()
+                .h_full()
+                .overflow_hidden()
+                .bg(background)
+                .child(layer(image, side_left))
+                .children(heatmap.map(|heatmap| layer(heatmap, side_left)))
+                .children(changes.iter().map(|change| {
+                    div()
+                        .absolute()
+                        .left(side_left + image_bounds.size.width * change.origin.x)
+                        .top(top + image_bounds.size.height * change.origin.y)
+                        .w(image_bounds.size.width * change.size.width)
+                        .h(image_bounds.size.height * change.size.height)
+                        .bg(colors.version_control_modified.opacity(0.25))
+                }))
+                .child(label(text).left_2())
+        };
+        let side_by_side = |left_side: Div, right_side: Div| {
+            h_flex()
+                .size_full()
+                .child(left_side)
+                .child(div().w_px().h_full().bg(colors.border))
+                .child(right_side)
+                .into_any_element()
+        };
+
+        let (title, content, buttons) = match comparison {
+            Comparison::Optimization {
+                text,
+                image,
+                original_len,
+            } => {
+                let original_size = util::size::format_file_size(*original_len as u64, true);
+                let optimized_size = util::size::format_file_size(text.len() as u64, true);
+                let saved = util::size::format_file_size(
+                    original_len.saturating_sub(text.len()) as u64,
+                    true,
+                );
+                (
+                    "Optimize SVG".to_string(),
+                    side_by_side(
+                        side(current, format!("Original · {original_size}"), &[], None),
+                        side(
+                            image.clone(),
+                            format!("Optimized · {optimized_size} (−{saved})"),
+                            &[],
+                            None,
+                        ),
+                    ),
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            Button::new("accept-optimization", "Accept")
+                                .label_size(LabelSize::Small)
+                                .style(ButtonStyle::Filled)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.accept_optimization(window, cx)
+                                })),
+                        )
+                        .child(
+                            Button::new("discard-optimization", "Discard")
+                                .label_size(LabelSize::Small)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.set_comparison(None, window, cx)
+                                })),
+                        ),
+                )
+            }
+            Comparison::Head {
+                image,
+                changes,
+                heatmap,
+                ..
+            } => {
+                let has_heatmap = heatmap.is_some();
+                let heatmap = heatmap.clone().filter(|_| self.comparison.show_heatmap);
+                // The heatmap shows the changes in more detail than the highlighted regions.
+                let highlighted_changes = if heatmap.is_some() { &[][..] } else { changes };
+                let content = match self.comparison.mode {
+                    CompareMode::SideBySide => side_by_side(
+                        side(
+                            image.clone(),
+                            "HEAD".to_string(),
+                            highlighted_changes,
+                            heatmap.clone(),
+                        ),
+                        side(
+                            current,
+                            "Working Copy".to_string(),
+                            highlighted_changes,
+                            heatmap,
+                        ),
+                    ),
+                    CompareMode::OnionSkin => div()
+                        .size_full()
+                        .bg(background)
+                        .child(layer(current, left))
+                        .child(layer(image.clone(), left).opacity(0.5))
+                        .children(heatmap.map(|heatmap| layer(heatmap, left)))
+                        .child(label("HEAD over Working Copy".to_string()).left_2())
+                        .into_any_element(),
+                    CompareMode::Swipe => {
+                        let divider =
+                            self.viewport_bounds.size.width * self.comparison.swipe_position;
+                        div()
+                            .size_full()
+                            .bg(background)
+                            .child(layer(current, left))
+                            .child(
+                                div()
+                                    .absolute()
+                                    .top_0()
+                                    .left_0()
+                                    .w(divider)
+                                    .h_full()
+                                    .overflow_hidden()
+                                    .bg(background)
+                                    .child(layer(image.clone(), left)),
+                            )
+                            .children(heatmap.map(|heatmap| layer(heatmap, left)))
+                            .child(
+                                div()
+                                    .absolute()
+                                    .top_0()
+                                    .left(divider - px(3.))
+                                    .w(px(7.))
+                                    .h_full()
+                                    .flex()
+                                    .justify_center()
+                                    .cursor_col_resize()
+                                    .child(div().w(px(1.)).h_full().bg(colors.border_focused))
+                                    .on_mouse_down(
+                                        MouseButton::Left,
+                                        cx.listener(|this, event: &MouseDownEvent, _, cx| {
+                                            cx.stop_propagation();
+                                            this.comparison.dragging_swipe_divider = true;
+                                            this.set_swipe_position(event.position.x, cx);
+                                        }),
+                                    ),
+                            )
+                            .child(label("HEAD".to_string()).left_2())
+                            .child(label("Working Copy".to_string()).right_2())
+                            .into_any_element()
+                    }
+                    CompareMode::Blink => {
+                        let (image, text) = if self.comparison.blink_showing_head {
+                            (image.clone(), "HEAD")
+                        } else {
+                            (current, "Working Copy")
+                        };
+                        div()
+                            .size_full()
+                            .bg(background)
+                            .child(layer(image, left))
+                            .children(heatmap.map(|heatmap| layer(heatmap, left)))
+                            .child(label(text.to_string()).left_2())
+                            .into_any_element()
+                    }
+                };
+                (
+                    match changes.len() {
+                        0 => "No visual changes since HEAD".to_string(),
+                        1 => "1 region changed since HEAD".to_string(),
+                        count => format!("{count} regions changed since HEAD"),
+                    },
+                    content,
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            h_flex().gap_0p5().children(
+                                [
+                                    (CompareMode::SideBySide, "Side by Side"),
+                                    (CompareMode::OnionSkin, "Onion Skin"),
+                                    (CompareMode::Swipe, "Swipe"),
+                                    (CompareMode::Blink, "Blink"),
+                                ]
+                                .into_iter()
+                                .map(|(mode, label)| {
+                                    Button::new(label, label)
+                                        .label_size(LabelSize::Small)
+                                        .toggle_state(self.comparison.mode == mode)
+                                        .on_click(cx.listener(move |this, _, _, cx| {
+                                            this.set_compare_mode(mode, cx)
+                                        }))
+                                }),
+                            ),
+                        )
+                        .child(
+                            Button::new("toggle-heatmap", "Heatmap")
+                                .label_size(LabelSize::Small)
+                                .toggle_state(self.comparison.show_heatmap)
+                                .disabled(!has_heatmap)
+                                .tooltip(Tooltip::text("Overlay How Much Each Pixel Changed"))
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.comparison.show_heatmap = !this.comparison.show_heatmap;
+                                    cx.notify();
+                                })),
+                        )
+                        .child(
+                            Button::new("close-comparison", "Close")
+                                .label_size(LabelSize::Small)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.comparison.task = None;
+                                    this.set_comparison(None, window, cx)
+                                })),
+                        ),
+                )
+            }
+        };
+
+        Some(
+            div().absolute().size_full().child(content).child(
+                h_flex()
+                    .absolute()
+                    .top_2()
+                    .left_0()
+                    .right_0()
+                    .justify_center()
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .p_1()
+                            .rounded_sm()
+                            .border_1()
+                            .border_color(colors.border)
+                            .bg(colors.elevated_surface_background)
+                            .shadow_md()
+                            // Keep clicks on the buttons from panning the preview.
+                            .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                            .child(Label::new(title).size(LabelSize::Small))
+                            .child(buttons),
+                    ),
+            ),
+        )
+    }
+
+    /// Renders how long the last render took and what it produced, so that it can be told why an
+    /// SVG is slow to render.
+    fn render_render_stats(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        if !self.show_render_stats {
+            return None;
+        }
+        let stats = self.render_stats?;
+        let colors = cx.theme().colors();
+        let row = |label: &'static str, value: String| {
+            h_flex()
+                .gap_2()
+                .justify_between()
+                .child(
+                    Label::new(label)
+                        .size(LabelSize::XSmall)
+                        .color(Color::Muted),
+                )
+                .child(Label::new(value).size(LabelSize::XSmall).buffer_font(cx))
+        };
+        let milliseconds = |duration: Duration| format!("{:.1} ms", duration.as_secs_f64() * 1000.);
+        Some(
+            h_flex()
+                .absolute()
+                .bottom_2()
+                .left_0()
+                .right_0()
+                .justify_center()
+                .child(
+                    v_flex()
+                        .px_1()
+                        .rounded_sm()
+                        .border_1()
+                        .border_color(colors.border)
+                        .bg(colors.elevated_surface_background)
+                        .child(row("Parse", milliseconds(stats.parse_time)))
+                        .child(row("Rasterize", milliseconds(stats.rasterize_time)))
+                        .child(row(
+                            "Size",
+                            format!(
+                                "{} × {} px",
+                                stats.image_size.width.0, stats.image_size.height.0
+                            ),
+                        ))
+                        .child(row("Elements", stats.element_count.to_string())),
+                ),
+        )
+    }
+
+    /// Renders a note that the SVG is the last one a following preview followed, while an item
+    /// other than an SVG editor is active.
+    fn render_inactive_indicator(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        if !self.following_inactive {
+            return None;
+        }
+        let name = self
+            .buffer
+            .as_ref()?
+            .read(cx)
+            .file()
+            .map_or_else(|| "SVG".to_string(), |file| file.file_name(cx).to_string());
+        let colors = cx.theme().colors();
+        Some(
+            h_flex()
+                .absolute()
+                .top_2()
+                .left_0()
+                .right_0()
+                .justify_center()
+                .child(
+                    div()
+                        .px_1()
+                        .rounded_sm()
+                        .border_1()
+                        .border_color(colors.border)
+                        .bg(colors.elevated_surface_background)
+                        .child(
+                            Label::new(format!("Following {name} — inactive"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+
+    fn set_compare_mode(&mut self, mode: CompareMode, cx: &mut Context<Self>) {
+        self.comparison.mode = mode;
+        self.update_blink_task(cx);
+        cx.notify();
+    }
+
+    /// Starts alternating between the SVG and its version in the last commit while they're
+    /// compared in [`CompareMode::Blink`], and stops otherwise.
+    fn update_blink_task(&mut self, cx: &mut Context<Self>) {
+        let blinking = self.comparison.mode == CompareMode::Blink
+            && matches!(self.comparison.current, Some(Comparison::Head { .. }));
+        if !blinking {
+            self.comparison.blink_task = None;
+        } else if self.comparison.blink_task.is_none() {
+            self.comparison.blink_showing_head = true;
+            self.comparison.blink_task = Some(cx.spawn(async move |this, cx| {
+                loop {
+                    cx.background_executor().timer(BLINK_INTERVAL).await;
+                    let blinked = this.update(cx, |this, cx| {
+                        this.comparison.blink_showing_head = !this.comparison.blink_showing_head;
+                        cx.notify();
+                    });
+                    if blinked.is_err() {
+                        break;
+                    }
+                }
+            }));
+        }
+    }
+
+    fn set_swipe_position(&mut self, position_x: Pixels, cx: &mut Context<Self>) {
+        let bounds = self.viewport_bounds;
+        if bounds.size.width <= px(0.) {
+            return;
+        }
+        self.comparison.swipe_position =
+            ((position_x - bounds.origin.x) / bounds.size.width).clamp(0., 1.);
+        cx.notify();
+    }
+
+    fn show_optimization_report(
+        &mut self,
+        _: &ShowOptimizationReport,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.optimization_report.shown = !self.optimization_report.shown;
+        if self.optimization_report.shown {
+            // The panels take the same place.
+            self.show_custom_properties = false;
+            self.show_accessibility_audit = false;
+            self.update_custom_property_editors(window, cx);
+            self.update_optimization_report(Duration::ZERO, window, cx);
+        } else {
+            self.optimization_report.savings = None;
+            self.optimization_report.task = None;
+        }
+        self.subscribe_panels_to_edits(window, cx);
+        self.notify_controls(cx);
+    }
+
+    /// Works out what each optimization pass saves in the background after `debounce`, if the
+    /// report is shown. The previous report stays on screen until then.
+    fn update_optimization_report(
+        &mut self,
+        debounce: Duration,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.optimization_report.shown {
+            return;
+        }
+        let Some(buffer) = self.buffer.as_ref() else {
+            return;
+        };
+        let text = buffer.read(cx).text();
+        self.optimization_report.task = Some(cx.spawn_in(window, async move |this, cx| {
+            cx.background_executor().timer(debounce).await;
+            let report = cx
+                .background_spawn(async move { optimize::savings(&text) })
+                .await;
+            this.update(cx, |this, cx| {
+                this.optimization_report.savings = Some(report);
+                this.optimization_report.task = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn toggle_optimization_pass(&mut self, pass: Pass, cx: &mut Context<Self>) {
+        if let Some(index) = self
+            .optimization_report
+            .passes
+            .iter()
+            .position(|p| *p == pass)
+        {
+            self.optimization_report.passes.remove(index);
+        } else {
+            // Keeps the passes in the order they're applied in.
+            self.optimization_report.passes = Pass::ALL
+                .into_iter()
+                .filter(|p| *p == pass || self.optimization_report.passes.contains(p))
+                .collect();
+        }
+        cx.notify();
+    }
+
+    fn render_optimization_report(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let top = if self.show_rulers {
+            RULER_SIZE + px(8.)
+        } else {
+            px(8.)
+        };
+        let format_savings = |bytes: usize, elements: usize| {
+            let mut savings = format!("−{}", util::size::format_file_size(bytes as u64, true));
+            if elements > 0 {
+                let noun = if elements == 1 { "element" } else { "elements" };
+                savings.push_str(&format!(" · −{elements} {noun}"));
+            }
+            savings
+        };
+
+        v_flex()
+            .id("optimization-report")
+            .absolute()
+            .top(top)
+            .right_2()
+            .w(px(280.))
+            .max_h((self.viewport_bounds.size.height - top - px(48.)).max(px(80.)))
+            .overflow_y_scroll()
+            .p_2()
+            .gap_1()
+            .rounded_sm()
+            .border_1()
+            .border_color(colors.border)
+            .bg(colors.elevated_surface_background)
+            .shadow_md()
+            // Keep clicks in the panel from panning the preview.
+            .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(Label::new("Optimization").size(LabelSize::Small))
+                    .child(
+                        IconButton::new("close-optimization-report", IconName::Close)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Close",
+                                &ShowOptimizationReport,
+                                &self.focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.optimization_report
+                                    .shown(&ShowOptimizationReport, window, cx)
+                            })),
+                    ),
+            )
+            .map(|this| match &self.optimization_report.savings {
+                None => this.child(
+                    Label::new("Loading…")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+                Some(report) => {
+                    let (bytes, elements) = report
+                        .iter()
+                        .filter(|savings| self.optimization_report.passes.contains(&savings.pass))
+                        .fold((0, 0), |(bytes, elements), savings| {
+                            (bytes + savings.bytes, elements + savings.elements)
+                        });
+                    this.children(report.iter().map(|savings| {
+                        let pass = savings.pass;
+                        h_flex()
+                            .justify_between()
+                            .gap_2()
+                            .child(
+                                Checkbox::new(
+                                    SharedString::from(format!("optimization-pass-{pass:?}")),
+                                    self.optimization_report.passes.contains(&pass).into(),
+                                )
+                                .label(pass.label())
+                                .label_size(LabelSize::Small)
+                                .on_click(cx.listener(
+                                    move |this, _, _, cx| this.toggle_optimization_pass(pass, cx),
+                                )),
+                            )
+                            .child(
+                                Label::new(format_savings(savings.bytes, savings.elements))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                    }))
+                    .child(
+                        h_flex()
+                            .pt_1()
+                            .justify_between()
+                            .gap_2()
+                            .border_t_1()
+                            .border_color(colors.border_variant)
+                            .child(
+                                Label::new(format!("Total {}", format_savings(bytes, elements)))
+                                    .size(LabelSize::Small),
+                            )
+                            .child(
+                                Button::new("apply-optimization", "Apply")
+                                    .label_size(LabelSize::Small)
+                                    .disabled(self.optimization_report.passes.is_empty())
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.optimize_svg(&OptimizeSvg, window, cx)
+                                    })),
+                            ),
+                    )
+                }
+            })
+    }
+
+    /// Renders the warnings about the SVG that haven't been dismissed, if any.
+    fn render_warnings(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let render_error = self.render_error_banner(cx);
+        let security_warning = self.render_security_warning(cx);
+        let unsupported_features_warning = self.render_unsupported_features_warning(cx);
+        if render_error.is_none()
+            && security_warning.is_none()
+            && unsupported_features_warning.is_none()
+        {
+            return None;
+        }
+        let inset = if self.show_rulers {
+            RULER_SIZE + px(8.)
+        } else {
+            px(8.)
+        };
+        Some(
+            v_flex()
+                .absolute()
+                .top(inset)
+                .left(inset)
+                .max_w(px(420.))
+                .gap_1()
+                .children(render_error)
+                .children(security_warning)
+                .children(unsupported_features_warning),
+        )
+    }
+
+    /// Reports why the SVG can't be rendered anymore, while its last successful render is shown.
+    fn render_error_banner(&self, cx: &mut Context<Self>) -> Option<Banner> {
+        let render_error = self
+            .render_error
+            .as_ref()
+            .filter(|render_error| !render_error.dismissed)?;
+        if !matches!(self.current_svg, Some(Ok(_))) {
+            return None;
+        }
+        Some(
+            Banner::new()
+                .severity(ui::Severity::Error)
+                .child(
+                    v_flex()
+                        .gap_0p5()
+                        .child(Label::new(render_error.message.clone()).size(LabelSize::Small))
+                        .children(render_error.line.as_ref().map(|(row, text)| {
+                            Label::new(format!("{row}: {text}"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .buffer_font(cx)
+                                .truncate()
+                        })),
+                )
+                .action_slot(
+                    IconButton::new("dismiss-render-error", IconName::Close)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Dismiss"))
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            if let Some(render_error) = this.render_error.as_mut() {
+                                render_error.dismissed = true;
+                            }
+                            cx.notify();
+                        })),
+                ),
+        )
+    }
+
+    /// Warns that the SVG would run code or make requests if opened in a browser, and whether
+    /// they were removed from the preview.
+    fn render_security_warning(&self, cx: &mut Context<Self>) -> Option<Banner> {
+        if self.security_risks.is_empty() || self.security_risks == self.dismissed_security_risks {
+            return None;
+        }
+        let consequence = if self.rendered_sandbox {
+            "They were removed before rendering."
+        } else {
+            "Enable the \"svg_preview.sandbox\" setting to remove them before rendering."
+        };
+        Some(
+            Banner::new()
+                .severity(ui::Severity::Warning)
+                .child(
+                    Label::new(format!(
+                        "This SVG contains {}. {consequence}",
+                        self.security_risks.join(", ")
+                    ))
+                    .size(LabelSize::Small),
+                )
+                .action_slot(
+                    IconButton::new("dismiss-security-risks", IconName::Close)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Dismiss"))
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.dismissed_security_risks = this.security_risks.clone();
+                            cx.notify();
+                        })),
+                ),
+        )
+    }
+
+    /// Warns that the preview may differ from browsers if the SVG uses features that the renderer
+    /// ignores.
+    fn render_unsupported_features_warning(&self, cx: &mut Context<Self>) -> Option<Banner> {
+        if self.unsupported_features.is_empty()
+            || self.unsupported_features == self.dismissed_unsupported_features
+        {
+            return None;
+        }
+        Some(
+            Banner::new()
+                .severity(ui::Severity::Warning)
+                .child(
+                    Label::new(format!(
+                        "The preview may differ from browsers, since it doesn't support: {}",
+                        self.unsupported_features.join(", ")
+                    ))
+                    .size(LabelSize::Small),
+                )
+                .action_slot(
+                    IconButton::new("dismiss-unsupported-features", IconName::Close)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Dismiss"))
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.dismissed_unsupported_features = this.unsupported_features.clone();
+                            cx.notify();
+                        })),
+                ),
+        )
+    }
+
+    fn render_accessibility_panel(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let top = if self.show_rulers {
+            RULER_SIZE + px(8.)
+        } else {
+            px(8.)
+        };
+        let findings =
+            self.buffer
+                .as_ref()
+                .zip(self.source_map.as_ref())
+                .map(|(buffer, source_map)| {
+                    accessibility::audit(
+                        &buffer.read(cx).text(),
+                        &source_map.text_fills(),
+                        self.background_color(cx).into(),
+                    )
+                });
+
+        v_flex()
+            .id("accessibility-audit")
+            .absolute()
+            .top(top)
+            .right_2()
+            .w(px(280.))
+            .max_h((self.viewport_bounds.size.height - top - px(48.)).max(px(80.)))
+            .overflow_y_scroll()
+            .p_2()
+            .gap_1()
+            .rounded_sm()
+            .border_1()
+            .border_color(colors.border)
+            .bg(colors.elevated_surface_background)
+            .shadow_md()
+            // Keep clicks in the panel from panning the preview.
+            .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(Label::new("Accessibility").size(LabelSize::Small))
+                    .child(
+                        IconButton::new("close-accessibility-audit", IconName::Close)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Close",
+                                &AuditAccessibility,
+                                &self.focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.audit_accessibility(&AuditAccessibility, window, cx)
+                            })),
+                    ),
+            )
+            .map(|this| match findings {
+                None => this.child(
+                    Label::new("Loading…")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+                Some(findings) if findings.is_empty() => this.child(
+                    Label::new("No problems found.")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+                Some(findings) => {
+                    this.children(findings.into_iter().enumerate().map(|(index, finding)| {
+                        let start = finding.range.start;
+                        ListItem::new(("accessibility-finding", index))
+                            .spacing(ListItemSpacing::Dense)
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.select_outline_element(start, window, cx)
+                            }))
+                            .start_slot(
+                                Icon::new(IconName::Warning)
+                                    .size(IconSize::Small)
+                                    .color(Color::Warning),
+                            )
+                            .child(Label::new(finding.message).size(LabelSize::Small))
+                    }))
+                }
+            })
+    }
+
+    fn set_custom_properties(
+        &mut self,
+        custom_properties: Vec<CustomProperty>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if custom_properties != self.custom_properties {
+            self.custom_properties = custom_properties;
+            self.update_custom_property_editors(window, cx);
+        }
+    }
+
+    /// Creates an editor for the value of each custom property while the panel is shown, keeping
+    /// the editors of properties that are still referenced.
+    fn update_custom_property_editors(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.show_custom_properties {
+            self.custom_property_editors.clear();
+            return;
+        }
+        let custom_properties = &self.custom_properties;
+        self.custom_property_editors.retain(|name, _| {
+            custom_properties
+                .iter()
+                .any(|property| &property.name == name)
+        });
+        for property in self.custom_properties.clone() {
+            if self.custom_property_editors.contains_key(&property.name) {
+                continue;
+            }
+            let editor = cx.new(|cx| {
+                let mut editor = Editor::single_line(window, cx);
+                editor.set_placeholder_text(
+                    property.value.as_deref().unwrap_or("Unset"),
+                    window,
+                    cx,
+                );
+                if let Some(value) = self.custom_property_overrides.get(&property.name) {
+                    editor.set_text(value.clone(), window, cx);
+                }
+                editor
+            });
+            let name = property.name.clone();
+            let subscription = cx.subscribe_in(
+                &editor,
+                window,
+                move |this, editor, event: &EditorEvent, window, cx| {
+                    if let EditorEvent::BufferEdited = event {
+                        let value = editor.read(cx).text(cx).trim().to_string();
+                        if value.is_empty() {
+                            this.custom_property_overrides.remove(&name);
+                        } else {
+                            this.custom_property_overrides.insert(name.clone(), value);
+                        }
+                        this.render_image_debounced(window, cx);
+                    }
+                },
+            );
+            self.custom_property_editors.insert(
+                property.name,
+                CustomPropertyEditor {
+                    editor,
+                    _subscription: subscription,
+                },
+            );
+        }
+    }
+
+    fn render_custom_properties_panel(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let top = if self.show_rulers {
+            RULER_SIZE + px(8.)
+        } else {
+            px(8.)
+        };
+        v_flex()
+            .id("custom-properties")
+            .absolute()
+            .top(top)
+            .right_2()
+            .w(px(280.))
+            .max_h((self.viewport_bounds.size.height - top - px(48.)).max(px(80.)))
+            .overflow_y_scroll()
+            .p_2()
+            .gap_1()
+            .rounded_sm()
+            .border_1()
+            .border_color(colors.border)
+            .bg(colors.elevated_surface_background)
+            .shadow_md()
+            // Keep clicks in the panel from panning the preview.
+            .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(Label::new("Custom Properties").size(LabelSize::Small))
+                    .child(
+                        IconButton::new("close-custom-properties", IconName::Close)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Close",
+                                &ToggleCustomProperties,
+                                &self.focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.toggle_custom_properties(&ToggleCustomProperties, window, cx)
+                            })),
+                    ),
+            )
+            .when(self.custom_properties.is_empty(), |this| {
+                this.child(
+                    Label::new("The SVG doesn't reference any custom properties.")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .children(self.custom_properties.iter().filter_map(|property| {
+                let editor = self.custom_property_editors.get(&property.name)?;
+                Some(
+                    v_flex()
+                        .gap_0p5()
+                        .child(
+                            Label::new(property.name.clone())
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted),
+                        )
+                        .child(
+                            div()
+                                .px_1()
+                                .rounded_sm()
+                                .border_1()
+                                .border_color(colors.border)
+                                .bg(colors.editor_background)
+                                .child(editor.editor.clone()),
+                        ),
+                )
+            }))
+    }
+
+    fn background_color(&self, cx: &App) -> Hsla {
+        let background = self
+            .background_override
+            .as_ref()
+            .unwrap_or(&SvgPreviewSettings::get_global(cx).background);
+        resolve_background(background, cx)
+    }
+
+    fn set_current(
+        &mut self,
+        image: Option<Result<Arc<RenderImage>, SharedString>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(Ok(image)) = mem::replace(&mut self.current_svg, image) {
+            window.drop_image(image).ok();
+        }
+        self.large_svg = None;
+        cx.notify();
+    }
+
+    fn find_existing_preview_item_idx(
+        pane: &Pane,
+        buffer: &Entity<MultiBuffer>,
+        cx: &App,
+    ) -> Option<usize> {
+        let buffer_id = buffer.entity_id();
+        pane.items_of_type::<SvgPreviewView>()
+            .find(|view| {
+                view.read(cx)
+                    .buffer
+                    .as_ref()
+                    .is_some_and(|buffer| buffer.entity_id() == buffer_id)
+            })
+            .and_then(|view| pane.index_for_item(&view))
     }
 
     pub fn resolve_active_item_as_svg_buffer(
@@ -162,6 +6871,29 @@ impl SvgPreviewView {
             .filter(|buffer| Self::is_svg_file(&buffer, cx))
     }
 
+    /// Returns the buffer of the active editor and the range of the `<svg>` element around its
+    /// cursor, for previewing SVGs embedded in other languages.
+    fn resolve_embedded_svg(
+        workspace: &Workspace,
+        cx: &mut Context<Workspace>,
+    ) -> Option<(Entity<MultiBuffer>, Range<Anchor>)> {
+        let editor = workspace.active_item(cx)?.act_as::<Editor>(cx)?;
+        let multi_buffer = editor.read(cx).buffer().clone();
+        let buffer = multi_buffer.read(cx).as_singleton()?;
+        let offset = editor.update(cx, |editor, cx| {
+            editor
+                .selections
+                .newest::<MultiBufferOffset>(&editor.display_snapshot(cx))
+                .head()
+        });
+        let buffer = buffer.read(cx);
+        let range = svg_element_at(&buffer.text(), offset.0)?;
+        Some((
+            multi_buffer,
+            buffer.anchor_before(range.start)..buffer.anchor_after(range.end),
+        ))
+    }
+
     fn create_svg_view(
         mode: SvgPreviewMode,
         workspace: &mut Workspace,
@@ -170,45 +6902,524 @@ impl SvgPreviewView {
         cx: &mut Context<Workspace>,
     ) -> Entity<SvgPreviewView> {
         let workspace_handle = workspace.weak_handle();
-        SvgPreviewView::new(mode, buffer, workspace_handle, window, cx)
+        let active_editor = workspace
+            .active_item(cx)
+            .and_then(|item| item.downcast::<Editor>());
+        let view = SvgPreviewView::new(mode, buffer, workspace_handle, window, cx);
+        if let Some(editor) = active_editor {
+            view.update(cx, |view, cx| view.track_editor(editor, window, cx));
+        }
+        view
+    }
+
+    fn create_buffer_subscription(
+        buffer: &Entity<Buffer>,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) -> Subscription {
+        cx.subscribe_in(
+            buffer,
+            window,
+            move |this, buffer, event: &BufferEvent, window, cx| match event {
+                BufferEvent::Edited => {
+                    // An optimization would be of an outdated version of the SVG, while the
+                    // comparison with HEAD is updated when the SVG is rendered again.
+                    if matches!(
+                        this.comparison.current,
+                        Some(Comparison::Optimization { .. })
+                    ) {
+                        this.comparison.task = None;
+                        this.set_comparison(None, window, cx);
+                    }
+                    this.render_image_debounced(window, cx);
+                }
+                BufferEvent::Saved => {
+                    this.render_image(window, cx);
+                }
+                BufferEvent::FileHandleChanged => {
+                    let deleted = buffer
+                        .read(cx)
+                        .file()
+                        .is_some_and(|file| file.disk_state() == DiskState::Deleted);
+                    if deleted {
+                        this.handle_file_deleted(window, cx);
+                    } else {
+                        // The file may have been restored or renamed.
+                        this.render_image(window, cx);
+                    }
+                }
+                _ => {}
+            },
+        )
+    }
+
+    /// Opens the buffers of the files the SVG references, so that their edits re-render it.
+    /// Files outside of the project's worktrees aren't watched.
+    /// Returns the versions of the files referenced by the SVG that are open, sorted by path.
+    fn dependency_versions(&self, cx: &App) -> Vec<(PathBuf, clock::Global)> {
+        let mut versions = self
+            .dependencies
+            .iter()
+            .filter_map(|(path, dependency)| match dependency {
+                Dependency::Open { buffer, .. } => Some((path.clone(), buffer.read(cx).version())),
+                Dependency::Image { .. } | Dependency::Opening(_) => None,
+            })
+            .collect::<Vec<_>>();
+        versions.sort_by(|(a, _), (b, _)| a.cmp(b));
+        versions
+    }
+
+    /// Opens the files referenced by the SVG, so that their edits re-render it. In remote
+    /// projects, the images it references are opened too, as they can't be read from disk, and
+    /// the SVG is rendered again once they're open.
+    fn watch_dependencies(
+        &mut self,
+        paths: &[PathBuf],
+        image_paths: &[PathBuf],
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            self.dependencies.clear();
+            return;
+        };
+        let is_remote = !project.read(cx).is_local();
+        let image_paths = if is_remote { image_paths } else { &[] };
+        self.dependencies
+            .retain(|path, _| paths.contains(path) || image_paths.contains(path));
+        for path in paths {
+            if self.dependencies.contains_key(path) {
+                continue;
+            }
+            let Some(project_path) = project.read(cx).find_project_path(path, cx) else {
+                continue;
+            };
+            let open_buffer =
+                project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+            let task = cx.spawn_in(window, {
+                let path = path.clone();
+                async move |this, cx| {
+                    let Ok(buffer) = open_buffer.await else {
+                        return;
+                    };
+                    this.update_in(cx, |this, window, cx| {
+                        if let Some(dependency) = this.dependencies.get_mut(&path) {
+                            let subscription =
+                                Self::create_buffer_subscription(&buffer, window, cx);
+                            *dependency = Dependency::Open {
+                                buffer,
+                                _subscription: subscription,
+                            };
+                            if is_remote {
+                                this.render_image(window, cx);
+                            }
+                        }
+                    })
+                    .ok();
+                }
+            });
+            self.dependencies
+                .insert(path.clone(), Dependency::Opening(task));
+        }
+        for path in image_paths {
+            if self.dependencies.contains_key(path) {
+                continue;
+            }
+            let Some(project_path) = project.read(cx).find_project_path(path, cx) else {
+                continue;
+            };
+            let open_image = project.update(cx, |project, cx| project.open_image(project_path, cx));
+            let task = cx.spawn_in(window, {
+                let path = path.clone();
+                async move |this, cx| {
+                    let Ok(image) = open_image.await else {
+                        return;
+                    };
+                    this.update_in(cx, |this, window, cx| {
+                        if let Some(dependency) = this.dependencies.get_mut(&path) {
+                            let subscription = cx.subscribe_in(
+                                &image,
+                                window,
+                                |this, _, event: &ImageItemEvent, window, cx| {
+                                    if let ImageItemEvent::Reloaded = event {
+                                        // Images aren't among the inputs that the parsed tree
+                                        // is reused for.
+                                        this.parsed_inputs = None;
+                                        this.render_image(window, cx);
+                                    }
+                                },
+                            );
+                            *dependency = Dependency::Image {
+                                image,
+                                _subscription: subscription,
+                            };
+                            this.parsed_inputs = None;
+                            this.render_image(window, cx);
+                        }
+                    })
+                    .ok();
+                }
+            });
+            self.dependencies
+                .insert(path.clone(), Dependency::Opening(task));
+        }
+    }
+
+    fn open_preview_to_the_side(
+        workspace: &mut Workspace,
+        buffer: Entity<MultiBuffer>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let view = Self::create_svg_view(
+            Self::default_mode(cx),
+            workspace,
+            buffer.clone(),
+            window,
+            cx,
+        );
+        let pane = workspace
+            .find_pane_in_direction(workspace::SplitDirection::Right, cx)
+            .unwrap_or_else(|| {
+                workspace.split_pane(
+                    workspace.active_pane().clone(),
+                    workspace::SplitDirection::Right,
+                    window,
+                    cx,
+                )
+            });
+        pane.update(cx, |pane, cx| {
+            if let Some(existing_view_idx) = Self::find_existing_preview_item_idx(pane, &buffer, cx)
+                && let Some(existing_view) = pane
+                    .item_for_index(existing_view_idx)
+                    .and_then(|item| item.downcast::<Self>())
+            {
+                pane.activate_item(existing_view_idx, true, true, window, cx);
+                existing_view
+            } else {
+                pane.add_item(Box::new(view.clone()), false, false, None, window, cx);
+                view
+            }
+        })
+    }
+
+    /// Closes the preview of the active SVG editor if it's visible and reveals it if it's hidden
+    /// behind other items of its pane. Otherwise opens a preview of it to the side. Toggling while
+    /// a preview is active closes it.
+    fn toggle_preview(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+        let (preview, is_visible) =
+            if let Some(preview) = workspace.active_item_as::<SvgPreviewView>(cx) {
+                (preview, true)
+            } else {
+                let Some(buffer) = Self::resolve_active_item_as_svg_buffer(workspace, cx) else {
+                    return;
+                };
+                let singleton = buffer.read(cx).as_singleton();
+                let Some(preview) = workspace
+                    .items_of_type::<SvgPreviewView>(cx)
+                    .find(|preview| preview.read(cx).buffer == singleton)
+                else {
+                    Self::open_preview_to_the_side(workspace, buffer, window, cx);
+                    return;
+                };
+                let is_visible = workspace.panes().iter().any(|pane| {
+                    pane.read(cx)
+                        .active_item()
+                        .is_some_and(|item| item.item_id() == preview.item_id())
+                });
+                (preview, is_visible)
+            };
+        let Some(pane) = workspace.pane_for(&preview) else {
+            return;
+        };
+        pane.update(cx, |pane, cx| {
+            if is_visible {
+                pane.close_item_by_id(preview.item_id(), SaveIntent::Skip, window, cx)
+                    .detach_and_log_err(cx);
+            } else if let Some(index) = pane.index_for_item(&preview) {
+                pane.activate_item(index, false, false, window, cx);
+            }
+        });
+    }
+
+    /// Opens the SVG markup on the clipboard in a new scratch buffer, with a preview of it to the
+    /// side.
+    fn preview_clipboard(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(text) = cx
+            .read_from_clipboard()
+            .and_then(|item| item.text())
+            .filter(|text| is_svg_markup(text))
+        else {
+            struct PreviewClipboardToast;
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<PreviewClipboardToast>(),
+                    "The clipboard doesn't contain SVG markup",
+                ),
+                cx,
+            );
+            return;
+        };
+        Self::open_scratch_preview(workspace, text, window, cx).detach_and_prompt_err(
+            "Failed to preview the clipboard",
+            window,
+            cx,
+            |_, _, _| None,
+        );
+    }
+
+    /// Opens the SVG of the data URI under the cursor of the active editor in a new scratch
+    /// buffer, with a preview of it to the side. With `write_back`, edits to the scratch buffer
+    /// are encoded back into the data URI.
+    fn preview_data_uri(
+        workspace: &mut Workspace,
+        action: &PreviewDataUri,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let source = workspace
+            .active_item(cx)
+            .and_then(|item| item.act_as::<Editor>(cx))
+            .and_then(|editor| {
+                let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+                let offset = editor.update(cx, |editor, cx| {
+                    editor
+                        .selections
+                        .newest::<MultiBufferOffset>(&editor.display_snapshot(cx))
+                        .head()
+                });
+                let data_uri = svg_data_uri_at(&buffer.read(cx).text(), offset.0)?;
+                Some((buffer, data_uri))
+            });
+        let Some((source_buffer, data_uri)) = source else {
+            struct PreviewDataUriToast;
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<PreviewDataUriToast>(),
+                    "There's no SVG data URI under the cursor",
+                ),
+                cx,
+            );
+            return;
+        };
+
+        let open = Self::open_scratch_preview(workspace, data_uri.svg.clone(), window, cx);
+        let write_back = action.write_back;
+        cx.spawn_in(window, async move |_, cx| {
+            let (scratch_buffer, preview) = open.await?;
+            if write_back {
+                preview.update(cx, |preview, cx| {
+                    preview.write_back_data_uri(&scratch_buffer, &source_buffer, data_uri, cx)
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to preview the data URI", window, cx, |_, _, _| None);
+    }
+
+    /// Opens `text` in a new scratch buffer, with a preview of it to the side.
+    fn open_scratch_preview(
+        workspace: &mut Workspace,
+        text: String,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<anyhow::Result<(Entity<Buffer>, Entity<Self>)>> {
+        let project = workspace.project().clone();
+        let create = project.update(cx, |project, cx| project.create_buffer(false, cx));
+        let language = project.read(cx).languages().language_for_name("SVG");
+        cx.spawn_in(window, async move |workspace, cx| {
+            let buffer = create.await?;
+            let language = language.await.ok();
+            workspace.update_in(cx, |workspace, window, cx| {
+                buffer.update(cx, |buffer, cx| {
+                    buffer.set_text(text, cx);
+                    buffer.set_language(language, cx);
+                });
+                let editor = cx.new(|cx| {
+                    Editor::for_buffer(buffer.clone(), Some(project.clone()), window, cx)
+                });
+                workspace.add_item_to_active_pane(Box::new(editor.clone()), None, true, window, cx);
+                let multi_buffer = editor.read(cx).buffer().clone();
+                let preview = Self::open_preview_to_the_side(workspace, multi_buffer, window, cx);
+                (buffer, preview)
+            })
+        })
+    }
+
+    /// Encodes the edits to `scratch_buffer` back into `data_uri` in `source_buffer`, once they
+    /// pause for the render debounce, for as long as the preview is open.
+    fn write_back_data_uri(
+        &mut self,
+        scratch_buffer: &Entity<Buffer>,
+        source_buffer: &Entity<Buffer>,
+        data_uri: SvgDataUri,
+        cx: &mut Context<Self>,
+    ) {
+        let source = source_buffer.read(cx);
+        let range =
+            source.anchor_before(data_uri.range.start)..source.anchor_after(data_uri.range.end);
+        let written = source.text_for_range(data_uri.range.clone()).collect();
+        let subscription = cx.subscribe(scratch_buffer, |this, _, event: &BufferEvent, cx| {
+            if let BufferEvent::Edited = event {
+                this.schedule_data_uri_write_back(cx);
+            }
+        });
+        self.data_uri_write_back = Some(DataUriWriteBack {
+            scratch_buffer: scratch_buffer.downgrade(),
+            source_buffer: source_buffer.downgrade(),
+            range,
+            data_uri,
+            written,
+            task: Task::ready(()),
+            _subscription: subscription,
+        });
+    }
+
+    fn schedule_data_uri_write_back(&mut self, cx: &mut Context<Self>) {
+        let debounce = Duration::from_millis(SvgPreviewSettings::get_global(cx).render_debounce_ms);
+        let Some(write_back) = self.data_uri_write_back.as_mut() else {
+            return;
+        };
+        write_back.task = cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(debounce).await;
+            this.update(cx, |this, cx| this.flush_data_uri_write_back(cx))
+                .ok();
+        });
+    }
+
+    /// Writes the SVG of the scratch buffer into the data URI. Stops writing back if either buffer
+    /// is gone or the URI no longer reads as it was last written.
+    fn flush_data_uri_write_back(&mut self, cx: &mut Context<Self>) {
+        let Some(mut write_back) = self.data_uri_write_back.take() else {
+            return;
+        };
+        let (Some(scratch_buffer), Some(source_buffer)) = (
+            write_back.scratch_buffer.upgrade(),
+            write_back.source_buffer.upgrade(),
+        ) else {
+            return;
+        };
+        let current: String = source_buffer
+            .read(cx)
+            .text_for_range(write_back.range.clone())
+            .collect();
+        if current != write_back.written {
+            return;
+        }
+        let uri = write_back
+            .data_uri
+            .with_svg(&scratch_buffer.read(cx).text());
+        source_buffer.update(cx, |buffer, cx| {
+            buffer.edit([(write_back.range.clone(), uri.clone())], None, cx)
+        });
+        write_back.written = uri;
+        self.data_uri_write_back = Some(write_back);
+    }
+
+    /// Returns the mode of the previews that the preview actions open.
+    fn default_mode(cx: &App) -> SvgPreviewMode {
+        match SvgPreviewSettings::get_global(cx).default_mode {
+            SvgPreviewDefaultMode::Pinned => SvgPreviewMode::Default,
+            SvgPreviewDefaultMode::Follow => SvgPreviewMode::Follow,
+        }
+    }
+
+    /// Opens a preview for a newly opened SVG editor, as configured by the `auto_open` setting,
+    /// unless the SVG already has one.
+    fn auto_open(
+        workspace: &mut Workspace,
+        item: &dyn ItemHandle,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let auto_open = SvgPreviewSettings::get_global(cx).auto_open;
+        if auto_open == SvgPreviewAutoOpen::Off {
+            return;
+        }
+        let Some(editor) = item.act_as::<Editor>(cx) else {
+            return;
+        };
+        let buffer = editor.read(cx).buffer().clone();
+        if !Self::is_svg_file(&buffer, cx) {
+            return;
+        }
+        let singleton = buffer.read(cx).as_singleton();
+        if workspace
+            .items_of_type::<SvgPreviewView>(cx)
+            .any(|preview| preview.read(cx).buffer == singleton)
+        {
+            return;
+        }
+
+        match auto_open {
+            SvgPreviewAutoOpen::Off => {}
+            SvgPreviewAutoOpen::SamePane => {
+                let Some(pane) = workspace.pane_for(item) else {
+                    return;
+                };
+                let view =
+                    Self::create_svg_view(Self::default_mode(cx), workspace, buffer, window, cx);
+                pane.update(cx, |pane, cx| {
+                    pane.add_item(Box::new(view), false, false, None, window, cx)
+                });
+            }
+            SvgPreviewAutoOpen::SplitRight => {
+                Self::open_preview_to_the_side(workspace, buffer, window, cx);
+            }
+        }
+    }
+
+    /// Whether `buffer` can be previewed: an SVG file, or an untitled buffer that's set to the SVG
+    /// or XML language or whose content starts with an `<svg>` element. With the
+    /// `detect_by_content` setting, files of any other name whose content starts with an `<svg>`
+    /// element can be previewed too.
+    pub fn is_svg_file(buffer: &Entity<MultiBuffer>, cx: &App) -> bool {
+        let Some(buffer) = buffer.read(cx).as_singleton() else {
+            return false;
+        };
+        let buffer = buffer.read(cx);
+        let starts_with_svg =
+            || starts_with_svg_root(&buffer.chars().take(SVG_SNIFF_LEN).collect::<String>());
+        match buffer.file() {
+            Some(file) => {
+                let path = file.path().as_std_path();
+                path.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"))
+                    || is_svgz_path(path)
+                    || (SvgPreviewSettings::get_global(cx).detect_by_content && starts_with_svg())
+            }
+            None => {
+                let is_svg_language = buffer.language().is_some_and(|language| {
+                    language.name() == "SVG".into() || language.name() == "XML".into()
+                });
+                is_svg_language || starts_with_svg()
+            }
+        }
     }
 
-    fn create_buffer_subscription(
-        buffer: &Entity<Buffer>,
-        window: &Window,
-        cx: &mut Context<Self>,
-    ) -> Subscription {
+    pub fn register(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+        let workspace_entity = cx.entity();
         cx.subscribe_in(
-            buffer,
+            &workspace_entity,
             window,
-            move |this, _buffer, event: &BufferEvent, window, cx| match event {
-                BufferEvent::Edited | BufferEvent::Saved => {
-                    this.render_image(window, cx);
+            |workspace, _, event: &workspace::Event, window, cx| {
+                if let workspace::Event::ItemAdded { item } = event {
+                    Self::auto_open(workspace, item.as_ref(), window, cx);
                 }
-                _ => {}
             },
         )
-    }
-
-    pub fn is_svg_file(buffer: &Entity<MultiBuffer>, cx: &App) -> bool {
-        buffer
-            .read(cx)
-            .as_singleton()
-            .and_then(|buffer| buffer.read(cx).file())
-            .is_some_and(|file| {
-                file.path()
-                    .extension()
-                    .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"))
-            })
-    }
+        .detach();
 
-    pub fn register(workspace: &mut Workspace, _window: &mut Window, _cx: &mut Context<Workspace>) {
         workspace.register_action(move |workspace, _: &OpenPreview, window, cx| {
             if let Some(buffer) = Self::resolve_active_item_as_svg_buffer(workspace, cx)
                 && Self::is_svg_file(&buffer, cx)
             {
                 let view = Self::create_svg_view(
-                    SvgPreviewMode::Default,
+                    Self::default_mode(cx),
                     workspace,
                     buffer.clone(),
                     window,
@@ -224,44 +7435,51 @@ impl SvgPreviewView {
                     }
                 });
                 cx.notify();
+            } else if let Some((buffer, range)) = Self::resolve_embedded_svg(workspace, cx) {
+                let view =
+                    Self::create_svg_view(SvgPreviewMode::Default, workspace, buffer, window, cx);
+                view.update(cx, |view, cx| view.set_embedded_range(range, window, cx));
+                workspace.active_pane().update(cx, |pane, cx| {
+                    pane.add_item(Box::new(view), true, true, None, window, cx)
+                });
+                cx.notify();
             }
         });
 
         workspace.register_action(move |workspace, _: &OpenPreviewToTheSide, window, cx| {
-            if let Some(editor) = Self::resolve_active_item_as_svg_buffer(workspace, cx)
-                && Self::is_svg_file(&editor, cx)
+            if let Some(buffer) = Self::resolve_active_item_as_svg_buffer(workspace, cx)
+                && Self::is_svg_file(&buffer, cx)
             {
-                let editor_clone = editor.clone();
-                let view = Self::create_svg_view(
-                    SvgPreviewMode::Default,
-                    workspace,
-                    editor_clone,
-                    window,
-                    cx,
-                );
-                let pane = workspace
-                    .find_pane_in_direction(workspace::SplitDirection::Right, cx)
-                    .unwrap_or_else(|| {
-                        workspace.split_pane(
-                            workspace.active_pane().clone(),
-                            workspace::SplitDirection::Right,
-                            window,
-                            cx,
-                        )
-                    });
-                pane.update(cx, |pane, cx| {
-                    if let Some(existing_view_idx) =
-                        Self::find_existing_preview_item_idx(pane, &editor, cx)
-                    {
-                        pane.activate_item(existing_view_idx, true, true, window, cx);
-                    } else {
-                        pane.add_item(Box::new(view), false, false, None, window, cx)
-                    }
+                Self::open_preview_to_the_side(workspace, buffer, window, cx);
+                cx.notify();
+            }
+        });
+
+        workspace.register_action(move |workspace, _: &TogglePreview, window, cx| {
+            Self::toggle_preview(workspace, window, cx);
+        });
+
+        workspace.register_action(move |workspace, _: &OpenAdditionalPreview, window, cx| {
+            if let Some(buffer) = Self::resolve_active_item_as_svg_buffer(workspace, cx)
+                && Self::is_svg_file(&buffer, cx)
+            {
+                let view =
+                    Self::create_svg_view(SvgPreviewMode::Default, workspace, buffer, window, cx);
+                workspace.active_pane().update(cx, |pane, cx| {
+                    pane.add_item(Box::new(view), true, true, None, window, cx)
                 });
                 cx.notify();
             }
         });
 
+        workspace.register_action(move |workspace, _: &PreviewClipboard, window, cx| {
+            Self::preview_clipboard(workspace, window, cx);
+        });
+
+        workspace.register_action(move |workspace, action: &PreviewDataUri, window, cx| {
+            Self::preview_data_uri(workspace, action, window, cx);
+        });
+
         workspace.register_action(move |workspace, _: &OpenFollowingPreview, window, cx| {
             if let Some(editor) = Self::resolve_active_item_as_svg_buffer(workspace, cx)
                 && Self::is_svg_file(&editor, cx)
@@ -277,31 +7495,349 @@ impl SvgPreviewView {
     }
 }
 
+/// Hashes the inputs of a rendering of the SVG, so that tiles of identical renderings can be
+/// reused from the [`TileCache`].
+fn content_hash(
+    text: &str,
+    external_resources: &ExternalResources,
+    fonts: Option<&Arc<SvgFonts>>,
+    viewport: Option<Size<f32>>,
+) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    external_resources.hash_contents(&mut hasher);
+    fonts.map(Arc::as_ptr).hash(&mut hasher);
+    viewport
+        .map(|viewport| (viewport.width.to_bits(), viewport.height.to_bits()))
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns the color that `background` is drawn with.
+pub(crate) fn resolve_background(background: &SvgPreviewBackground, cx: &App) -> Hsla {
+    let theme_background = cx.theme().colors().editor_background;
+    match background {
+        SvgPreviewBackground::Theme => theme_background,
+        SvgPreviewBackground::White => gpui::white(),
+        SvgPreviewBackground::Black => gpui::black(),
+        SvgPreviewBackground::Custom(color) => Rgba::try_from(color.as_str())
+            .map(Hsla::from)
+            .unwrap_or(theme_background),
+    }
+}
+
+/// Renders the entry of the sprite `text` with `id` on its own.
+fn render_sprite_entry(
+    text: &str,
+    id: &str,
+    options: &usvg::Options,
+    renderer: &SvgRenderer,
+    length: f32,
+) -> Option<Arc<RenderImage>> {
+    let entry = sprite::entry_svg(text, id)?;
+    let tree = usvg::Tree::from_str(&entry, options).ok()?;
+    let svg_size = size(tree.size().width(), tree.size().height());
+    render_whole_svg(renderer, &tree, svg_size, length).ok()
+}
+
+/// Renders the whole SVG so that its longer side is `length` device pixels long.
+pub(crate) fn render_whole_svg(
+    renderer: &SvgRenderer,
+    tree: &usvg::Tree,
+    svg_size: Size<f32>,
+    length: f32,
+) -> Result<Arc<RenderImage>, usvg::Error> {
+    let scale = length / svg_size.width.max(svg_size.height);
+    renderer.render_region(
+        tree,
+        point(0., 0.),
+        tiles::image_size(svg_size, scale),
+        scale,
+        true,
+    )
+}
+
+/// Adds the entries for exporting the SVG to `menu`, offering the element with the given id for
+/// exporting on its own.
+fn export_menu_entries(
+    menu: ContextMenu,
+    element_id: Option<String>,
+    preset_names: Vec<String>,
+) -> ContextMenu {
+    menu.action("Export PNG…", Box::new(ExportPng))
+        .action("Export JPEG…", Box::new(ExportJpeg))
+        .action("Export WebP…", Box::new(ExportWebp))
+        .action("Export PNG Set…", Box::new(ExportPngSet))
+        .action("Export ICO…", Box::new(ExportIco))
+        .action("Export ICNS…", Box::new(ExportIcns))
+        .action("Export PDF…", Box::new(ExportPdf))
+        .action(
+            match &element_id {
+                Some(id) => format!("Export Element #{id}…"),
+                None => "Export Element…".to_string(),
+            },
+            Box::new(ExportElement { id: element_id }),
+        )
+        .map(|mut menu| {
+            for name in preset_names {
+                menu = menu.action(
+                    format!("Export ({name})"),
+                    Box::new(ExportWithPreset { preset: Some(name) }),
+                );
+            }
+            menu
+        })
+}
+
+fn zoom_percentage(scale_factor: f32) -> f32 {
+    (scale_factor * 100.).round()
+}
+
+/// Parses a user-entered zoom level such as `150`, `150%` or `37.5 %` into a scale factor.
+fn parse_zoom_percentage(text: &str) -> Option<f32> {
+    let percentage = text
+        .trim()
+        .trim_end_matches('%')
+        .trim_end()
+        .parse::<f32>()
+        .ok()?;
+    (percentage.is_finite() && percentage > 0.).then_some(percentage / 100.)
+}
+
+/// Parses the document shown at `time` on the animation timeline.
+fn animation_snapshot_tree(
+    animations: &Animations,
+    time: f32,
+    renderer: &SvgRenderer,
+    external_resources: &Arc<ExternalResources>,
+    fonts: Option<&SvgFonts>,
+    simulated_viewport: Option<Size<f32>>,
+) -> anyhow::Result<usvg::Tree> {
+    let snapshot = animations.snapshot(time)?;
+    let mut options = external_resources.usvg_options(renderer, fonts);
+    apply_simulated_viewport(&mut options, simulated_viewport);
+    Ok(usvg::Tree::from_data(snapshot.as_bytes(), &options)?)
+}
+
+/// Returns the offset closest to `offset` that keeps at least [`MIN_VISIBLE_IMAGE_SIZE`] of an
+/// image centered in the viewport visible along each axis.
+fn clamp_image_offset(
+    offset: Point<Pixels>,
+    image_size: Size<Pixels>,
+    viewport_size: Size<Pixels>,
+) -> Point<Pixels> {
+    let clamp = |offset: Pixels, image_length: Pixels, viewport_length: Pixels| {
+        let max_offset = ((viewport_length + image_length) / 2.
+            - MIN_VISIBLE_IMAGE_SIZE.min(image_length))
+        .max(px(0.));
+        offset.clamp(-max_offset, max_offset)
+    };
+    point(
+        clamp(offset.x, image_size.width, viewport_size.width),
+        clamp(offset.y, image_size.height, viewport_size.height),
+    )
+}
+
 impl Render for SvgPreviewView {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.hidden = false;
+        if self.render_pending {
+            self.render_image(window, cx);
+        }
+        self.update_theme_inputs(window, cx);
+        if self.animation.frame.is_none() && !self.update_gpu_scene(window, cx) {
+            self.update_tiles(window, cx);
+        }
+        let image_bounds = self.image_bounds();
+        let viewport_origin = self.viewport_bounds.origin;
+
         v_flex()
             .id("SvgPreview")
             .key_context("SvgPreview")
             .track_focus(&self.focus_handle(cx))
+            .on_action(cx.listener(Self::fit_to_window))
+            .on_action(cx.listener(Self::reset_view))
+            .on_action(cx.listener(Self::zoom_in))
+            .on_action(cx.listener(Self::zoom_out))
+            .on_action(cx.listener(Self::zoom_reset))
+            .on_action(cx.listener(Self::zoom))
+            .on_action(cx.listener(Self::pan_left))
+            .on_action(cx.listener(Self::pan_right))
+            .on_action(cx.listener(Self::pan_up))
+            .on_action(cx.listener(Self::pan_down))
+            .on_action(cx.listener(Self::pan))
+            .on_action(cx.listener(Self::toggle_rulers))
+            .on_action(cx.listener(Self::toggle_render_stats))
+            .on_action(cx.listener(Self::toggle_background))
+            .on_action(cx.listener(Self::play_animation))
+            .on_action(cx.listener(Self::pause_animation))
+            .on_action(cx.listener(Self::restart_animation))
+            .on_action(cx.listener(Self::toggle_custom_properties))
+            .on_action(cx.listener(Self::toggle_outline))
+            .on_action(cx.listener(Self::toggle_bounding_boxes))
+            .on_action(cx.listener(Self::toggle_canvas_outline))
+            .on_action(cx.listener(Self::toggle_path_points))
+            .on_action(cx.listener(Self::toggle_measure))
+            .on_action(cx.listener(Self::toggle_eyedropper))
+            .on_action(cx.listener(Self::toggle_hidden_elements))
+            .on_action(cx.listener(Self::toggle_gallery))
+            .on_action(cx.listener(Self::audit_accessibility))
+            .on_action(cx.listener(Self::optimize_svg))
+            .on_action(cx.listener(Self::clean_svg))
+            .on_action(cx.listener(Self::show_optimization_report))
+            .on_action(cx.listener(Self::compare_with_head))
+            .on_action(cx.listener(Self::toggle_pinned))
+            .on_action(cx.listener(Self::open_source))
+            .on_action(cx.listener(Self::find_element))
+            .on_action(cx.listener(Self::toggle_color_scheme))
+            .on_action(cx.listener(Self::export_png))
+            .on_action(cx.listener(Self::export_jpeg))
+            .on_action(cx.listener(Self::export_webp))
+            .on_action(cx.listener(Self::export_png_set))
+            .on_action(cx.listener(Self::export_ico))
+            .on_action(cx.listener(Self::export_icns))
+            .on_action(cx.listener(Self::export_pdf))
+            .on_action(cx.listener(Self::export_element))
+            .on_action(cx.listener(Self::export_with_preset))
+            .on_action(cx.listener(Self::copy_image))
+            .on_scroll_wheel(cx.listener(Self::handle_scroll_wheel))
+            .on_pinch(cx.listener(Self::handle_pinch))
+            .on_mouse_down(MouseButton::Left, cx.listener(Self::handle_mouse_down))
+            .on_mouse_down(MouseButton::Right, cx.listener(Self::deploy_context_menu))
+            .on_mouse_move(cx.listener(Self::handle_mouse_move))
+            .on_hover(cx.listener(|this, hovered: &bool, _, cx| {
+                if !hovered {
+                    this.pointer_position = None;
+                    this.hovered_element = None;
+                    cx.notify();
+                }
+            }))
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, event: &MouseUpEvent, _, cx| {
+                    this.drop_guide(event.position, cx);
+                    this.drag_start = None;
+                    this.dragging_minimap = false;
+                    this.measure.dragging = false;
+                    this.dragging_scrubber = false;
+                    this.export.dragging_quality_slider = false;
+                    this.comparison.dragging_swipe_divider = false;
+                }),
+            )
             .size_full()
-            .bg(cx.theme().colors().editor_background)
+            .relative()
+            .overflow_hidden()
+            .bg(self.background_color(cx))
             .flex()
             .justify_center()
             .items_center()
-            .map(|this| match self.current_svg.clone() {
-                Some(Ok(image)) => {
-                    this.child(img(image).max_w_full().max_h_full().with_fallback(|| {
-                        h_flex()
-                            .p_4()
-                            .gap_2()
-                            .child(Icon::new(IconName::Warning))
-                            .child("Failed to load SVG image")
-                            .into_any_element()
-                    }))
-                }
-                Some(Err(e)) => this.child(div().p_4().child(e).into_any_element()),
-                None => this.child(div().p_4().child("No SVG file selected")),
+            .child({
+                let this = cx.entity();
+                canvas(
+                    move |bounds, _window, cx| {
+                        this.update(cx, |this, cx| this.set_viewport_bounds(bounds, cx))
+                    },
+                    |_, _, _, _| {},
+                )
+                .absolute()
+                .size_full()
+            })
+            .map(|this| match (self.current_svg.clone(), image_bounds) {
+                (Some(Ok(image)), Some(image_bounds)) => this
+                    .when(self.show_canvas_outline, |this| {
+                        this.child(self.render_canvas_shadow(image_bounds, cx))
+                    })
+                    .child(
+                        // While animating, the frame covers the whole SVG in place of the tiles.
+                        img(self.animation.frame.clone().unwrap_or(image))
+                            .object_fit(ObjectFit::Fill)
+                            .absolute()
+                            .left(image_bounds.origin.x - viewport_origin.x)
+                            .top(image_bounds.origin.y - viewport_origin.y)
+                            .w(image_bounds.size.width)
+                            .h(image_bounds.size.height)
+                            .with_fallback(|| {
+                                h_flex()
+                                    .p_4()
+                                    .gap_2()
+                                    .child(Icon::new(IconName::Warning))
+                                    .child("Failed to load SVG image")
+                                    .into_any_element()
+                            }),
+                    )
+                    .when(self.animation.frame.is_none(), |this| {
+                        match self.render_gpu_scene(image_bounds) {
+                            Some(gpu_scene) => this.child(gpu_scene),
+                            None => this.children(self.render_tiles(image_bounds)),
+                        }
+                    })
+                    .children(self.render_hidden_elements(image_bounds, cx))
+                    .when(self.show_canvas_outline, |this| {
+                        this.children(self.render_canvas_outline(image_bounds, cx))
+                    })
+                    .when(self.show_bounding_boxes, |this| {
+                        this.children(self.render_bounding_boxes(image_bounds, cx))
+                    })
+                    .children(self.render_cursor_highlight(image_bounds, cx))
+                    .when(self.show_path_points, |this| {
+                        this.children(self.render_path_points(image_bounds, cx))
+                    })
+                    .children(self.render_flashed_bounds(image_bounds, cx))
+                    .children(self.render_measurement(image_bounds, cx))
+                    .children(self.render_color_sample(image_bounds, cx)),
+                (Some(Ok(_)), None) => this,
+                (Some(Err(e)), _) => this.child(div().p_4().child(e).into_any_element()),
+                (None, _) => match self.large_svg {
+                    Some(large_svg) => this.child(self.render_large_svg_prompt(large_svg, cx)),
+                    None => this.child(div().p_4().child(match self.mode {
+                        SvgPreviewMode::Default => "No SVG file selected",
+                        SvgPreviewMode::Follow => "Waiting for an SVG editor…",
+                    })),
+                },
+            })
+            .when(self.show_rulers, |this| {
+                this.children(self.render_guides(cx))
+                    .children(self.render_rulers(cx))
+            })
+            .children(self.render_hovered_element(cx))
+            .children(self.render_minimap(cx))
+            .when(self.animation.animations.is_some(), |this| {
+                this.child(self.render_animation_controls(cx))
+            })
+            .children(self.render_warnings(cx))
+            .children(self.render_export_input(cx))
+            .children(self.render_export_element_input(cx))
+            .when(self.show_outline, |this| {
+                this.child(self.render_outline_panel(cx))
+            })
+            .when(self.show_custom_properties, |this| {
+                this.child(self.render_custom_properties_panel(cx))
+            })
+            .when(self.show_accessibility_audit, |this| {
+                this.child(self.render_accessibility_panel(cx))
             })
+            .when(self.optimization_report.shown, |this| {
+                this.child(self.render_optimization_report(cx))
+            })
+            .when(
+                self.gallery.shown && !self.gallery.tiles.is_empty(),
+                |this| this.child(self.render_gallery(cx)),
+            )
+            .children(self.render_comparison(cx))
+            .children(self.render_inactive_indicator(cx))
+            .children(self.render_render_stats(cx))
+            .when(self.svg_size.is_some(), |this| {
+                this.children(self.render_pointer_position(cx))
+            })
+            .children(self.context_menu.as_ref().map(|(menu, position, _)| {
+                deferred(
+                    anchored()
+                        .position(*position)
+                        .anchor(Corner::TopLeft)
+                        .child(menu.clone()),
+                )
+                .with_priority(1)
+            }))
     }
 }
 
@@ -311,12 +7847,42 @@ impl Focusable for SvgPreviewView {
     }
 }
 
-impl EventEmitter<()> for SvgPreviewView {}
+/// The events of an SVG preview.
+pub enum SvgPreviewEvent {
+    /// The SVG the preview shows, its mode or its zoom or position changed.
+    ViewChanged,
+    /// The thumbnail shown in the preview's tab changed.
+    ThumbnailChanged,
+    /// A control shown in the preview's toolbar or status bar item changed, such as a toggled
+    /// tool or the zoom level input.
+    ControlsChanged,
+}
+
+impl EventEmitter<SvgPreviewEvent> for SvgPreviewView {}
 
 impl Item for SvgPreviewView {
-    type Event = ();
+    type Event = SvgPreviewEvent;
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        h_flex()
+            .gap_1()
+            .children(self.thumbnail.clone().map(|thumbnail| {
+                img(thumbnail)
+                    .size(IconSize::Small.rems())
+                    .object_fit(ObjectFit::Contain)
+            }))
+            .child(
+                Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+                    .color(params.text_color()),
+            )
+            .into_any_element()
+    }
 
     fn tab_icon(&self, _window: &Window, cx: &App) -> Option<Icon> {
+        // The thumbnail in the tab content takes the place of the icon.
+        if self.thumbnail.is_some() {
+            return None;
+        }
         self.buffer
             .as_ref()
             .and_then(|buffer| buffer.read(cx).file())
@@ -337,5 +7903,559 @@ impl Item for SvgPreviewView {
         Some("svg preview: open")
     }
 
-    fn to_item_events(_event: &Self::Event, _f: impl FnMut(workspace::item::ItemEvent)) {}
+    fn deactivated(&mut self, _window: &mut Window, _cx: &mut Context<Self>) {
+        self.hidden = true;
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(workspace::item::ItemEvent)) {
+        if let SvgPreviewEvent::ThumbnailChanged = event {
+            f(workspace::item::ItemEvent::UpdateTab);
+        }
+    }
+}
+
+impl FollowableItem for SvgPreviewView {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, _window: &Window, cx: &App) -> Option<proto::view::Variant> {
+        Some(proto::view::Variant::SvgPreview(self.follow_state(cx)))
+    }
+
+    fn from_state_proto(
+        workspace: Entity<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Entity<Self>>>> {
+        let Some(proto::view::Variant::SvgPreview(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::SvgPreview(state)) = state.take() else {
+            unreachable!()
+        };
+        let project = workspace.read(cx).project().clone();
+        let open_buffer = open_followed_buffer(&project, state.buffer_id, cx);
+        let workspace = workspace.downgrade();
+        Some(window.spawn(cx, async move |cx| {
+            let buffer = open_buffer.await?;
+            cx.update(|window, cx| {
+                let multi_buffer = cx.new(|cx| match buffer.clone() {
+                    Some(buffer) => MultiBuffer::singleton(buffer, cx),
+                    None => MultiBuffer::new(Capability::ReadWrite),
+                });
+                let view = SvgPreviewView::new(
+                    SvgPreviewMode::Default,
+                    multi_buffer,
+                    workspace,
+                    window,
+                    cx,
+                );
+                view.update(cx, |view, cx| {
+                    view.remote_id = Some(remote_id);
+                    view.apply_follow_state(&state, buffer, window, cx);
+                });
+                view
+            })
+        }))
+    }
+
+    fn to_follow_event(_event: &Self::Event) -> Option<workspace::item::FollowEvent> {
+        None
+    }
+
+    fn add_event_to_update_proto(
+        &self,
+        event: &Self::Event,
+        update: &mut Option<proto::update_view::Variant>,
+        _window: &Window,
+        cx: &App,
+    ) -> bool {
+        match event {
+            SvgPreviewEvent::ViewChanged => {
+                *update = Some(proto::update_view::Variant::SvgPreview(
+                    self.follow_state(cx),
+                ));
+                true
+            }
+            SvgPreviewEvent::ThumbnailChanged | SvgPreviewEvent::ControlsChanged => false,
+        }
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        project: &Entity<Project>,
+        message: proto::update_view::Variant,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let proto::update_view::Variant::SvgPreview(state) = message else {
+            return Task::ready(Ok(()));
+        };
+        let open_buffer = open_followed_buffer(project, state.buffer_id, cx);
+        cx.spawn_in(window, async move |this, cx| {
+            let buffer = open_buffer.await?;
+            this.update_in(cx, |this, window, cx| {
+                this.apply_follow_state(&state, buffer, window, cx)
+            })
+        })
+    }
+
+    fn is_project_item(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn set_leader_id(
+        &mut self,
+        _leader_id: Option<CollaboratorId>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) {
+    }
+
+    fn dedup(&self, _existing: &Self, _window: &Window, _cx: &App) -> Option<Dedup> {
+        None
+    }
+}
+
+/// Opens the buffer that a followed collaborator's preview shows, given its remote id.
+fn open_followed_buffer(
+    project: &Entity<Project>,
+    buffer_id: Option<u64>,
+    cx: &mut App,
+) -> Task<anyhow::Result<Option<Entity<Buffer>>>> {
+    let Some(buffer_id) = buffer_id else {
+        return Task::ready(Ok(None));
+    };
+    let buffer_id = match BufferId::new(buffer_id) {
+        Ok(buffer_id) => buffer_id,
+        Err(error) => return Task::ready(Err(error)),
+    };
+    let open_buffer = project.update(cx, |project, cx| project.open_buffer_by_id(buffer_id, cx));
+    cx.spawn(async move |_| Ok(Some(open_buffer.await?)))
+}
+
+impl workspace::item::ProjectItem for SvgPreviewView {
+    type Item = SvgzFile;
+
+    fn for_project_item(
+        _project: Entity<Project>,
+        _pane: Option<&Pane>,
+        item: Entity<Self::Item>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let buffer = item.read(cx).buffer.clone();
+        let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+        let workspace = window
+            .root::<Workspace>()
+            .flatten()
+            .map(|workspace| workspace.downgrade())
+            .unwrap_or_default();
+        Self::build(SvgPreviewMode::Default, buffer, workspace, window, cx)
+    }
+}
+
+impl SerializableItem for SvgPreviewView {
+    fn serialized_item_kind() -> &'static str {
+        "SvgPreview"
+    }
+
+    fn deserialize(
+        project: Entity<Project>,
+        workspace: WeakEntity<Workspace>,
+        workspace_id: WorkspaceId,
+        item_id: ItemId,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<anyhow::Result<Entity<Self>>> {
+        window.spawn(cx, async move |cx| {
+            let preview = SVG_PREVIEW
+                .get_svg_preview(item_id, workspace_id)?
+                .context("No SVG preview found")?;
+
+            let buffer = match preview.svg_path {
+                Some(svg_path) if is_svgz_path(&svg_path) => {
+                    let path = project
+                        .read_with(cx, |project, cx| project.find_project_path(&svg_path, cx))?
+                        .with_context(|| format!("{svg_path:?} is not in the project"))?;
+                    Some(
+                        cx.update(|_, cx| open_svgz_buffer(&project, path, cx))?
+                            .await?,
+                    )
+                }
+                Some(svg_path) => Some(
+                    project
+                        .update(cx, |project, cx| project.open_local_buffer(svg_path, cx))?
+                        .await?,
+                ),
+                None => None,
+            };
+
+            cx.update(|window, cx| {
+                let buffer = cx.new(|cx| match buffer {
+                    Some(buffer) => MultiBuffer::singleton(buffer, cx),
+                    None => MultiBuffer::new(Capability::ReadWrite),
+                });
+                let mode = if preview.following {
+                    SvgPreviewMode::Follow
+                } else {
+                    SvgPreviewMode::Default
+                };
+                let view = SvgPreviewView::new(mode, buffer, workspace, window, cx);
+                view.update(cx, |view, cx| {
+                    view.scale_factor = preview.scale_factor;
+                    view.image_offset = point(px(preview.offset_x), px(preview.offset_y));
+                    view.fit_to_window = preview.fit_to_window;
+                    cx.notify();
+                });
+                view
+            })
+        })
+    }
+
+    fn cleanup(
+        workspace_id: WorkspaceId,
+        alive_items: Vec<ItemId>,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Task<anyhow::Result<()>> {
+        delete_unloaded_items(alive_items, workspace_id, "svg_previews", &SVG_PREVIEW, cx)
+    }
+
+    fn serialize(
+        &mut self,
+        workspace: &mut Workspace,
+        item_id: ItemId,
+        _closing: bool,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<anyhow::Result<()>>> {
+        // Embedded SVGs can't be found again once the buffer is reloaded.
+        if self.embedded_range.is_some() {
+            return None;
+        }
+        let workspace_id = workspace.database_id()?;
+        let preview = self.serialized(cx);
+
+        Some(cx.background_spawn(async move {
+            SVG_PREVIEW
+                .save_svg_preview(item_id, workspace_id, preview)
+                .await
+        }))
+    }
+
+    fn should_serialize(&self, event: &Self::Event) -> bool {
+        matches!(event, SvgPreviewEvent::ViewChanged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::FakeFs;
+    use util::path;
+    use workspace::{AppState, OpenOptions};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_zoom_percentage() {
+        assert_eq!(parse_zoom_percentage("150"), Some(1.5));
+        assert_eq!(parse_zoom_percentage(" 150% "), Some(1.5));
+        assert_eq!(parse_zoom_percentage("37.5 %"), Some(0.375));
+        assert_eq!(parse_zoom_percentage("0"), None);
+        assert_eq!(parse_zoom_percentage("-20%"), None);
+        assert_eq!(parse_zoom_percentage("abc"), None);
+        assert_eq!(parse_zoom_percentage(""), None);
+    }
+
+    #[test]
+    fn test_clamp_image_offset() {
+        let image_size = size(px(100.), px(10.));
+        let viewport_size = size(px(200.), px(200.));
+        assert_eq!(
+            clamp_image_offset(point(px(20.), px(-30.)), image_size, viewport_size),
+            point(px(20.), px(-30.))
+        );
+        assert_eq!(
+            clamp_image_offset(point(px(500.), px(-500.)), image_size, viewport_size),
+            point(px(102.), px(-95.))
+        );
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+
+    fn svg_of_size(width: u32, height: u32) -> String {
+        format!(r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}"/>"#)
+    }
+
+    fn new_preview(
+        mode: SvgPreviewMode,
+        buffer: Option<&Entity<Buffer>>,
+        workspace: &Entity<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> Entity<SvgPreviewView> {
+        workspace.update_in(cx, |workspace, window, cx| {
+            let buffer = cx.new(|cx| match buffer {
+                Some(buffer) => MultiBuffer::singleton(buffer.clone(), cx),
+                None => MultiBuffer::new(Capability::ReadWrite),
+            });
+            SvgPreviewView::new(mode, buffer, workspace.weak_handle(), window, cx)
+        })
+    }
+
+    /// Opens `path` in an editor, returning its buffer.
+    async fn open_in_editor(
+        path: &str,
+        workspace: &Entity<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> Entity<Buffer> {
+        let item = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_abs_path(PathBuf::from(path), OpenOptions::default(), window, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        let editor = item.downcast::<Editor>().unwrap();
+        editor.read_with(cx, |editor, cx| {
+            editor.buffer().read(cx).as_singleton().unwrap()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_zoom_around_cursor(cx: &mut TestAppContext) {
+        init_test(cx);
+        let project = Project::test(FakeFs::new(cx.executor()), [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+        let preview = new_preview(SvgPreviewMode::Default, None, &workspace, cx);
+
+        preview.update(cx, |preview, cx| {
+            preview.viewport_bounds = Bounds::new(point(px(0.), px(0.)), size(px(200.), px(100.)));
+            // Zooming in around a point right of the center moves the image left, so that the
+            // point stays under the cursor.
+            let cursor = point(px(150.), px(50.));
+            preview.set_scale_factor(2., cursor, cx);
+            assert_eq!(preview.scale_factor, 2.);
+            assert_eq!(preview.image_offset, point(px(-50.), px(0.)));
+            assert!(!preview.fit_to_window);
+
+            preview.set_scale_factor(4., cursor, cx);
+            assert_eq!(preview.image_offset, point(px(-150.), px(0.)));
+
+            preview.set_scale_factor(1., cursor, cx);
+            assert_eq!(preview.image_offset, point(px(0.), px(0.)));
+
+            preview.set_scale_factor(1000., cursor, cx);
+            assert_eq!(preview.scale_factor, MAX_SCALE_FACTOR);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_render_debounced(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file(path!("/dir/icon.svg"), svg_of_size(10, 10).into_bytes())
+            .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/icon.svg"), cx)
+            })
+            .await
+            .unwrap();
+        let preview = new_preview(SvgPreviewMode::Default, Some(&buffer), &workspace, cx);
+        cx.run_until_parked();
+        let svg_size =
+            |cx: &mut VisualTestContext| preview.read_with(cx, |preview, _| preview.svg_size);
+        assert_eq!(svg_size(cx), Some(size(10., 10.)));
+
+        // Each edit restarts the debounce interval, so the SVG is only rendered again once it
+        // hasn't been edited for 250ms.
+        buffer.update(cx, |buffer, cx| buffer.set_text(svg_of_size(20, 20), cx));
+        cx.executor().advance_clock(Duration::from_millis(200));
+        cx.run_until_parked();
+        assert_eq!(svg_size(cx), Some(size(10., 10.)));
+
+        buffer.update(cx, |buffer, cx| buffer.set_text(svg_of_size(30, 30), cx));
+        cx.executor().advance_clock(Duration::from_millis(200));
+        cx.run_until_parked();
+        assert_eq!(svg_size(cx), Some(size(10., 10.)));
+
+        cx.executor().advance_clock(Duration::from_millis(100));
+        cx.run_until_parked();
+        assert_eq!(svg_size(cx), Some(size(30., 30.)));
+    }
+
+    #[gpui::test]
+    async fn test_stale_renders_are_dropped(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings
+                    .svg_preview
+                    .get_or_insert_default()
+                    .render_debounce_ms = Some(0);
+            });
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file(path!("/dir/icon.svg"), svg_of_size(10, 10).into_bytes())
+            .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/icon.svg"), cx)
+            })
+            .await
+            .unwrap();
+        let preview = new_preview(SvgPreviewMode::Default, Some(&buffer), &workspace, cx);
+        cx.run_until_parked();
+        let generation = preview.read_with(cx, |preview, _| {
+            preview.render_generation.load(Ordering::SeqCst)
+        });
+
+        // Without a debounce interval, every edit starts a render, and only the last one's
+        // result is shown.
+        buffer.update(cx, |buffer, cx| buffer.set_text(svg_of_size(20, 20), cx));
+        buffer.update(cx, |buffer, cx| buffer.set_text(svg_of_size(30, 30), cx));
+        preview.read_with(cx, |preview, _| {
+            assert_eq!(
+                preview.render_generation.load(Ordering::SeqCst),
+                generation + 2
+            );
+            assert_eq!(preview.svg_size, Some(size(10., 10.)));
+        });
+
+        cx.run_until_parked();
+        preview.read_with(cx, |preview, _| {
+            assert_eq!(preview.svg_size, Some(size(30., 30.)));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_follow_mode(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file(path!("/dir/a.svg"), svg_of_size(10, 10).into_bytes())
+            .await;
+        fs.insert_file(path!("/dir/b.svg"), svg_of_size(20, 20).into_bytes())
+            .await;
+        fs.insert_file(path!("/dir/notes.txt"), b"Hello".to_vec())
+            .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+        let a = open_in_editor(path!("/dir/a.svg"), &workspace, cx).await;
+        let preview = new_preview(SvgPreviewMode::Follow, Some(&a), &workspace, cx);
+        cx.run_until_parked();
+
+        let b = open_in_editor(path!("/dir/b.svg"), &workspace, cx).await;
+        preview.read_with(cx, |preview, _| {
+            assert_eq!(preview.buffer.as_ref(), Some(&b));
+            assert_eq!(preview.svg_size, Some(size(20., 20.)));
+            assert!(!preview.following_inactive);
+        });
+
+        // Editors of other files leave the preview on the last SVG.
+        open_in_editor(path!("/dir/notes.txt"), &workspace, cx).await;
+        preview.read_with(cx, |preview, _| {
+            assert_eq!(preview.buffer.as_ref(), Some(&b));
+            assert!(preview.following_inactive);
+        });
+
+        open_in_editor(path!("/dir/a.svg"), &workspace, cx).await;
+        preview.read_with(cx, |preview, _| {
+            assert_eq!(preview.buffer.as_ref(), Some(&a));
+            assert_eq!(preview.svg_size, Some(size(10., 10.)));
+            assert!(!preview.following_inactive);
+        });
+
+        // A pinned preview stays on its SVG.
+        preview.update_in(cx, |preview, window, cx| {
+            preview.toggle_pinned(&TogglePinned, window, cx)
+        });
+        open_in_editor(path!("/dir/b.svg"), &workspace, cx).await;
+        preview.read_with(cx, |preview, _| {
+            assert_eq!(preview.mode, SvgPreviewMode::Default);
+            assert_eq!(preview.buffer.as_ref(), Some(&a));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_serialization_round_trip(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file(path!("/dir/icon.svg"), svg_of_size(10, 10).into_bytes())
+            .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/icon.svg"), cx)
+            })
+            .await
+            .unwrap();
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+        let deserialize = |item_id: ItemId, cx: &mut VisualTestContext| {
+            workspace.update_in(cx, |workspace, window, cx| {
+                SvgPreviewView::deserialize(
+                    project.clone(),
+                    workspace.weak_handle(),
+                    workspace_id,
+                    item_id,
+                    window,
+                    cx,
+                )
+            })
+        };
+
+        let preview = new_preview(SvgPreviewMode::Default, Some(&buffer), &workspace, cx);
+        cx.run_until_parked();
+        let serialized = preview.update(cx, |preview, cx| {
+            preview.scale_factor = 2.5;
+            preview.image_offset = point(px(12.), px(-8.));
+            preview.fit_to_window = false;
+            preview.serialized(cx)
+        });
+        SVG_PREVIEW
+            .save_svg_preview(1234, workspace_id, serialized)
+            .await
+            .unwrap();
+        let restored = deserialize(1234, cx).await.unwrap();
+        restored.read_with(cx, |restored, _| {
+            assert_eq!(restored.mode, SvgPreviewMode::Default);
+            assert_eq!(restored.buffer.as_ref(), Some(&buffer));
+            assert_eq!(restored.scale_factor, 2.5);
+            assert_eq!(restored.image_offset, point(px(12.), px(-8.)));
+            assert!(!restored.fit_to_window);
+        });
+
+        // A following preview without an SVG is restored waiting for an SVG editor.
+        let following = new_preview(SvgPreviewMode::Follow, None, &workspace, cx);
+        let serialized = following.read_with(cx, |following, cx| following.serialized(cx));
+        SVG_PREVIEW
+            .save_svg_preview(5678, workspace_id, serialized)
+            .await
+            .unwrap();
+        let restored = deserialize(5678, cx).await.unwrap();
+        restored.read_with(cx, |restored, _| {
+            assert_eq!(restored.mode, SvgPreviewMode::Follow);
+            assert_eq!(restored.buffer, None);
+        });
+    }
 }
@@ -0,0 +1,197 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::{Context as _, Result, anyhow};
+use gpui::{App, AppContext as _, Context, Entity, Task};
+use language::{Buffer, Capability, DiskState};
+use project::{File, PathChange, Project, ProjectEntryId, ProjectPath};
+
+/// Whether the file at `path` is a gzip-compressed SVG.
+pub fn is_svgz_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("svgz"))
+}
+
+/// Decompresses the contents of an SVGZ file into the SVG's source.
+pub fn decompress(bytes: &[u8]) -> Result<String> {
+    let bytes = usvg::decompress_svgz(bytes).map_err(|error| anyhow!("{error}"))?;
+    String::from_utf8(bytes).context("the decompressed SVG isn't valid UTF-8")
+}
+
+/// Opens the SVGZ file at `path` as a read-only buffer with its decompressed source, which can't
+/// be loaded into a regular buffer as it isn't text. The buffer is reloaded when the file changes
+/// on disk.
+///
+/// Only local projects are supported, as remote worktrees can't load binary files.
+pub fn open_svgz_buffer(
+    project: &Entity<Project>,
+    path: ProjectPath,
+    cx: &mut App,
+) -> Task<Result<Entity<Buffer>>> {
+    if !project.read(cx).is_local() {
+        return Task::ready(Err(anyhow!(
+            "SVGZ files can only be previewed in local projects"
+        )));
+    }
+    let load = load_svgz(project, &path, cx);
+    let create_buffer = project.update(cx, |project, cx| project.create_buffer(false, cx));
+    let project = project.clone();
+    cx.spawn(async move |cx| {
+        let (file, text) = load.await?;
+        let buffer = create_buffer.await?;
+        buffer.update(cx, |buffer, cx| {
+            buffer.set_capability(Capability::ReadOnly, cx);
+            set_contents(buffer, file, text, cx);
+            cx.subscribe(&project, move |buffer, project, event, cx| {
+                if let project::Event::WorktreeUpdatedEntries(worktree_id, changes) = event
+                    && *worktree_id == path.worktree_id
+                    && let Some((_, _, change)) = changes
+                        .iter()
+                        .find(|(changed_path, _, _)| *changed_path == path.path)
+                {
+                    file_changed(buffer, &project, &path, *change, cx);
+                }
+            })
+            .detach();
+        })?;
+        Ok(buffer)
+    })
+}
+
+/// Loads and decompresses the SVGZ file at `path`.
+fn load_svgz(
+    project: &Entity<Project>,
+    path: &ProjectPath,
+    cx: &mut App,
+) -> Task<Result<(Arc<File>, String)>> {
+    let Some(worktree) = project.read(cx).worktree_for_id(path.worktree_id, cx) else {
+        return Task::ready(Err(anyhow!("no worktree for {:?}", path.path)));
+    };
+    let load = worktree.update(cx, |worktree, cx| worktree.load_binary_file(&path.path, cx));
+    let path = path.path.clone();
+    cx.spawn(async move |cx| {
+        let loaded = load.await?;
+        let content = loaded.content;
+        let text = cx
+            .background_spawn(async move { decompress(&content) })
+            .await
+            .with_context(|| format!("decompressing {path:?}"))?;
+        // The buffer store reloads the buffers of files with an entry as text, which fails for
+        // compressed files, so the entry is left out and the buffer is reloaded by
+        // `file_changed` instead.
+        let file = Arc::new(File {
+            entry_id: None,
+            ..loaded.file.as_ref().clone()
+        });
+        Ok((file, text))
+    })
+}
+
+/// Reloads the buffer of an SVGZ file after the file changed on disk.
+fn file_changed(
+    buffer: &mut Buffer,
+    project: &Entity<Project>,
+    path: &ProjectPath,
+    change: PathChange,
+    cx: &mut Context<Buffer>,
+) {
+    if change == PathChange::Removed {
+        if let Some(file) = File::from_dyn(buffer.file()) {
+            let file = Arc::new(File {
+                disk_state: DiskState::Deleted,
+                ..file.clone()
+            });
+            buffer.file_updated(file, cx);
+        }
+        return;
+    }
+    let load = load_svgz(project, path, cx);
+    cx.spawn(async move |buffer, cx| {
+        let (file, text) = load.await?;
+        buffer.update(cx, |buffer, cx| set_contents(buffer, file, text, cx))
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Replaces the buffer's contents with the decompressed file. The text is set first, which leaves
+/// the buffer dirty while the file is updated, so that the project doesn't try to reload the
+/// buffer as text when the file's modification time changes.
+fn set_contents(buffer: &mut Buffer, file: Arc<File>, text: String, cx: &mut Context<Buffer>) {
+    buffer.set_text(text, cx);
+    let mtime = file.disk_state.mtime();
+    buffer.file_updated(file, cx);
+    buffer.did_reload(buffer.version(), buffer.line_ending(), mtime, cx);
+}
+
+/// An SVGZ file, which is opened straight into an SVG preview rather than an editor.
+pub struct SvgzFile {
+    pub buffer: Entity<Buffer>,
+    project_path: ProjectPath,
+    entry_id: Option<ProjectEntryId>,
+}
+
+impl project::ProjectItem for SvgzFile {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        if !is_svgz_path(path.path.as_std_path()) {
+            return None;
+        }
+        let entry_id = project
+            .read(cx)
+            .entry_for_path(path, cx)
+            .map(|entry| entry.id);
+        let project_path = path.clone();
+        let buffer = open_svgz_buffer(project, path.clone(), cx);
+        Some(cx.spawn(async move |cx| {
+            let buffer = buffer.await?;
+            cx.new(|_| SvgzFile {
+                buffer,
+                project_path,
+                entry_id,
+            })
+        }))
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &App) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+
+    fn is_dirty(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_svgz_path() {
+        assert!(is_svgz_path(Path::new("icons/logo.svgz")));
+        assert!(is_svgz_path(Path::new("LOGO.SVGZ")));
+        assert!(!is_svgz_path(Path::new("logo.svg")));
+        assert!(!is_svgz_path(Path::new("svgz")));
+    }
+
+    #[test]
+    fn test_decompress() {
+        const SVGZ: &[u8] = &[
+            0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xb3, 0x29, 0x2e, 0x4b,
+            0x57, 0xa8, 0xc8, 0xcd, 0xc9, 0x2b, 0xb6, 0x55, 0xca, 0x28, 0x29, 0x29, 0xb0, 0xd2,
+            0xd7, 0x2f, 0x2f, 0x2f, 0xd7, 0x2b, 0x37, 0xd6, 0xcb, 0x2f, 0x4a, 0xd7, 0x37, 0x32,
+            0x30, 0x30, 0xd0, 0x07, 0xaa, 0x50, 0xd2, 0xb7, 0x03, 0x00, 0xc4, 0xf7, 0x91, 0x93,
+            0x29, 0x00, 0x00, 0x00,
+        ];
+        assert_eq!(
+            decompress(SVGZ).unwrap(),
+            r#"<svg xmlns="http://www.w3.org/2000/svg"/>"#
+        );
+        assert!(decompress(b"<svg/>").is_err());
+    }
+}
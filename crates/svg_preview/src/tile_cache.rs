@@ -0,0 +1,105 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use collections::HashMap;
+use gpui::RenderImage;
+
+use crate::memory_budget::image_bytes;
+use crate::tiles::TileIndex;
+
+/// How many zoom levels' worth of tiles are kept besides the ones on screen.
+const CAPACITY: usize = 4;
+/// How many scale buckets there are per doubling of the scale. Scales that differ by less than
+/// a bucket share their tiles, which absorbs the rounding of zooming in and back out.
+const BUCKETS_PER_DOUBLING: f32 = 1024.;
+
+/// Identifies the tiles of a particular rendering of an SVG at a particular zoom level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TileSetKey {
+    /// A hash of the source the SVG was rendered from and the resources it was rendered with.
+    pub content: u64,
+    scale_bucket: i32,
+}
+
+impl TileSetKey {
+    pub fn new(content: u64, scale: f32) -> Self {
+        Self {
+            content,
+            scale_bucket: (scale.log2() * BUCKETS_PER_DOUBLING).round() as i32,
+        }
+    }
+}
+
+/// Tiles of an SVG rasterized at `scale` device pixels per SVG pixel.
+#[derive(Default)]
+pub struct TileSet {
+    pub scale: f32,
+    pub tiles: HashMap<TileIndex, Arc<RenderImage>>,
+}
+
+/// The tiles of recently shown zoom levels and renderings, so that returning to them doesn't
+/// rasterize the SVG again. The least recently stored tiles are evicted first.
+#[derive(Default)]
+pub struct TileCache {
+    entries: VecDeque<(TileSetKey, TileSet)>,
+}
+
+impl TileCache {
+    /// Removes and returns the tiles stored under `key`.
+    pub fn take(&mut self, key: TileSetKey) -> Option<TileSet> {
+        let index = self
+            .entries
+            .iter()
+            .position(|(entry_key, _)| *entry_key == key)?;
+        self.entries.remove(index).map(|(_, tiles)| tiles)
+    }
+
+    /// Stores `tiles` under `key`, returning the images of the tiles that were evicted to make
+    /// room, which the caller must drop from the window.
+    pub fn insert(&mut self, key: TileSetKey, tiles: TileSet) -> Vec<Arc<RenderImage>> {
+        let mut evicted = self.take(key).map(into_images).unwrap_or_default();
+        if !tiles.tiles.is_empty() {
+            self.entries.push_back((key, tiles));
+        }
+        while self.entries.len() > CAPACITY {
+            if let Some((_, tiles)) = self.entries.pop_front() {
+                evicted.extend(into_images(tiles));
+            }
+        }
+        evicted
+    }
+
+    /// Returns the number of bytes that the cached tiles take up.
+    pub fn bytes(&self) -> usize {
+        self.entries
+            .iter()
+            .flat_map(|(_, tiles)| tiles.tiles.values())
+            .map(|tile| image_bytes(tile))
+            .sum()
+    }
+
+    /// Empties the cache, returning the images of its tiles.
+    pub fn clear(&mut self) -> Vec<Arc<RenderImage>> {
+        self.entries
+            .drain(..)
+            .flat_map(|(_, tiles)| into_images(tiles))
+            .collect()
+    }
+}
+
+fn into_images(tiles: TileSet) -> impl Iterator<Item = Arc<RenderImage>> {
+    tiles.tiles.into_values()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tile_set_key() {
+        assert_eq!(TileSetKey::new(1, 2.), TileSetKey::new(1, 2. * 1.25 / 1.25));
+        assert_eq!(TileSetKey::new(1, 1.), TileSetKey::new(1, 1.0001));
+        assert_ne!(TileSetKey::new(1, 1.), TileSetKey::new(1, 1.25));
+        assert_ne!(TileSetKey::new(1, 1.), TileSetKey::new(2, 1.));
+    }
+}
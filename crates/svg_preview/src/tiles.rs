@@ -0,0 +1,122 @@
+use gpui::{Bounds, DevicePixels, Point, Size, point, size};
+
+/// The width and height of a tile, in device pixels.
+pub const TILE_SIZE: u32 = 512;
+
+/// Identifies a tile of an SVG rasterized at a particular scale.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TileIndex {
+    pub column: u32,
+    pub row: u32,
+}
+
+/// Returns the tiles intersecting `visible`, a region given in SVG pixels, when an SVG of
+/// `svg_size` is rasterized at `scale` device pixels per SVG pixel.
+pub fn visible_tiles(svg_size: Size<f32>, visible: Bounds<f32>, scale: f32) -> Vec<TileIndex> {
+    let (columns, rows) = tile_counts(svg_size, scale);
+    if columns == 0 || rows == 0 || visible.size.width <= 0. || visible.size.height <= 0. {
+        return Vec::new();
+    }
+
+    let tile_span = |start: f32, length: f32, count: u32| {
+        let first = (start * scale / TILE_SIZE as f32).floor().max(0.) as u32;
+        let last = ((start + length) * scale / TILE_SIZE as f32).ceil().max(0.) as u32;
+        first.min(count)..last.min(count)
+    };
+    let column_span = tile_span(visible.origin.x, visible.size.width, columns);
+    let row_span = tile_span(visible.origin.y, visible.size.height, rows);
+
+    row_span
+        .flat_map(|row| {
+            column_span
+                .clone()
+                .map(move |column| TileIndex { column, row })
+        })
+        .collect()
+}
+
+/// Returns the origin of a tile in SVG pixels and its size in device pixels. Tiles along the
+/// right and bottom edges are cropped to the extent of the SVG.
+pub fn tile_region(
+    index: TileIndex,
+    svg_size: Size<f32>,
+    scale: f32,
+) -> (Point<f32>, Size<DevicePixels>) {
+    let image_size = image_size(svg_size, scale);
+    let left = index.column * TILE_SIZE;
+    let top = index.row * TILE_SIZE;
+    let tile_length = |image_length: DevicePixels, start: u32| {
+        DevicePixels(
+            (image_length.0.max(0) as u32)
+                .saturating_sub(start)
+                .min(TILE_SIZE) as i32,
+        )
+    };
+    (
+        point(left as f32 / scale, top as f32 / scale),
+        size(
+            tile_length(image_size.width, left),
+            tile_length(image_size.height, top),
+        ),
+    )
+}
+
+/// Returns the size in device pixels of an SVG of `svg_size` rasterized at `scale`.
+pub fn image_size(svg_size: Size<f32>, scale: f32) -> Size<DevicePixels> {
+    size(
+        DevicePixels((svg_size.width * scale).ceil() as i32),
+        DevicePixels((svg_size.height * scale).ceil() as i32),
+    )
+}
+
+fn tile_counts(svg_size: Size<f32>, scale: f32) -> (u32, u32) {
+    let image_size = image_size(svg_size, scale);
+    let count = |length: DevicePixels| (length.0.max(0) as u32).div_ceil(TILE_SIZE);
+    (count(image_size.width), count(image_size.height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visible_tiles() {
+        let svg_size = size(1000., 600.);
+
+        let tiles = visible_tiles(svg_size, Bounds::new(point(0., 0.), svg_size), 1.);
+        assert_eq!(tiles.len(), 4);
+
+        let tiles = visible_tiles(
+            svg_size,
+            Bounds::new(point(300., 300.), size(50., 100.)),
+            4.,
+        );
+        assert_eq!(
+            tiles,
+            vec![
+                TileIndex { column: 2, row: 2 },
+                TileIndex { column: 2, row: 3 }
+            ]
+        );
+
+        let tiles = visible_tiles(
+            svg_size,
+            Bounds::new(point(-500., 0.), size(100., 100.)),
+            1.,
+        );
+        assert!(tiles.is_empty());
+    }
+
+    #[test]
+    fn test_tile_region() {
+        let svg_size = size(1000., 600.);
+        assert_eq!(
+            tile_region(TileIndex { column: 1, row: 0 }, svg_size, 2.),
+            (point(256., 0.), size(DevicePixels(512), DevicePixels(512)))
+        );
+        assert_eq!(
+            tile_region(TileIndex { column: 1, row: 1 }, svg_size, 1.),
+            (point(512., 512.), size(DevicePixels(488), DevicePixels(88)))
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use roxmltree::Document;
+
+/// HTML elements that browsers render inside SVGs, but the renderer ignores.
+const HTML_ELEMENTS: &[(&str, &str)] = &[
+    ("audio", "<audio>"),
+    ("canvas", "<canvas>"),
+    ("iframe", "<iframe>"),
+    ("video", "<video>"),
+];
+
+/// Filter inputs that refer to the canvas or the element's paint, which the renderer doesn't
+/// support.
+const FILTER_INPUTS: &[&str] = &[
+    "BackgroundImage",
+    "BackgroundAlpha",
+    "FillPaint",
+    "StrokePaint",
+];
+
+/// Returns the features of `document` that the renderer ignores, so that it looks different than
+/// in a browser, in the order they first appear.
+pub fn unsupported_features(document: &Document) -> Vec<String> {
+    let mut features = Vec::new();
+    let mut add = |feature: String| {
+        if !features.contains(&feature) {
+            features.push(feature);
+        }
+    };
+    for node in document.descendants().filter(|node| node.is_element()) {
+        let name = node.tag_name().name();
+        match name {
+            "foreignObject" => add("<foreignObject>".to_string()),
+            "script" => add("scripts".to_string()),
+            "font" | "font-face" => add("SVG fonts".to_string()),
+            "style" => {
+                let mut css = node.descendants().filter_map(|node| node.text());
+                if css.any(|css| css.contains("@keyframes")) {
+                    add("CSS animations".to_string());
+                }
+            }
+            _ => {
+                if let Some((_, label)) = HTML_ELEMENTS.iter().find(|(element, _)| *element == name)
+                {
+                    add(label.to_string());
+                }
+            }
+        }
+        for attribute in node.attributes() {
+            let attribute_name = attribute.name();
+            if attribute_name.len() > 2 && attribute_name.starts_with("on") {
+                add("event handlers".to_string());
+            } else if name.starts_with("fe")
+                && matches!(attribute_name, "in" | "in2")
+                && FILTER_INPUTS.contains(&attribute.value().trim())
+            {
+                add(format!("{} filter input", attribute.value().trim()));
+            }
+        }
+    }
+    features
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn features(text: &str) -> Vec<String> {
+        unsupported_features(&Document::parse(text).unwrap())
+    }
+
+    #[test]
+    fn test_unsupported_features() {
+        assert!(features(r#"<svg xmlns="http://www.w3.org/2000/svg"><rect/></svg>"#).is_empty());
+        assert_eq!(
+            features(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" onload="init()">
+                    <style>@keyframes spin { to { transform: rotate(1turn) } }</style>
+                    <script>function init() {}</script>
+                    <foreignObject><div/></foreignObject>
+                    <foreignObject><video/></foreignObject>
+                    <filter><feBlend in="SourceGraphic" in2="BackgroundImage"/></filter>
+                    <rect onclick="init()"/>
+                </svg>"#
+            ),
+            [
+                "event handlers",
+                "CSS animations",
+                "scripts",
+                "<foreignObject>",
+                "<video>",
+                "BackgroundImage filter input",
+            ]
+        );
+    }
+}
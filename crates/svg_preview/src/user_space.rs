@@ -0,0 +1,194 @@
+use gpui::{Bounds, Point, Size, point, size};
+
+/// Maps between the user coordinate system of an SVG document, as established by the `viewBox`
+/// and `preserveAspectRatio` attributes of its root element, and the document's intrinsic size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UserSpace {
+    scale_x: f32,
+    scale_y: f32,
+    translate_x: f32,
+    translate_y: f32,
+    /// The `viewBox` as its x, y, width and height, if the document has one.
+    view_box: Option<[f32; 4]>,
+}
+
+impl Default for UserSpace {
+    fn default() -> Self {
+        Self {
+            scale_x: 1.,
+            scale_y: 1.,
+            translate_x: 0.,
+            translate_y: 0.,
+            view_box: None,
+        }
+    }
+}
+
+impl UserSpace {
+    pub fn new(root: roxmltree::Node, size: Size<f32>) -> Self {
+        let Some([x, y, width, height]) = root.attribute("viewBox").and_then(parse_view_box) else {
+            return Self::default();
+        };
+
+        let (align, slice) = root
+            .attribute("preserveAspectRatio")
+            .map(parse_preserve_aspect_ratio)
+            .unwrap_or((Some((0.5, 0.5)), false));
+
+        let mut scale_x = size.width / width;
+        let mut scale_y = size.height / height;
+        let mut translate_x = -x * scale_x;
+        let mut translate_y = -y * scale_y;
+        if let Some((align_x, align_y)) = align {
+            let scale = if slice {
+                scale_x.max(scale_y)
+            } else {
+                scale_x.min(scale_y)
+            };
+            scale_x = scale;
+            scale_y = scale;
+            translate_x = -x * scale + (size.width - width * scale) * align_x;
+            translate_y = -y * scale + (size.height - height * scale) * align_y;
+        }
+
+        Self {
+            scale_x,
+            scale_y,
+            translate_x,
+            translate_y,
+            view_box: Some([x, y, width, height]),
+        }
+    }
+
+    /// The bounds of the `viewBox` in intrinsic pixels, if the document has one. They extend
+    /// past the document when the `viewBox` is sliced, and don't cover all of it when the
+    /// `viewBox` is letterboxed.
+    pub fn view_box_bounds(&self) -> Option<Bounds<f32>> {
+        let [x, y, width, height] = self.view_box?;
+        Some(Bounds::new(
+            self.from_user(point(x, y)),
+            size(width * self.scale_x, height * self.scale_y),
+        ))
+    }
+
+    /// The number of intrinsic pixels per user unit along each axis.
+    pub fn scale(&self) -> Point<f32> {
+        point(self.scale_x, self.scale_y)
+    }
+
+    pub fn to_user(&self, position: Point<f32>) -> Point<f32> {
+        point(
+            (position.x - self.translate_x) / self.scale_x,
+            (position.y - self.translate_y) / self.scale_y,
+        )
+    }
+
+    pub fn from_user(&self, position: Point<f32>) -> Point<f32> {
+        point(
+            position.x * self.scale_x + self.translate_x,
+            position.y * self.scale_y + self.translate_y,
+        )
+    }
+}
+
+/// Formats a coordinate or length with at most two decimals, dropping trailing zeros.
+pub fn format_units(value: f32) -> String {
+    let text = format!("{value:.2}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => "0".to_string(),
+        _ => text.to_string(),
+    }
+}
+
+pub(crate) fn parse_view_box(value: &str) -> Option<[f32; 4]> {
+    let mut numbers = value
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<f32>().ok());
+    let view_box = [
+        numbers.next()??,
+        numbers.next()??,
+        numbers.next()??,
+        numbers.next()??,
+    ];
+    (view_box[2] > 0. && view_box[3] > 0.).then_some(view_box)
+}
+
+/// Returns the alignment of the viewBox within the viewport as fractions of the free space, or
+/// `None` when the aspect ratio isn't preserved, along with whether the viewBox is sliced.
+fn parse_preserve_aspect_ratio(value: &str) -> (Option<(f32, f32)>, bool) {
+    let mut parts = value.split_whitespace();
+    let align = match parts.next() {
+        Some("none") => None,
+        Some(align) if align.len() == 8 && align.is_ascii() => {
+            let fraction = |position: &str| match position {
+                "Min" => Some(0.),
+                "Mid" => Some(0.5),
+                "Max" => Some(1.),
+                _ => None,
+            };
+            fraction(&align[1..4])
+                .zip(fraction(&align[5..8]))
+                .or(Some((0.5, 0.5)))
+        }
+        _ => Some((0.5, 0.5)),
+    };
+    (align, parts.next() == Some("slice"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_space(attributes: &str, size: Size<f32>) -> UserSpace {
+        let text = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" {attributes}/>");
+        let document = roxmltree::Document::parse(&text).unwrap();
+        UserSpace::new(document.root_element(), size)
+    }
+
+    #[test]
+    fn test_user_space_without_view_box() {
+        let space = user_space("width=\"24\" height=\"24\"", size(24., 24.));
+        assert_eq!(space.to_user(point(12., 6.)), point(12., 6.));
+        assert_eq!(space.view_box_bounds(), None);
+    }
+
+    #[test]
+    fn test_user_space_with_view_box() {
+        let space = user_space("viewBox=\"10 20 50 50\"", size(100., 100.));
+        assert_eq!(space.to_user(point(0., 0.)), point(10., 20.));
+        assert_eq!(space.to_user(point(100., 100.)), point(60., 70.));
+        assert_eq!(space.from_user(point(35., 45.)), point(50., 50.));
+    }
+
+    #[test]
+    fn test_user_space_preserve_aspect_ratio() {
+        let space = user_space("viewBox=\"0 0 10 20\"", size(100., 100.));
+        assert_eq!(space.from_user(point(0., 0.)), point(25., 0.));
+        assert_eq!(
+            space.view_box_bounds(),
+            Some(Bounds::new(point(25., 0.), size(50., 100.)))
+        );
+
+        let space = user_space(
+            "viewBox=\"0 0 10 20\" preserveAspectRatio=\"xMinYMin\"",
+            size(100., 100.),
+        );
+        assert_eq!(space.from_user(point(0., 0.)), point(0., 0.));
+
+        let space = user_space(
+            "viewBox=\"0 0 10 20\" preserveAspectRatio=\"none\"",
+            size(100., 100.),
+        );
+        assert_eq!(space.from_user(point(10., 20.)), point(100., 100.));
+    }
+
+    #[test]
+    fn test_format_units() {
+        assert_eq!(format_units(12.), "12");
+        assert_eq!(format_units(0.5), "0.5");
+        assert_eq!(format_units(1. / 3.), "0.33");
+        assert_eq!(format_units(-0.001), "0");
+    }
+}
@@ -0,0 +1,494 @@
+use std::borrow::Cow;
+use std::mem;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use collections::{HashMap, HashSet};
+use gpui::{App, Entity, EntityId, Global, Subscription, Task, WeakEntity};
+use language::{
+    Anchor, Buffer, BufferEvent, DiagnosticSourceKind, LanguageServerId, LanguageServerName,
+    OffsetRangeExt as _,
+};
+use project::Project;
+use project::lsp_store::DocumentDiagnosticsUpdate;
+use settings::Settings as _;
+use util::ResultExt as _;
+
+use crate::SvgPreviewSettings;
+use crate::animation;
+use crate::user_space::parse_view_box;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem found in the source of an SVG.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Problem {
+    pub range: Range<usize>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Checks `text` for syntax errors, and for mistakes that make the renderer ignore parts of it:
+/// references to elements that don't exist and invalid `viewBox`, `width` and `height`
+/// attributes on the root element.
+pub fn validate(text: &str) -> Vec<Problem> {
+    let document = match animation::parse_document(text) {
+        Ok(document) => document,
+        Err(error) => {
+            let position = error.pos();
+            let start = offset_at(text, position.row, position.col);
+            let end = text[start..]
+                .chars()
+                .next()
+                .map_or(start, |c| start + c.len_utf8());
+            return vec![Problem {
+                range: start..end,
+                severity: Severity::Error,
+                message: error.to_string(),
+            }];
+        }
+    };
+
+    let mut problems = Vec::new();
+    let root = document.root_element();
+    for attribute in root.attributes() {
+        let message = match attribute.name() {
+            "viewBox" if parse_view_box(attribute.value()).is_none() => {
+                "Invalid viewBox, expected a positive width and height: min-x min-y width height"
+            }
+            "width" | "height" if !is_positive_length(attribute.value()) => {
+                "Invalid size, expected a positive length"
+            }
+            _ => continue,
+        };
+        problems.push(Problem {
+            range: attribute.range_value(),
+            severity: Severity::Error,
+            message: message.to_string(),
+        });
+    }
+
+    let ids = document
+        .descendants()
+        .filter_map(|node| node.attribute("id"))
+        .collect::<HashSet<_>>();
+    for node in document.descendants().filter(|node| node.is_element()) {
+        for attribute in node.attributes() {
+            let references = if attribute.name() == "href" {
+                attribute
+                    .value()
+                    .trim()
+                    .strip_prefix('#')
+                    .into_iter()
+                    .collect()
+            } else {
+                url_references(attribute.value())
+            };
+            for id in references {
+                if !ids.contains(id) {
+                    problems.push(Problem {
+                        range: attribute.range_value(),
+                        severity: Severity::Warning,
+                        message: format!("No element with id \"{id}\""),
+                    });
+                }
+            }
+        }
+    }
+    problems
+}
+
+/// Returns the zero-based line and UTF-16 column of `offset` in `text`, as language servers
+/// report them.
+pub fn line_and_column(text: &str, offset: usize) -> (u32, u32) {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    let line = before.matches('\n').count();
+    let column = before[line_start..].encode_utf16().count();
+    (line as u32, column as u32)
+}
+
+/// Returns the offset of the one-based `row` and `column`, in characters, in `text`.
+fn offset_at(text: &str, row: u32, column: u32) -> usize {
+    let line_start = text
+        .split_inclusive('\n')
+        .take(row.saturating_sub(1) as usize)
+        .map(str::len)
+        .sum::<usize>();
+    let line = &text[line_start..];
+    line_start
+        + line
+            .char_indices()
+            .nth(column.saturating_sub(1) as usize)
+            .map_or(line.len(), |(index, _)| index)
+}
+
+/// Returns the ids referenced with `url(#id)` in `value`.
+fn url_references(value: &str) -> Vec<&str> {
+    value
+        .split("url(")
+        .skip(1)
+        .filter_map(|reference| {
+            let reference = reference[..reference.find(')')?].trim();
+            let reference = reference.trim_matches(|c| c == '"' || c == '\'');
+            reference.strip_prefix('#')
+        })
+        .collect()
+}
+
+fn is_positive_length(value: &str) -> bool {
+    let value = value.trim();
+    let number_len = value
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E')))
+        .unwrap_or(value.len());
+    value[..number_len]
+        .parse::<f32>()
+        .is_ok_and(|number| number > 0.)
+}
+
+/// Reports the problems found in previewed SVGs as diagnostics of their buffers, so that they
+/// show up in their editors and in the project's diagnostics. A buffer's diagnostics are kept up
+/// to date while it's previewed, and are reported under a diagnostic source that is registered
+/// with each project's language server store while any of its buffers are.
+///
+/// Only local projects are supported: the language server stores of remote projects and of
+/// collaborators' projects only show the diagnostics that their host reports.
+pub struct SvgDiagnostics {
+    /// The id of the diagnostic source registered in each project.
+    sources: HashMap<EntityId, LanguageServerId>,
+    buffers: HashMap<EntityId, DiagnosedBuffer>,
+}
+
+struct DiagnosedBuffer {
+    project: WeakEntity<Project>,
+    /// The previews of the buffer, along with the range of the SVG each of them shows for SVGs
+    /// embedded in other languages, or `None` if it shows the whole buffer.
+    previews: HashMap<EntityId, Option<Range<Anchor>>>,
+    /// The path of the file that diagnostics were last reported for.
+    path: Option<PathBuf>,
+    task: Task<()>,
+    _subscriptions: [Subscription; 2],
+}
+
+impl Global for SvgDiagnostics {}
+
+impl SvgDiagnostics {
+    const SOURCE_NAME: LanguageServerName = LanguageServerName::new_static("svg-preview");
+
+    pub fn init(cx: &mut App) {
+        cx.set_global(Self {
+            sources: HashMap::default(),
+            buffers: HashMap::default(),
+        });
+    }
+
+    /// Starts reporting the problems in `buffer` for `preview`, or only in the SVG at `range` for
+    /// SVGs embedded in other languages, instead of the problems in the buffer it showed before.
+    pub fn watch(
+        preview: EntityId,
+        project: &Entity<Project>,
+        buffer: &Entity<Buffer>,
+        range: Option<Range<Anchor>>,
+        cx: &mut App,
+    ) {
+        let buffer_id = buffer.entity_id();
+        if !cx.has_global::<Self>() {
+            return;
+        }
+        let this = cx.global::<Self>();
+        if this
+            .buffers
+            .get(&buffer_id)
+            .and_then(|diagnosed| diagnosed.previews.get(&preview))
+            == Some(&range)
+        {
+            return;
+        }
+        Self::remove_preview(preview, Some(buffer_id), cx);
+        if !project.read(cx).is_local() {
+            return;
+        }
+
+        if !cx.global::<Self>().buffers.contains_key(&buffer_id) {
+            let subscriptions = [
+                cx.subscribe(buffer, |buffer, event: &BufferEvent, cx| {
+                    if matches!(
+                        event,
+                        BufferEvent::Edited
+                            | BufferEvent::Reloaded
+                            | BufferEvent::FileHandleChanged
+                    ) {
+                        Self::update(&buffer, cx);
+                    }
+                }),
+                cx.observe_release(buffer, move |_, cx| Self::forget(buffer_id, cx)),
+            ];
+            cx.global_mut::<Self>().buffers.insert(
+                buffer_id,
+                DiagnosedBuffer {
+                    project: project.downgrade(),
+                    previews: HashMap::default(),
+                    path: None,
+                    task: Task::ready(()),
+                    _subscriptions: subscriptions,
+                },
+            );
+        }
+        if let Some(diagnosed) = cx.global_mut::<Self>().buffers.get_mut(&buffer_id) {
+            diagnosed.previews.insert(preview, range);
+        }
+        Self::update(buffer, cx);
+    }
+
+    /// Stops reporting problems for `preview`, clearing the diagnostics of the buffer it showed
+    /// if no other preview shows it.
+    pub fn unwatch(preview: EntityId, cx: &mut App) {
+        if cx.has_global::<Self>() {
+            Self::remove_preview(preview, None, cx);
+        }
+    }
+
+    /// Removes `preview` from the buffers it's watching other than `except`, forgetting the
+    /// buffers that no other preview watches.
+    fn remove_preview(preview: EntityId, except: Option<EntityId>, cx: &mut App) {
+        let mut unwatched = Vec::new();
+        for (buffer_id, diagnosed) in &mut cx.global_mut::<Self>().buffers {
+            if Some(*buffer_id) != except
+                && diagnosed.previews.remove(&preview).is_some()
+                && diagnosed.previews.is_empty()
+            {
+                unwatched.push(*buffer_id);
+            }
+        }
+        for buffer_id in unwatched {
+            Self::forget(buffer_id, cx);
+        }
+    }
+
+    /// Checks the SVGs of `buffer` for problems in the background, once it hasn't been edited for
+    /// the render debounce delay, and reports them.
+    fn update(buffer: &Entity<Buffer>, cx: &mut App) {
+        let buffer_id = buffer.entity_id();
+        let weak_buffer = buffer.downgrade();
+        let debounce = Duration::from_millis(SvgPreviewSettings::get_global(cx).render_debounce_ms);
+        let task = cx.spawn(async move |cx| {
+            cx.background_executor().timer(debounce).await;
+            let inputs = cx.update(|cx| {
+                let diagnosed = cx.global::<Self>().buffers.get(&buffer_id)?;
+                let buffer = weak_buffer.upgrade()?;
+                let buffer = buffer.read(cx);
+                let mut ranges = if diagnosed.previews.values().any(Option::is_none) {
+                    vec![0..buffer.len()]
+                } else {
+                    diagnosed
+                        .previews
+                        .values()
+                        .flatten()
+                        .map(|range| range.to_offset(buffer))
+                        .filter(|range| !range.is_empty())
+                        .collect::<Vec<_>>()
+                };
+                // Several previews may show the same embedded SVG.
+                ranges.sort_by_key(|range| (range.start, range.end));
+                ranges.dedup();
+                let path = buffer
+                    .file()
+                    .and_then(|file| Some(file.as_local()?.abs_path(cx)));
+                Some((buffer.text(), ranges, path))
+            });
+            let Some((text, ranges, path)) = inputs.ok().flatten() else {
+                return;
+            };
+            let diagnostics = cx
+                .background_spawn(async move {
+                    let mut problems = Vec::new();
+                    // Embedded SVGs are validated on their own, but reported in the whole buffer.
+                    for range in ranges {
+                        problems.extend(validate(&text[range.clone()]).into_iter().map(
+                            |mut problem| {
+                                problem.range.start += range.start;
+                                problem.range.end += range.start;
+                                problem
+                            },
+                        ));
+                    }
+                    problems
+                        .into_iter()
+                        .map(|problem| lsp_diagnostic(&text, problem))
+                        .collect::<Vec<_>>()
+                })
+                .await;
+            cx.update(|cx| Self::publish(buffer_id, path, diagnostics, cx))
+                .ok();
+        });
+        if let Some(diagnosed) = cx.global_mut::<Self>().buffers.get_mut(&buffer_id) {
+            diagnosed.task = task;
+        }
+    }
+
+    /// Reports `diagnostics` for the file at `path`, clearing those reported for the file's
+    /// previous path if it was renamed.
+    fn publish(
+        buffer_id: EntityId,
+        path: Option<PathBuf>,
+        diagnostics: Vec<lsp::Diagnostic>,
+        cx: &mut App,
+    ) {
+        let Some(diagnosed) = cx.global_mut::<Self>().buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        let previous_path = mem::replace(&mut diagnosed.path, path.clone());
+        let Some(project) = diagnosed.project.upgrade() else {
+            return;
+        };
+        let server_id = Self::source(&project, cx);
+        if let Some(previous_path) =
+            previous_path.filter(|previous| Some(previous) != path.as_ref())
+        {
+            report_diagnostics(&project, server_id, previous_path, Vec::new(), cx);
+        }
+        if let Some(path) = path {
+            report_diagnostics(&project, server_id, path, diagnostics, cx);
+        }
+    }
+
+    /// Returns the id of the diagnostic source registered in `project`, registering it first if
+    /// it isn't.
+    fn source(project: &Entity<Project>, cx: &mut App) -> LanguageServerId {
+        let project_id = project.entity_id();
+        if let Some(server_id) = cx.global::<Self>().sources.get(&project_id) {
+            return *server_id;
+        }
+        let server_id = project.read(cx).lsp_store().update(cx, |lsp_store, cx| {
+            lsp_store.register_diagnostic_source(Self::SOURCE_NAME, cx)
+        });
+        cx.global_mut::<Self>()
+            .sources
+            .insert(project_id, server_id);
+        server_id
+    }
+
+    /// Clears the diagnostics of a buffer that isn't previewed anymore, unregistering the
+    /// diagnostic source of its project once none of the project's buffers are previewed.
+    fn forget(buffer_id: EntityId, cx: &mut App) {
+        let this = cx.global_mut::<Self>();
+        let Some(diagnosed) = this.buffers.remove(&buffer_id) else {
+            return;
+        };
+        let project_id = diagnosed.project.entity_id();
+        let server_id = if this
+            .buffers
+            .values()
+            .any(|other| other.project.entity_id() == project_id)
+        {
+            this.sources.get(&project_id).copied()
+        } else {
+            this.sources.remove(&project_id)
+        };
+        let (Some(server_id), Some(project)) = (server_id, diagnosed.project.upgrade()) else {
+            return;
+        };
+        if let Some(path) = diagnosed.path {
+            report_diagnostics(&project, server_id, path, Vec::new(), cx);
+        }
+        if !cx.global::<Self>().sources.contains_key(&project_id) {
+            project.read(cx).lsp_store().update(cx, |lsp_store, cx| {
+                lsp_store.unregister_diagnostic_source(server_id, cx)
+            });
+        }
+    }
+}
+
+/// Replaces the diagnostics reported under `server_id` for the file at `path` with `diagnostics`.
+fn report_diagnostics(
+    project: &Entity<Project>,
+    server_id: LanguageServerId,
+    path: PathBuf,
+    diagnostics: Vec<lsp::Diagnostic>,
+    cx: &mut App,
+) {
+    let Ok(uri) = lsp::Uri::from_file_path(&path) else {
+        return;
+    };
+    project
+        .read(cx)
+        .lsp_store()
+        .update(cx, |lsp_store, cx| {
+            lsp_store.merge_lsp_diagnostics(
+                DiagnosticSourceKind::Pushed,
+                vec![DocumentDiagnosticsUpdate {
+                    diagnostics: lsp::PublishDiagnosticsParams {
+                        uri,
+                        diagnostics,
+                        version: None,
+                    },
+                    result_id: None,
+                    registration_id: None,
+                    server_id,
+                    disk_based_sources: Cow::Borrowed(&[]),
+                }],
+                |_, _, _| false,
+                cx,
+            )
+        })
+        .log_err();
+}
+
+/// Converts a problem found in the SVG source `text` into a diagnostic, with its range in the
+/// lines and columns of `text`.
+fn lsp_diagnostic(text: &str, problem: Problem) -> lsp::Diagnostic {
+    let position = |offset| {
+        let (line, character) = line_and_column(text, offset);
+        lsp::Position::new(line, character)
+    };
+    lsp::Diagnostic {
+        range: lsp::Range::new(position(problem.range.start), position(problem.range.end)),
+        severity: Some(match problem.severity {
+            Severity::Error => lsp::DiagnosticSeverity::ERROR,
+            Severity::Warning => lsp::DiagnosticSeverity::WARNING,
+        }),
+        source: Some("svg".to_string()),
+        message: problem.message,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let text = "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 0 24\" width=\"24px\">\n  <rect fill=\"url(#missing)\" clip-path=\"url('#clip')\"/>\n  <clipPath id=\"clip\"/>\n  <use href=\"#clip\"/>\n</svg>";
+        let problems = validate(text)
+            .into_iter()
+            .map(|problem| (&text[problem.range], problem.severity))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            problems,
+            [
+                ("0 0 0 24", Severity::Error),
+                ("url(#missing)", Severity::Warning)
+            ]
+        );
+
+        let text = "<svg>\n  <g>\n</svg>";
+        let problems = validate(text);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].severity, Severity::Error);
+        assert_eq!(line_and_column(text, problems[0].range.start).0, 2);
+    }
+
+    #[test]
+    fn test_line_and_column() {
+        let text = "a\nbé😀c";
+        assert_eq!(line_and_column(text, 0), (0, 0));
+        assert_eq!(line_and_column(text, 2), (1, 0));
+        assert_eq!(line_and_column(text, text.find('c').unwrap()), (1, 4));
+    }
+}
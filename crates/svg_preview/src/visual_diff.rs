@@ -0,0 +1,126 @@
+use gpui::{Bounds, RenderImage, point, size};
+use image::{Frame, RgbaImage};
+
+/// The size of the cells that changes are reported in, in pixels.
+const CELL_SIZE: usize = 16;
+
+/// How much a channel of a pixel may differ before the pixel counts as changed, so that slight
+/// differences in antialiasing aren't reported.
+const THRESHOLD: u8 = 8;
+
+/// Returns the regions where two renders differ, as fractions of their size. Renders of
+/// different sizes differ everywhere.
+pub fn changed_regions(a: &RenderImage, b: &RenderImage) -> Vec<Bounds<f32>> {
+    let image_size = a.size(0);
+    match (a.as_bytes(0), b.as_bytes(0)) {
+        (Some(a), Some(b)) if image_size == b.size(0) => changed_cells(
+            a,
+            b,
+            image_size.width.0 as usize,
+            image_size.height.0 as usize,
+        ),
+        _ => vec![Bounds::new(point(0., 0.), size(1., 1.))],
+    }
+}
+
+/// Returns an image of how much two renders differ at each pixel, or `None` if they're of
+/// different sizes.
+pub fn heatmap(a: &RenderImage, b: &RenderImage) -> Option<RenderImage> {
+    let image_size = a.size(0);
+    if image_size != b.size(0) {
+        return None;
+    }
+    let pixels = heatmap_pixels(a.as_bytes(0)?, b.as_bytes(0)?);
+    let buffer = RgbaImage::from_raw(
+        image_size.width.0 as u32,
+        image_size.height.0 as u32,
+        pixels,
+    )?;
+    Some(RenderImage::new([Frame::new(buffer)]))
+}
+
+/// Returns pixels that are transparent where the pixels of `a` and `b` are the same, and go from
+/// translucent yellow to opaque red the more they differ. Differences are amplified so that the
+/// slightest change in antialiasing still shows up.
+fn heatmap_pixels(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.chunks_exact(4)
+        .zip(b.chunks_exact(4))
+        .flat_map(|(a, b)| {
+            let difference = a.iter().zip(b).map(|(a, b)| a.abs_diff(*b)).max();
+            match difference {
+                None | Some(0) => [0; 4],
+                Some(difference) => {
+                    let intensity = difference.saturating_mul(4);
+                    // The renders are in BGRA.
+                    [0, 255 - intensity, 255, 128 + intensity / 2]
+                }
+            }
+        })
+        .collect()
+}
+
+/// Returns the cells where two images of `width` by `height` pixels with 4 bytes per pixel differ,
+/// as fractions of the images' size.
+fn changed_cells(a: &[u8], b: &[u8], width: usize, height: usize) -> Vec<Bounds<f32>> {
+    let mut cells = Vec::new();
+    if width == 0 || height == 0 {
+        return cells;
+    }
+    for cell_y in (0..height).step_by(CELL_SIZE) {
+        for cell_x in (0..width).step_by(CELL_SIZE) {
+            let cell_width = CELL_SIZE.min(width - cell_x);
+            let cell_height = CELL_SIZE.min(height - cell_y);
+            let changed = (cell_y..cell_y + cell_height).any(|y| {
+                let row = (y * width + cell_x) * 4..(y * width + cell_x + cell_width) * 4;
+                a[row.clone()]
+                    .iter()
+                    .zip(&b[row])
+                    .any(|(a, b)| a.abs_diff(*b) > THRESHOLD)
+            });
+            if changed {
+                cells.push(Bounds::new(
+                    point(cell_x as f32 / width as f32, cell_y as f32 / height as f32),
+                    size(
+                        cell_width as f32 / width as f32,
+                        cell_height as f32 / height as f32,
+                    ),
+                ));
+            }
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_cells() {
+        let (width, height) = (32, 20);
+        let a = vec![0; width * height * 4];
+        let mut b = a.clone();
+        assert!(changed_cells(&a, &b, width, height).is_empty());
+
+        // Slight changes are ignored.
+        b[0] = THRESHOLD;
+        assert!(changed_cells(&a, &b, width, height).is_empty());
+
+        // A pixel in the bottom right cell, which is cut off by the edges.
+        b[((18 * width) + 20) * 4 + 1] = 255;
+        assert_eq!(
+            changed_cells(&a, &b, width, height),
+            [Bounds::new(point(0.5, 0.8), size(0.5, 0.2))]
+        );
+    }
+
+    #[test]
+    fn test_heatmap_pixels() {
+        let a = [0, 0, 0, 255, 10, 20, 30, 255, 0, 0, 0, 0];
+        let b = [0, 0, 0, 255, 11, 20, 30, 255, 0, 0, 200, 255];
+        assert_eq!(
+            heatmap_pixels(&a, &b),
+            [0, 0, 0, 0, 0, 251, 255, 130, 0, 0, 255, 255]
+        );
+    }
+}
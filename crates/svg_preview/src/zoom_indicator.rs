@@ -0,0 +1,54 @@
+use gpui::{Context, Entity, IntoElement, Render, Subscription, Window};
+use ui::prelude::*;
+use workspace::{ItemHandle, StatusItemView, Workspace};
+
+use crate::svg_preview_view::{SvgPreviewEvent, SvgPreviewView};
+
+/// Shows the zoom level and the size of the SVG of the active preview in the status bar.
+pub struct ZoomIndicator {
+    preview: Option<Entity<SvgPreviewView>>,
+    _subscription: Option<Subscription>,
+}
+
+impl ZoomIndicator {
+    pub fn new(_workspace: &Workspace) -> Self {
+        Self {
+            preview: None,
+            _subscription: None,
+        }
+    }
+}
+
+impl Render for ZoomIndicator {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div().children(self.preview.clone().and_then(|preview| {
+            preview.update(cx, |preview, cx| {
+                preview
+                    .render_zoom_status(cx)
+                    .map(IntoElement::into_any_element)
+            })
+        }))
+    }
+}
+
+impl StatusItemView for ZoomIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.preview = active_pane_item.and_then(|item| item.downcast::<SvgPreviewView>());
+        self._subscription = self.preview.as_ref().map(|preview| {
+            cx.subscribe(preview, |_, _, event: &SvgPreviewEvent, cx| {
+                if matches!(
+                    event,
+                    SvgPreviewEvent::ViewChanged | SvgPreviewEvent::ControlsChanged
+                ) {
+                    cx.notify();
+                }
+            })
+        });
+        cx.notify();
+    }
+}
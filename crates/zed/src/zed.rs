@@ -431,6 +431,8 @@ pub fn initialize_workspace(
             cx.new(|cx| toolchain_selector::ActiveToolchain::new(workspace, window, cx));
         let vim_mode_indicator = cx.new(|cx| vim::ModeIndicator::new(window, cx));
         let image_info = cx.new(|_cx| ImageInfo::new(workspace));
+        let svg_zoom_indicator =
+            cx.new(|_| svg_preview::zoom_indicator::ZoomIndicator::new(workspace));
 
         let lsp_button_menu_handle = PopoverMenuHandle::default();
         let lsp_button =
@@ -457,6 +459,7 @@ pub fn initialize_workspace(
             status_bar.add_right_item(vim_mode_indicator, window, cx);
             status_bar.add_right_item(cursor_position, window, cx);
             status_bar.add_right_item(image_info, window, cx);
+            status_bar.add_right_item(svg_zoom_indicator, window, cx);
         });
 
         let handle = cx.entity().downgrade();
@@ -1160,6 +1163,9 @@ fn initialize_pane(
             toolbar.add_item(acp_tools_item, window, cx);
             let syntax_tree_item = cx.new(|_| language_tools::SyntaxTreeToolbarItemView::new());
             toolbar.add_item(syntax_tree_item, window, cx);
+            let svg_preview_toolbar =
+                cx.new(|_| svg_preview::svg_preview_toolbar::SvgPreviewToolbar::new());
+            toolbar.add_item(svg_preview_toolbar, window, cx);
             let migration_banner = cx.new(|cx| MigrationBanner::new(workspace, cx));
             toolbar.add_item(migration_banner, window, cx);
             let project_diff_toolbar = cx.new(|cx| ProjectDiffToolbar::new(workspace, cx));
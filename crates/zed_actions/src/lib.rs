@@ -265,6 +265,35 @@ pub mod project_panel {
         ]
     );
 }
+
+pub mod svg_preview {
+    use std::path::PathBuf;
+
+    use gpui::Action;
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+
+    /// Exports every SVG in a directory and its subdirectories to PNGs at the scales configured
+    /// for the SVG preview. Prompts for the directory when none is given.
+    #[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+    #[action(namespace = svg)]
+    #[serde(deny_unknown_fields)]
+    pub struct ExportDirectory {
+        #[serde(default)]
+        pub directory: Option<PathBuf>,
+    }
+
+    /// Opens a gallery of thumbnails of the SVGs in a directory, which open in an editor when
+    /// clicked. Prompts for the directory when none is given.
+    #[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+    #[action(namespace = svg)]
+    #[serde(deny_unknown_fields)]
+    pub struct OpenGallery {
+        #[serde(default)]
+        pub directory: Option<PathBuf>,
+    }
+}
+
 pub mod feedback {
     use gpui::actions;
 